@@ -1,12 +1,27 @@
+use std::cell::RefCell;
+use std::collections::HashSet;
 use std::fmt::Display;
 use std::io;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
 use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
 
-use chrono::{Datelike, Duration, NaiveDate};
+use chrono::{Datelike, Duration, Local, Months, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use ratatui::layout::{Position, Rect};
+use ratatui::widgets::ListState;
+use serde::{Deserialize, Serialize};
 
-use crate::habit::{today_with_cutoff, Day, Habit, HabitType};
-use crate::storage::{self, NotificationSettings};
-use crate::notifications::NotificationData;
+use crate::date_input::parse_date;
+use crate::digest::{self, Delivery, Digest};
+use crate::export;
+use crate::palette::{self, Command};
+use crate::text_field::{Edit, TextField};
+use crate::habit::{today_with_cutoff, week_start, Habit, HabitType, Slip, TimeOfDay};
+use crate::storage::{self, ConfigWatcher, NotificationSettings};
+use crate::keymap::{Action, KeyMode, Keymap};
+use crate::theme::Theme;
+use crate::notifications::{NotificationData, NotificationRecord};
 
 #[derive(Debug)]
 
@@ -14,6 +29,7 @@ pub enum AppError {
     Io(io::Error),
     TomlSer(toml::ser::Error),
     TomlDe(toml::de::Error),
+    Config(String),
 }
 
 impl From<io::Error> for AppError {
@@ -40,12 +56,16 @@ impl Display for AppError {
             AppError::Io(err) => write!(f, "IO error,{}", err),
             AppError::TomlSer(err) => write!(f, "Toml serialization error,{}", err),
             AppError::TomlDe(err) => write!(f, "Toml deserialization error,{}", err),
+            AppError::Config(msg) => write!(f, "Invalid configuration in {}", msg),
         }
     }
 }
 
+#[derive(Clone, Copy, PartialEq)]
 pub enum CurrentScreen {
     Today,
+    Week,
+    Month,
     Stats,
     Heatmap,
 }
@@ -56,46 +76,322 @@ pub enum ScreenMode {
     Editing,
     Deleting,
     Reset,
+    /// A habit's holidays, to add, edit or delete.
+    Holidays,
     Holiday,
+    Slip,
     Help,
+    Inbox,
+    Digest,
+    DayDetail,
+    GoTo,
+    Archive,
+    Search,
+    Command,
 }
 
+/// Longest habit name, in characters.
+pub const MAX_NAME_LEN: usize = 60;
+const MAX_TAGS_LEN: usize = 120;
+/// Room for `next wednesday` as well as YYYY-MM-DD.
+const DATE_LEN: usize = 16;
+const MAX_REASON_LEN: usize = 60;
+
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum HolidayField {
+    #[default]
+    Start,
+    End,
+    Reason,
+}
+
+impl HolidayField {
+    fn next(self) -> Self {
+        match self {
+            HolidayField::Start => HolidayField::End,
+            HolidayField::End => HolidayField::Reason,
+            HolidayField::Reason => HolidayField::Start,
+        }
+    }
+}
+
+/// The holiday form. Either date takes anything `parse_date` reads, and
+/// the picker keys rewrite the focused one as an ISO date.
 pub struct HolidayInput {
-    pub start: String,
-    pub end: String,
-    pub focus_end: bool,
+    pub start: TextField,
+    /// Left empty for a one-day holiday.
+    pub end: TextField,
+    pub reason: TextField,
+    pub focus: HolidayField,
+    /// Index into the habit's holidays when changing an existing one.
+    pub editing: Option<usize>,
     pub error: Option<String>,
 }
 
 impl Default for HolidayInput {
     fn default() -> Self {
         HolidayInput {
-            start: String::new(),
-            end: String::new(),
-            focus_end: false,
+            start: TextField::new(DATE_LEN),
+            end: TextField::new(DATE_LEN),
+            reason: TextField::new(MAX_REASON_LEN),
+            focus: HolidayField::default(),
+            editing: None,
             error: None,
         }
     }
 }
 
-pub struct Counter {
-    pub build_counter: usize,
-    pub avoid_counter: usize,
-    pub year_counter: usize,
-    pub switch: bool,
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum HabitField {
+    #[default]
+    Name,
+    Tags,
+    Type,
+    TimeOfDay,
+}
+
+impl HabitField {
+    fn next(self) -> Self {
+        match self {
+            HabitField::Name => HabitField::Tags,
+            HabitField::Tags => HabitField::Type,
+            HabitField::Type => HabitField::TimeOfDay,
+            HabitField::TimeOfDay => HabitField::Name,
+        }
+    }
+}
+
+/// The add/edit form's text fields. Type and time of day are set on
+/// `current_habit` directly.
+pub struct HabitInput {
+    pub name: TextField,
+    /// Comma-separated, e.g. "health, morning".
+    pub tags: TextField,
+    pub focus: HabitField,
+    pub error: Option<String>,
+}
+
+impl Default for HabitInput {
+    fn default() -> Self {
+        HabitInput {
+            name: TextField::new(MAX_NAME_LEN),
+            tags: TextField::new(MAX_TAGS_LEN),
+            focus: HabitField::default(),
+            error: None,
+        }
+    }
+}
+
+/// The `/` search bar. The selection follows the current match as the
+/// query is typed.
+pub struct SearchInput {
+    pub query: TextField,
+    /// Rows of matching habits (build habits first), best match first.
+    pub matches: Vec<usize>,
+    pub current: usize,
+    /// Focus and selections to return to if the search is cancelled.
+    origin: (bool, usize, usize),
+}
+
+impl Default for SearchInput {
+    fn default() -> Self {
+        SearchInput {
+            query: TextField::new(MAX_NAME_LEN),
+            matches: Vec::new(),
+            current: 0,
+            origin: (false, 0, 0),
+        }
+    }
+}
+
+/// Longest palette command accepted.
+const MAX_COMMAND_LEN: usize = 80;
+
+/// The `:` command palette.
+pub struct CommandInput {
+    pub text: TextField,
+    pub error: Option<String>,
+}
+
+impl Default for CommandInput {
+    fn default() -> Self {
+        CommandInput {
+            text: TextField::new(MAX_COMMAND_LEN),
+            error: None,
+        }
+    }
+}
+
+pub struct GoToInput {
+    pub date: TextField,
+    pub error: Option<String>,
+}
+
+impl Default for GoToInput {
+    fn default() -> Self {
+        GoToInput {
+            date: TextField::new(DATE_LEN),
+            error: None,
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq)]
+pub enum SlipField {
+    #[default]
+    Count,
+    Time,
+    Trigger,
+}
+
+impl SlipField {
+    fn next(self) -> Self {
+        match self {
+            SlipField::Count => SlipField::Time,
+            SlipField::Time => SlipField::Trigger,
+            SlipField::Trigger => SlipField::Count,
+        }
+    }
 }
 
-impl Default for Counter {
+pub struct SlipInput {
+    pub count: TextField,
+    pub time: TextField,
+    pub trigger: TextField,
+    pub focus: SlipField,
+    pub error: Option<String>,
+}
+
+impl Default for SlipInput {
     fn default() -> Self {
-        Counter {
-            build_counter: 0,
-            avoid_counter: 0,
-            year_counter: 0,
-            switch: false,
+        SlipInput {
+            count: TextField::new(MAX_COUNT_LEN),
+            time: TextField::new(TIME_LEN),
+            trigger: TextField::new(MAX_TRIGGER_LEN),
+            focus: SlipField::default(),
+            error: None,
+        }
+    }
+}
+
+const MAX_COUNT_LEN: usize = 6;
+/// "HH:MM".
+const TIME_LEN: usize = 5;
+/// Longest trigger note accepted; it's meant to be a word or two.
+const MAX_TRIGGER_LEN: usize = 32;
+
+/// Heading for habits without a tag once a list is grouped.
+pub const UNTAGGED: &str = "Untagged";
+
+/// A line of a habit list grouped by category: a heading with the number
+/// of habits under it, or a habit by its index in the list.
+pub enum ListRow {
+    Group(String, usize),
+    Habit(usize),
+}
+
+/// Scroll positions of the lists that outgrow their panels. Unlike the
+/// hitboxes these carry over between draws, so a list only scrolls when
+/// the selection would leave it.
+#[derive(Default)]
+pub struct ListStates {
+    pub build: ListState,
+    pub avoid: ListState,
+    pub years: ListState,
+}
+
+/// Where clickable things were drawn in the last frame, so mouse events
+/// can be matched to them. Rebuilt on every draw.
+#[derive(Default)]
+pub struct Hitboxes {
+    pub tabs: Vec<(Rect, CurrentScreen)>,
+    /// Inner areas of the habit lists; row `n` is `App::list_rows()[n]`
+    /// counted from the list's scroll offset.
+    pub build_list: Rect,
+    pub avoid_list: Rect,
+    /// Inner area of the year list, likewise scrolled.
+    pub years: Rect,
+    /// Habit rows that fit on screen in the current list, for paging.
+    pub page_rows: usize,
+    pub heatmap_cells: Vec<(Rect, NaiveDate)>,
+    /// Week grid cells with their habit row (build habits first) and date.
+    pub week_cells: Vec<(Rect, usize, NaiveDate)>,
+    pub month_cells: Vec<(Rect, NaiveDate)>,
+    /// The day breakdown popup and its habit list; row `n` is
+    /// `App::detail_rows()[n]`.
+    pub popup: Rect,
+    pub detail_list: Rect,
+    /// The archive popup's list; row `n` is `App::archived_rows()[n]`.
+    pub archive_list: Rect,
+    /// The holiday manager's list; row `n` is the habit's `n`th holiday.
+    pub holiday_list: Rect,
+    /// Help lines cut off at the bottom, bounding how far it scrolls.
+    pub help_hidden: usize,
+    /// Only the "terminal too small" message fit, so nothing else is
+    /// on screen to act on.
+    pub too_small: bool,
+}
+
+/// Two clicks on the same cell within this window count as a double-click.
+pub const DOUBLE_CLICK: std::time::Duration = std::time::Duration::from_millis(400);
+
+/// Which habits the heatmap counts.
+#[derive(Default, Clone, PartialEq)]
+pub enum HeatmapFilter {
+    #[default]
+    All,
+    Build,
+    Avoid,
+    /// One habit, by name, which stays put when rows are moved or sorted.
+    Habit(String),
+}
+
+/// How habits are ordered within their group in the lists.
+#[derive(Serialize, Deserialize, Default, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SortMode {
+    /// The order habits were added in, or moved into.
+    #[default]
+    Manual,
+    Name,
+    /// Strongest tier first.
+    Tier,
+    /// Habits not yet done on the shown day first.
+    Pending,
+    /// Morning habits first; habits without a set time last.
+    TimeOfDay,
+}
+
+impl SortMode {
+    fn next(self) -> Self {
+        match self {
+            SortMode::Manual => SortMode::Name,
+            SortMode::Name => SortMode::Tier,
+            SortMode::Tier => SortMode::Pending,
+            SortMode::Pending => SortMode::TimeOfDay,
+            SortMode::TimeOfDay => SortMode::Manual,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            SortMode::Manual => "your order",
+            SortMode::Name => "name",
+            SortMode::Tier => "tier",
+            SortMode::Pending => "pending first",
+            SortMode::TimeOfDay => "time of day",
         }
     }
 }
 
+#[derive(Default)]
+pub struct Counter {
+    pub build_counter: usize,
+    pub avoid_counter: usize,
+    pub year_counter: usize,
+    pub switch: bool,
+}
+
 pub struct App {
     pub build_habits: Vec<Habit>,
     pub avoid_habits: Vec<Habit>,
@@ -103,11 +399,60 @@ pub struct App {
     pub counter: Counter,
     pub current_screen: CurrentScreen,
     pub screen_mode: ScreenMode,
+    pub heatmap_filter: HeatmapFilter,
+    /// Whether the heatmap shows the last 365 days rather than a calendar year.
+    pub heatmap_rolling: bool,
+    /// Only habits with this tag are shown, on every screen.
+    pub tag_filter: Option<String>,
+    /// Folded groups in the habit lists, by lowercased name.
+    pub collapsed: HashSet<String>,
+    pub sort_mode: SortMode,
     pub current_habit: Habit,
-    pub current_day: Day,
+    /// The day the Today, Week and Month views show and edit. Never later
+    /// than today.
+    pub current_date: NaiveDate,
+    /// The heatmap's selected day, kept apart from `current_date` so
+    /// browsing the year doesn't move the other views. Jumping to a date or
+    /// back to today moves both.
+    pub heatmap_cursor: NaiveDate,
+    /// The day the breakdown popup shows; check-ins and slips made from it
+    /// land there (see `acting_date`).
+    pub detail_date: NaiveDate,
+    /// The wall clock to the minute as of the last tick, shown in the title.
+    pub clock: NaiveDateTime,
+    /// What `today()` was at the last tick, to notice the day turning over.
+    seen_today: NaiveDate,
     pub notif: Arc<Mutex<NotificationData>>,
     pub holiday_input: HolidayInput,
+    pub habit_input: HabitInput,
+    pub slip_input: SlipInput,
+    pub goto_input: GoToInput,
+    pub search_input: SearchInput,
+    pub command_input: CommandInput,
+    /// One-off result of a command, shown until the next key.
+    pub status: Option<String>,
     pub day_cutoff_hour: u32,
+    pub notification_log: Vec<NotificationRecord>,
+    pub digest: Option<Digest>,
+    /// Digests being delivered in the background since launch.
+    digest_delivery: Option<Receiver<Delivery>>,
+    pub config_error: Option<String>,
+    config_watcher: ConfigWatcher,
+    pub theme: Theme,
+    pub theme_error: Option<String>,
+    pub keymap: Keymap,
+    pub keymap_error: Option<String>,
+    pub hitboxes: RefCell<Hitboxes>,
+    pub list_states: RefCell<ListStates>,
+    /// Time and cell of the last left click, for double-click detection.
+    pub last_click: Option<(Instant, Position)>,
+    pub help_scroll: usize,
+    /// Selected line of the archive popup.
+    pub archive_selected: usize,
+    /// Selected row in the holiday manager.
+    pub holiday_selected: usize,
+    /// The slip form was opened from the day breakdown and returns to it.
+    slip_from_detail: bool,
 }
 
 impl App {
@@ -118,224 +463,1442 @@ impl App {
             years: Vec::new(),
             counter: Counter::default(),
             current_screen: CurrentScreen::Today,
+            heatmap_filter: HeatmapFilter::default(),
+            heatmap_rolling: false,
+            tag_filter: None,
+            collapsed: HashSet::new(),
+            sort_mode: SortMode::default(),
             screen_mode: ScreenMode::Normal,
-            current_day: Day::Today,
+            current_date: today_with_cutoff(0),
+            heatmap_cursor: today_with_cutoff(0),
+            detail_date: today_with_cutoff(0),
+            clock: current_minute(),
+            seen_today: today_with_cutoff(0),
             current_habit: Habit::default(),
             notif: Arc::new(Mutex::new(NotificationData::default())),
             holiday_input: HolidayInput::default(),
+            habit_input: HabitInput::default(),
+            slip_input: SlipInput::default(),
+            goto_input: GoToInput::default(),
+            search_input: SearchInput::default(),
+            command_input: CommandInput::default(),
+            status: None,
             day_cutoff_hour: 0,
+            notification_log: Vec::new(),
+            digest: None,
+            digest_delivery: None,
+            config_error: None,
+            config_watcher: ConfigWatcher::new(),
+            theme: Theme::default(),
+            theme_error: None,
+            keymap: Keymap::default(),
+            keymap_error: None,
+            hitboxes: RefCell::new(Hitboxes::default()),
+            list_states: RefCell::new(ListStates::default()),
+            last_click: None,
+            help_scroll: 0,
+            archive_selected: 0,
+            holiday_selected: 0,
+            slip_from_detail: false,
+        }
+    }
+
+    pub fn set_notifications(&mut self, settings: NotificationSettings) {
+        // A new cutoff can move "today"; stay on it if that's where we were.
+        let on_today = self.current_date == self.today();
+        self.day_cutoff_hour = settings.day_cutoff_hour.min(23);
+        if on_today || self.current_date > self.today() {
+            self.current_date = self.today();
+        }
+        self.heatmap_cursor = self.heatmap_cursor.min(self.today());
+        self.notif.lock().unwrap().apply(&settings);
+    }
+
+    /// Re-reads `notification.toml` if it changed on disk. An invalid edit
+    /// keeps the current settings and surfaces the problem in the footer.
+    /// Returns whether it was re-read.
+    pub fn reload_config_if_changed(&mut self) -> bool {
+        if !self.config_watcher.changed() {
+            return false;
+        }
+        match storage::load_notification_settings() {
+            Ok(settings) => {
+                self.config_error = None;
+                self.set_notifications(settings);
+            }
+            Err(e) => self.config_error = Some(e.to_string()),
+        }
+        true
+    }
+
+    /// Keeps up with the clock while no keys come in: moves the clock on a
+    /// minute, and when the day turns over (at midnight or the cutoff hour)
+    /// moves the Today view along if it was showing today. A day picked on
+    /// purpose stays put. Returns whether anything shown changed.
+    pub fn tick(&mut self) -> bool {
+        let delivered = self.finish_digest_delivery();
+        let minute = current_minute();
+        if minute == self.clock {
+            return delivered;
+        }
+        self.clock = minute;
+        let today = self.today();
+        if today != self.seen_today {
+            if self.current_date == self.seen_today {
+                self.current_date = today;
+            }
+            if self.heatmap_cursor == self.seen_today {
+                self.heatmap_cursor = today;
+                self.sync_year_counter();
+            }
+            self.seen_today = today;
+        }
+        true
+    }
+
+    pub fn load_habits(&mut self) -> Result<(), AppError> {
+        let data = storage::load_habits_data()?;
+        self.build_habits = data.build_habits;
+        self.avoid_habits = data.avoid_habits;
+        self.sort_mode = data.sort;
+        self.years = self.get_heatmap_years();
+        self.ensure_selection_shown();
+        Ok(())
+    }
+
+    pub fn save_habits(&self) -> Result<(), AppError> {
+        storage::save_habits(&self.build_habits, &self.avoid_habits, self.sort_mode)
+    }
+
+    pub fn toggle_page(&mut self) {
+        self.show_screen(match self.current_screen {
+            CurrentScreen::Today => CurrentScreen::Week,
+            CurrentScreen::Week => CurrentScreen::Month,
+            CurrentScreen::Month => CurrentScreen::Stats,
+            CurrentScreen::Stats => CurrentScreen::Heatmap,
+            CurrentScreen::Heatmap => CurrentScreen::Today,
+        });
+    }
+
+    pub fn show_screen(&mut self, screen: CurrentScreen) {
+        self.current_screen = screen;
+        if screen == CurrentScreen::Heatmap {
+            self.years = self.get_heatmap_years();
+            self.sync_year_counter();
+        }
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        today_with_cutoff(self.day_cutoff_hour)
+    }
+
+    /// Moves the current date by `days`, refusing to step past today.
+    pub fn step_day(&mut self, days: i64) {
+        let date = self.current_date + Duration::days(days);
+        if date <= self.today() {
+            self.current_date = date;
+        }
+    }
+
+    /// Moves a week at a time; stepping past today lands on today.
+    pub fn step_week(&mut self, weeks: i64) {
+        self.current_date = (self.current_date + Duration::weeks(weeks)).min(self.today());
+    }
+
+    /// Moves a month at a time, keeping the day where the month allows.
+    pub fn step_month(&mut self, months: i32) {
+        let date = if months < 0 {
+            self.current_date.checked_sub_months(Months::new(months.unsigned_abs()))
+        } else {
+            self.current_date.checked_add_months(Months::new(months as u32))
+        };
+        if let Some(date) = date {
+            self.current_date = date.min(self.today());
+        }
+    }
+
+    /// Monday to Sunday of the week containing the current date.
+    pub fn week_dates(&self) -> [NaiveDate; 7] {
+        let start = week_start(self.current_date);
+        std::array::from_fn(|i| start + Duration::days(i as i64))
+    }
+
+    pub fn go_to_today(&mut self) {
+        self.jump_to(self.today());
+    }
+
+    /// Takes every view, the heatmap included, to `date`.
+    fn jump_to(&mut self, date: NaiveDate) {
+        self.current_date = date;
+        self.heatmap_cursor = date;
+        self.sync_year_counter();
+    }
+
+    /// The day check-ins and slips apply to: the breakdown's day while it
+    /// (or a slip form opened from it) is up, otherwise `current_date`.
+    pub fn acting_date(&self) -> NaiveDate {
+        if matches!(self.screen_mode, ScreenMode::DayDetail) || self.slip_from_detail {
+            self.detail_date
+        } else {
+            self.current_date
+        }
+    }
+
+    /// "Sat, Oct 18", with "Today" or "Yesterday" added when it applies.
+    pub fn current_date_label(&self) -> String {
+        let date = self.current_date.format("%a, %b %-d");
+        match (self.today() - self.current_date).num_days() {
+            0 => format!("{date} · Today"),
+            1 => format!("{date} · Yesterday"),
+            _ if self.current_date.year() != self.today().year() => {
+                self.current_date.format("%a, %b %-d %Y").to_string()
+            }
+            _ => date.to_string(),
+        }
+    }
+
+    pub fn toggle_goto_mode(&mut self) {
+        if let ScreenMode::Normal = self.screen_mode {
+            self.screen_mode = ScreenMode::GoTo;
+            self.goto_input = GoToInput::default();
+            self.search_input = SearchInput::default();
+            self.command_input = CommandInput::default();
+        }
+    }
+
+    pub fn toggle_search_mode(&mut self) {
+        if let ScreenMode::Normal = self.screen_mode {
+            self.screen_mode = ScreenMode::Search;
+            self.search_input = SearchInput {
+                origin: (
+                    self.counter.switch,
+                    self.counter.build_counter,
+                    self.counter.avoid_counter,
+                ),
+                ..SearchInput::default()
+            };
+        }
+    }
+
+    /// Ranks the shown habits of both lists against the query and jumps to
+    /// the best match.
+    fn update_search(&mut self) {
+        let habits: Vec<&Habit> = self.all_habits().collect();
+        let query = self.search_input.query.text();
+        let mut scored: Vec<(i32, usize)> = self
+            .row_order(false)
+            .into_iter()
+            .filter_map(|row| palette::fuzzy_score(query, &habits[row].name).map(|s| (s, row)))
+            .collect();
+        scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+        self.search_input.matches = scored.into_iter().map(|(_, row)| row).collect();
+        self.search_input.current = 0;
+        if query.trim().is_empty() {
+            let (switch, build, avoid) = self.search_input.origin;
+            self.counter.switch = switch;
+            self.counter.build_counter = build;
+            self.counter.avoid_counter = avoid;
+        } else {
+            self.select_search_match();
+        }
+    }
+
+    pub fn next_search_match(&mut self) {
+        let count = self.search_input.matches.len();
+        if count > 0 {
+            self.search_input.current = (self.search_input.current + 1) % count;
+            self.select_search_match();
+        }
+    }
+
+    /// Selects the current match, unfolding its group if need be.
+    fn select_search_match(&mut self) {
+        let Some(row) = self.search_input.matches.get(self.search_input.current).copied() else {
+            return;
+        };
+        self.select_row(row);
+        let group = self.get_selected_habit().category().unwrap_or(UNTAGGED).to_lowercase();
+        self.collapsed.remove(&group);
+    }
+
+    /// Keeps the match selected and shows it in a list.
+    pub fn confirm_search(&mut self) {
+        self.toggle_normal_mode();
+        if !matches!(self.current_screen, CurrentScreen::Today | CurrentScreen::Week) {
+            self.show_screen(CurrentScreen::Today);
+        }
+    }
+
+    pub fn cancel_search(&mut self) {
+        let (switch, build, avoid) = self.search_input.origin;
+        self.counter.switch = switch;
+        self.counter.build_counter = build;
+        self.counter.avoid_counter = avoid;
+        self.toggle_normal_mode();
+    }
+
+    pub fn toggle_command_mode(&mut self) {
+        if let ScreenMode::Normal = self.screen_mode {
+            self.screen_mode = ScreenMode::Command;
+            self.command_input = CommandInput::default();
+        }
+    }
+
+    /// Completes the command name to the first suggestion.
+    pub fn complete_command(&mut self) {
+        let text = self.command_input.text.text();
+        if text.trim_start().contains(' ') {
+            return;
+        }
+        if let Some((name, _, _)) = palette::suggestions(text).first() {
+            self.command_input.text = TextField::with_text(&format!("{name} "), MAX_COMMAND_LEN);
+        }
+    }
+
+    /// Runs the palette's command. On an error the palette stays open with
+    /// it shown; otherwise it closes, unless the command opened a popup.
+    pub fn run_command(&mut self) {
+        let result = palette::parse(self.command_input.text.text(), self.today()).and_then(|command| {
+            self.screen_mode = ScreenMode::Normal;
+            self.apply_command(command)
+        });
+        match result {
+            Ok(()) => {
+                if let ScreenMode::Normal = self.screen_mode {
+                    self.command_input = CommandInput::default();
+                }
+            }
+            Err(error) => {
+                self.screen_mode = ScreenMode::Command;
+                self.command_input.error = Some(error);
+            }
+        }
+    }
+
+    fn apply_command(&mut self, command: Command) -> Result<(), String> {
+        let needs_habit = matches!(command, Command::Holiday(..) | Command::Pause | Command::Archive);
+        if needs_habit && !self.selection_shown() {
+            return Err("Select a habit first".to_string());
+        }
+        match command {
+            Command::GoTo(date) if date > self.today() => {
+                return Err("Can't go past today".to_string());
+            }
+            Command::GoTo(date) => self.jump_to(date),
+            Command::Today => self.go_to_today(),
+            Command::Holiday(start, end) => self.selected_habit_mut().add_holiday(start, end, None),
+            Command::Pause => self.toggle_pause_current(),
+            Command::Archive => self.archive_current_habit(),
+            Command::ShowArchive => self.toggle_archive_mode(),
+            Command::Sort(mode) => self.sort_mode = mode,
+            Command::Tag(None) => {
+                self.tag_filter = None;
+                self.ensure_selection_shown();
+            }
+            Command::Tag(Some(tag)) => {
+                let Some(tag) = self.tags().into_iter().find(|t| t.eq_ignore_ascii_case(&tag)) else {
+                    return Err(format!("No habits tagged #{tag}"));
+                };
+                self.tag_filter = Some(tag);
+                self.ensure_selection_shown();
+            }
+            Command::ExportCsv => {
+                let habits: Vec<Habit> = self.all_habits().cloned().collect();
+                let today = self.today();
+                let csv = export::habits_csv(&habits, today);
+                let path = storage::save_export(&format!("habits-{today}.csv"), &csv)
+                    .map_err(|e| e.to_string())?;
+                self.status = Some(format!("Exported to {}", path.display()));
+            }
+            Command::Help => self.toggle_help_mode(),
+        }
+        Ok(())
+    }
+
+    pub fn go_to_date(&mut self) {
+        match parse_date(self.goto_input.date.text(), self.today()) {
+            Some(date) if date > self.today() => {
+                self.goto_input.error = Some("Can't go past today".to_string());
+            }
+            Some(date) => {
+                self.jump_to(date);
+                self.toggle_normal_mode();
+            }
+            None => self.goto_input.error = Some("Use YYYY-MM-DD, or e.g. -7d".to_string()),
+        }
+    }
+
+    pub fn toggle_habit_type(&mut self) {
+        self.current_habit.habit_type = match self.current_habit.habit_type {
+            HabitType::Build => HabitType::Avoid,
+            HabitType::Avoid => HabitType::Build,
+        };
+    }
+
+    pub fn toggle_habit_focus(&mut self) {
+        self.habit_input.focus = self.habit_input.focus.next();
+    }
+
+    /// Space switches the type or time of day when one is focused.
+    pub fn push_habit_char(&mut self, value: char) {
+        match self.habit_input.focus {
+            HabitField::Type if value == ' ' => self.toggle_habit_type(),
+            HabitField::TimeOfDay if value == ' ' => self.cycle_habit_time(),
+            _ => {}
+        }
+    }
+
+    /// The text field keys go to, if the open form has one focused.
+    pub fn focused_field(&mut self) -> Option<&mut TextField> {
+        match self.screen_mode {
+            ScreenMode::Adding | ScreenMode::Editing => match self.habit_input.focus {
+                HabitField::Name => Some(&mut self.habit_input.name),
+                HabitField::Tags => Some(&mut self.habit_input.tags),
+                HabitField::Type | HabitField::TimeOfDay => None,
+            },
+            ScreenMode::Holiday => Some(match self.holiday_input.focus {
+                HolidayField::Start => &mut self.holiday_input.start,
+                HolidayField::End => &mut self.holiday_input.end,
+                HolidayField::Reason => &mut self.holiday_input.reason,
+            }),
+            ScreenMode::Slip => Some(match self.slip_input.focus {
+                SlipField::Count => &mut self.slip_input.count,
+                SlipField::Time => &mut self.slip_input.time,
+                SlipField::Trigger => &mut self.slip_input.trigger,
+            }),
+            ScreenMode::GoTo => Some(&mut self.goto_input.date),
+            ScreenMode::Search => Some(&mut self.search_input.query),
+            ScreenMode::Command => Some(&mut self.command_input.text),
+            _ => None,
+        }
+    }
+
+    /// Clears a form's error once its text changes, or says why the text
+    /// was cut short.
+    pub fn field_edited(&mut self, edit: Edit) {
+        let error = match edit {
+            Edit::Ignored | Edit::Moved => return,
+            Edit::Changed => None,
+            Edit::TooLong => {
+                let max = self.focused_field().map_or(0, |field| field.max_len());
+                Some(format!("At most {max} characters fit here"))
+            }
+        };
+        match self.screen_mode {
+            ScreenMode::Holiday => self.holiday_input.error = error,
+            ScreenMode::Slip => self.slip_input.error = error,
+            ScreenMode::GoTo => self.goto_input.error = error,
+            ScreenMode::Command => self.command_input.error = error,
+            // The match count stands in for an error.
+            ScreenMode::Search => self.update_search(),
+            _ => self.habit_input.error = error,
+        }
+    }
+
+    /// The form's name, trimmed, unless it's empty, too long, or already
+    /// taken by a habit other than the `editing` row.
+    fn validate_habit_name(&self, editing: Option<usize>) -> Result<String, String> {
+        let name = self.habit_input.name.text().trim();
+        if name.is_empty() {
+            return Err("Give the habit a name".to_string());
+        }
+        if name.chars().count() > MAX_NAME_LEN {
+            return Err(format!("Names are at most {MAX_NAME_LEN} characters"));
+        }
+        let taken = self
+            .all_habits()
+            .enumerate()
+            .any(|(row, h)| Some(row) != editing && h.name.trim().eq_ignore_ascii_case(name));
+        if taken {
+            return Err(format!("There's already a habit called \"{name}\""));
+        }
+        Ok(name.to_string())
+    }
+
+    /// Steps through the times of day, then back to none.
+    fn cycle_habit_time(&mut self) {
+        let next = match self.current_habit.time_of_day {
+            None => Some(0),
+            Some(time) => Some(time as usize + 1).filter(|i| *i < TimeOfDay::ALL.len()),
+        };
+        self.current_habit.time_of_day = next.map(|i| TimeOfDay::ALL[i]);
+    }
+
+    pub fn toggle_add_mode(&mut self) {
+        if let ScreenMode::Normal = self.screen_mode {
+            self.screen_mode = ScreenMode::Adding;
+            // New habits land in the group being looked at.
+            let tags = self.tag_filter.clone().unwrap_or_default();
+            self.habit_input = HabitInput {
+                tags: TextField::with_text(&tags, MAX_TAGS_LEN),
+                ..HabitInput::default()
+            };
+        }
+    }
+
+    pub fn toggle_edit_mode(&mut self, habit: Habit) {
+        if let ScreenMode::Normal = self.screen_mode {
+            self.screen_mode = ScreenMode::Editing;
+            self.habit_input = HabitInput {
+                name: TextField::with_text(&habit.name, MAX_NAME_LEN),
+                tags: TextField::with_text(&habit.tags.join(", "), MAX_TAGS_LEN),
+                ..HabitInput::default()
+            };
+            self.current_habit = habit;
+        }
+    }
+
+    pub fn toggle_help_mode(&mut self) {
+        match self.screen_mode {
+            ScreenMode::Normal => {
+                self.help_scroll = 0;
+                self.screen_mode = ScreenMode::Help;
+            }
+            ScreenMode::Help => self.screen_mode = ScreenMode::Normal,
+            _ => {}
+        }
+    }
+
+    pub fn scroll_help(&mut self, down: bool) {
+        if down {
+            let hidden = self.hitboxes.borrow().help_hidden;
+            self.help_scroll = (self.help_scroll + 1).min(hidden);
+        } else {
+            self.help_scroll = self.help_scroll.saturating_sub(1);
+        }
+    }
+
+    pub fn toggle_inbox_mode(&mut self) {
+        match self.screen_mode {
+            ScreenMode::Normal => {
+                self.notification_log = storage::load_notification_log().unwrap_or_default();
+                self.screen_mode = ScreenMode::Inbox;
+            }
+            ScreenMode::Inbox => self.screen_mode = ScreenMode::Normal,
+            _ => {}
+        }
+    }
+
+    /// The first config file that failed to load and its first problem, on
+    /// one line, e.g. "notification.toml: hour = 25 is out of range".
+    pub fn config_problem(&self) -> Option<String> {
+        let (file, error) = [
+            ("notification.toml", &self.config_error),
+            ("theme.toml", &self.theme_error),
+            ("keymap.toml", &self.keymap_error),
+        ]
+        .into_iter()
+        .find_map(|(file, error)| error.as_deref().map(|e| (file, e)))?;
+        // Errors lead with the file's full path; the problem comes after.
+        let detail = error.split_once(".toml:").map_or(error, |(_, rest)| rest);
+        let line = detail.lines().map(str::trim).find(|l| !l.is_empty()).unwrap_or("invalid");
+        Some(format!("{file}: {line}"))
+    }
+
+    /// Keys bound to `action` in `mode`, as shown in hints.
+    pub fn keys_for(&self, mode: KeyMode, action: Action) -> String {
+        self.keymap.keys(mode, action, self.theme.glyphs)
+    }
+
+    /// Opens the day breakdown, which lists and edits `date` like the
+    /// Today view does for the current date.
+    pub fn show_day_detail(&mut self, date: NaiveDate) {
+        if let ScreenMode::Normal = self.screen_mode {
+            if date <= self.today() {
+                self.detail_date = date;
+                self.screen_mode = ScreenMode::DayDetail;
+            }
+        }
+    }
+
+    /// Sends any digest that came due while the app was closed. Sinks can
+    /// take seconds each, so this runs on its own thread and `tick` picks up
+    /// the result.
+    pub fn deliver_digests(&mut self, settings: &NotificationSettings) {
+        let (tx, rx) = mpsc::channel();
+        let (digest, sinks) = (settings.digest.clone(), settings.sinks.clone());
+        let habits: Vec<_> = self.all_habits().cloned().collect();
+        let today = self.today();
+        thread::spawn(move || {
+            let _ = tx.send(digest::deliver_due(&digest, &sinks, &habits, today, true));
+        });
+        self.digest_delivery = Some(rx);
+    }
+
+    /// Shows the outcome of `deliver_digests` once it is in, waiting for
+    /// any open popup to close first, and any failure of a digest the
+    /// scheduler sent since.
+    fn finish_digest_delivery(&mut self) -> bool {
+        if !matches!(self.screen_mode, ScreenMode::Normal) {
+            return false;
+        }
+        let mut delivery = match self.digest_delivery.as_ref().map(Receiver::try_recv) {
+            Some(Ok(delivery)) => {
+                self.digest_delivery = None;
+                delivery
+            }
+            Some(Err(TryRecvError::Disconnected)) => {
+                self.digest_delivery = None;
+                Delivery::default()
+            }
+            Some(Err(TryRecvError::Empty)) | None => Delivery::default(),
+        };
+        delivery.errors.append(&mut self.notif.lock().unwrap().digest_errors);
+        if delivery.shown.is_none() && delivery.errors.is_empty() {
+            return false;
+        }
+        if !delivery.errors.is_empty() {
+            self.status = Some(format!("Digest not delivered: {}", delivery.errors.join("; ")));
+        }
+        if let Some(digest) = delivery.shown {
+            self.show_digest(digest);
+        }
+        true
+    }
+
+    pub fn show_digest(&mut self, digest: Digest) {
+        if let ScreenMode::Normal = self.screen_mode {
+            self.digest = Some(digest);
+            self.screen_mode = ScreenMode::Digest;
+        }
+    }
+
+    /// Opens the holiday manager for `habit`, the selected one.
+    pub fn toggle_holidays_mode(&mut self, habit: Habit) {
+        if let ScreenMode::Normal = self.screen_mode {
+            self.screen_mode = ScreenMode::Holidays;
+            self.current_habit = habit;
+            self.holiday_selected = 0;
+        }
+    }
+
+    pub fn step_holiday_selection(&mut self, forward: bool) {
+        let last = self.current_habit.holidays.len().saturating_sub(1);
+        self.holiday_selected = if forward {
+            (self.holiday_selected + 1).min(last)
+        } else {
+            self.holiday_selected.saturating_sub(1)
+        };
+    }
+
+    /// Opens the holiday form over the manager: empty from today for a
+    /// new holiday, or filled in from the selected one to change it.
+    pub fn open_holiday_form(&mut self, edit: bool) {
+        let existing = self.current_habit.holidays.get(self.holiday_selected).cloned();
+        self.holiday_input = match existing {
+            Some(holiday) if edit => {
+                // Reasons merged from overlapping holidays can run past the
+                // limit; editing one mustn't cut it short.
+                let reason = holiday.reason.as_deref().unwrap_or_default();
+                HolidayInput {
+                    start: TextField::with_text(&holiday.start.to_string(), DATE_LEN),
+                    end: TextField::with_text(&holiday.end.to_string(), DATE_LEN),
+                    reason: TextField::with_text(
+                        reason,
+                        MAX_REASON_LEN.max(reason.chars().count()),
+                    ),
+                    editing: Some(self.holiday_selected),
+                    ..HolidayInput::default()
+                }
+            }
+            _ if edit => return,
+            _ => HolidayInput {
+                start: TextField::with_text(&self.today().to_string(), DATE_LEN),
+                ..HolidayInput::default()
+            },
+        };
+        self.screen_mode = ScreenMode::Holiday;
+    }
+
+    /// Back from the holiday form to the manager.
+    pub fn close_holiday_form(&mut self) {
+        self.holiday_input = HolidayInput::default();
+        self.screen_mode = ScreenMode::Holidays;
+    }
+
+    pub fn toggle_holiday_focus(&mut self) {
+        self.holiday_input.focus = self.holiday_input.focus.next();
+    }
+
+    /// The holiday form's start and end as far as they read as dates. An
+    /// empty end is the start day.
+    pub fn holiday_dates(&self) -> (Option<NaiveDate>, Option<NaiveDate>) {
+        let today = self.today();
+        let start = parse_date(self.holiday_input.start.text(), today);
+        let end = if self.holiday_input.end.text().trim().is_empty() {
+            start
+        } else {
+            parse_date(self.holiday_input.end.text(), today)
+        };
+        (start, end)
+    }
+
+    /// The date the picker keys move: the focused field's, else the start
+    /// for an unset end, else today.
+    pub fn picked_date(&self) -> NaiveDate {
+        let (start, end) = self.holiday_dates();
+        let picked = match self.holiday_input.focus {
+            HolidayField::End => end.or(start),
+            HolidayField::Start | HolidayField::Reason => start,
+        };
+        picked.unwrap_or_else(|| self.today())
+    }
+
+    /// Moves the focused holiday date by `days`, or by whole months.
+    pub fn move_picked_date(&mut self, days: i64, months: i32) {
+        let date = self.picked_date() + Duration::days(days);
+        let date = if months < 0 {
+            date.checked_sub_months(Months::new(months.unsigned_abs()))
+        } else {
+            date.checked_add_months(Months::new(months as u32))
+        }
+        .unwrap_or(date);
+        let field = TextField::with_text(&date.to_string(), DATE_LEN);
+        match self.holiday_input.focus {
+            HolidayField::Start => self.holiday_input.start = field,
+            HolidayField::End => self.holiday_input.end = field,
+            HolidayField::Reason => return,
+        }
+        self.holiday_input.error = None;
+    }
+
+    /// Saves the holiday form, replacing the holiday being edited, and
+    /// goes back to the manager with the saved range selected. Ranges it
+    /// overlaps are merged into it.
+    pub fn save_holiday(&mut self) {
+        match self.holiday_dates() {
+            (Some(start), Some(end)) if end < start => {
+                self.holiday_input.error = Some("The holiday ends before it starts".to_string());
+            }
+            (Some(start), Some(end)) => {
+                let reason = Some(self.holiday_input.reason.text().trim())
+                    .filter(|r| !r.is_empty())
+                    .map(str::to_string);
+                let editing = self.holiday_input.editing;
+                let habit = self.selected_habit_mut();
+                if let Some(index) = editing.filter(|i| *i < habit.holidays.len()) {
+                    habit.holidays.remove(index);
+                }
+                habit.add_holiday(start, end, reason);
+                self.current_habit = habit.clone();
+                self.holiday_selected = self
+                    .current_habit
+                    .holidays
+                    .iter()
+                    .position(|h| h.overlaps(start, start))
+                    .unwrap_or(0);
+                self.close_holiday_form();
+            }
+            _ => {
+                self.holiday_input.error =
+                    Some("Try a date like 2026-12-20, +7d or next friday".to_string());
+            }
+        }
+    }
+
+    pub fn delete_selected_holiday(&mut self) {
+        let index = self.holiday_selected;
+        let habit = self.selected_habit_mut();
+        if index < habit.holidays.len() {
+            habit.holidays.remove(index);
+        }
+        self.current_habit = habit.clone();
+        self.holiday_selected = index.min(self.current_habit.holidays.len().saturating_sub(1));
+    }
+
+    pub fn toggle_slip_mode(&mut self, habit: Habit) {
+        if let ScreenMode::Normal | ScreenMode::DayDetail = self.screen_mode {
+            self.slip_from_detail = matches!(self.screen_mode, ScreenMode::DayDetail);
+            self.screen_mode = ScreenMode::Slip;
+            self.current_habit = habit;
+            let date = self.acting_date();
+            // Logging as it happens is the common case, so default the time
+            // to now; backfilled days start blank.
+            let time = if date == self.today() {
+                Local::now().format("%H:%M").to_string()
+            } else {
+                String::new()
+            };
+            self.slip_input = SlipInput {
+                count: TextField::with_text("1", MAX_COUNT_LEN),
+                time: TextField::with_text(&time, TIME_LEN),
+                ..SlipInput::default()
+            };
+        }
+    }
+
+    pub fn toggle_slip_focus(&mut self) {
+        self.slip_input.focus = self.slip_input.focus.next();
+    }
+
+    pub fn log_slip(&mut self) {
+        let input = &self.slip_input;
+        let count = match input.count.text().trim() {
+            "" => Ok(1),
+            n => n.parse::<u32>().ok().filter(|n| *n > 0).ok_or("Count must be a whole number above 0"),
+        };
+        let time = match input.time.text().trim() {
+            "" => Ok(None),
+            t => NaiveTime::parse_from_str(t, "%H:%M").map(Some).map_err(|_| "Use HH:MM for the time"),
+        };
+        match (count, time) {
+            (Ok(count), Ok(time)) => {
+                let trigger = Some(input.trigger.text().trim().to_string()).filter(|t| !t.is_empty());
+                let date = self.acting_date();
+                self.avoid_habits[self.counter.avoid_counter].log_slip(Slip {
+                    date,
+                    time,
+                    count,
+                    trigger,
+                });
+                self.note_acted_on(date);
+                self.close_slip_form();
+            }
+            (Err(e), _) | (_, Err(e)) => self.slip_input.error = Some(e.to_string()),
+        }
+    }
+
+    pub fn close_slip_form(&mut self) {
+        self.toggle_normal_mode();
+        if self.slip_from_detail {
+            self.slip_from_detail = false;
+            self.screen_mode = ScreenMode::DayDetail;
+        }
+    }
+
+    pub fn undo_slip(&mut self) {
+        if self.counter.switch && !self.avoid_habits.is_empty() {
+            let date = self.acting_date();
+            self.avoid_habits[self.counter.avoid_counter].undo_slip(date);
+        }
+    }
+
+    pub fn toggle_delete_mode(&mut self) {
+        if let ScreenMode::Normal = self.screen_mode {
+            self.screen_mode = ScreenMode::Deleting;
+        }
+    }
+
+    pub fn toggle_reset_mode(&mut self) {
+        if let ScreenMode::Normal = self.screen_mode {
+            self.screen_mode = ScreenMode::Reset;
+        }
+    }
+
+    pub fn toggle_normal_mode(&mut self) {
+        if !matches!(self.screen_mode, ScreenMode::Normal) {
+            self.screen_mode = ScreenMode::Normal;
+            self.current_habit = Habit::default();
+            self.holiday_input = HolidayInput::default();
+            self.habit_input = HabitInput::default();
+            self.slip_input = SlipInput::default();
+            self.goto_input = GoToInput::default();
+            // The breakdown can select an archived habit the lists don't show.
+            self.ensure_selection_shown();
+        }
+    }
+
+    pub fn toggle_build_habits(&mut self) {
+        if self.counter.switch {
+            self.counter.switch = !self.counter.switch;
         }
     }
 
-    pub fn set_notifications(&mut self, settings: NotificationSettings) {
-        self.day_cutoff_hour = settings.day_cutoff_hour.min(23);
-        let mut notif = self.notif.lock().unwrap();
-        (*notif).low_threshold = settings.low_threshold;
-        (*notif).high_threshold = settings.high_threshold;
+    pub fn toggle_avoid_habit(&mut self) {
+        if !self.counter.switch {
+            self.counter.switch = !self.counter.switch;
+        }
     }
 
-    pub fn load_habits(&mut self) -> Result<(), AppError> {
-        let (build, avoid) = storage::load_habits()?;
-        self.build_habits = build;
-        self.avoid_habits = avoid;
-        self.years = self.get_heatmap_years();
-        Ok(())
+    pub fn increment_habits_counter(&mut self) {
+        let order = self.side_order();
+        self.step_selection(&order, true);
     }
 
-    pub fn save_habits(&self) -> Result<(), AppError> {
-        storage::save_habits(&self.build_habits, &self.avoid_habits)
+    pub fn decrement_habits_counter(&mut self) {
+        let order = self.side_order();
+        self.step_selection(&order, false);
     }
 
-    pub fn toggle_page(&mut self) {
-        self.current_screen = match self.current_screen {
-            CurrentScreen::Today => CurrentScreen::Stats,
-            CurrentScreen::Stats => CurrentScreen::Heatmap,
-            CurrentScreen::Heatmap => CurrentScreen::Today,
-        };
+    /// Rows of the focused Today list, in the order they're shown.
+    fn side_order(&self) -> Vec<usize> {
+        let build = self.build_habits.len();
+        self.row_order(true)
+            .into_iter()
+            .filter(|row| (*row >= build) == self.counter.switch)
+            .collect()
     }
 
-    pub fn toggle_day(&mut self) {
-        self.current_day = match self.current_day {
-            Day::Today => Day::Yesterday,
-            Day::Yesterday => Day::Today,
+    /// Moves the selection one step along `order`, or onto its first row
+    /// if the selected habit isn't in it.
+    fn step_selection(&mut self, order: &[usize], forward: bool) {
+        let next = match order.iter().position(|row| *row == self.selected_row()) {
+            Some(pos) if forward => order.get(pos + 1),
+            Some(pos) => pos.checked_sub(1).and_then(|p| order.get(p)),
+            None => order.first(),
         };
+        if let Some(row) = next.copied() {
+            self.select_row(row);
+        }
     }
 
-    pub fn toggle_habit_type(&mut self) {
-        self.current_habit.habit_type = match self.current_habit.habit_type {
-            HabitType::Build => HabitType::Avoid,
-            HabitType::Avoid => HabitType::Build,
+    /// Moves the selection a screenful along the focused list (or the
+    /// week grid's rows), stopping at the ends. `None` goes all the way.
+    pub fn page_selection(&mut self, forward: bool, rows: Option<usize>) {
+        let order = if self.current_screen == CurrentScreen::Week {
+            self.row_order(true)
+        } else {
+            self.side_order()
+        };
+        let Some(last) = order.len().checked_sub(1) else {
+            return;
+        };
+        let step = rows.unwrap_or(usize::MAX);
+        let target = match order.iter().position(|row| *row == self.selected_row()) {
+            Some(pos) if forward => pos.saturating_add(step).min(last),
+            Some(pos) => pos.saturating_sub(step),
+            None => 0,
         };
+        self.select_row(order[target]);
     }
 
-    pub fn toggle_add_mode(&mut self) {
-        if let ScreenMode::Normal = self.screen_mode {
-            self.screen_mode = ScreenMode::Adding;
+    /// A page of habit rows as last drawn, less one for overlap.
+    pub fn page_rows(&self) -> usize {
+        self.hitboxes.borrow().page_rows.saturating_sub(1).max(1)
+    }
+    /// Selection as one index over build habits followed by avoid habits,
+    /// the row order of the week grid.
+    pub fn selected_row(&self) -> usize {
+        if self.counter.switch {
+            self.build_habits.len() + self.counter.avoid_counter
+        } else {
+            self.counter.build_counter
         }
     }
 
-    pub fn toggle_edit_mode(&mut self, habit: Habit) {
-        if let ScreenMode::Normal = self.screen_mode {
-            self.screen_mode = ScreenMode::Editing;
-            self.current_habit = habit;
+    pub fn select_row(&mut self, row: usize) {
+        if row < self.build_habits.len() {
+            self.counter.switch = false;
+            self.counter.build_counter = row;
+        } else if row - self.build_habits.len() < self.avoid_habits.len() {
+            self.counter.switch = true;
+            self.counter.avoid_counter = row - self.build_habits.len();
         }
     }
 
-    pub fn toggle_help_mode(&mut self) {
-        match self.screen_mode {
-            ScreenMode::Normal => self.screen_mode = ScreenMode::Help,
-            ScreenMode::Help => self.screen_mode = ScreenMode::Normal,
-            _ => {}
-        }
+    pub fn increment_row(&mut self) {
+        let order = self.navigable_rows();
+        self.step_selection(&order, true);
     }
 
-    pub fn toggle_holiday_mode(&mut self, habit: Habit) {
-        if let ScreenMode::Normal = self.screen_mode {
-            self.screen_mode = ScreenMode::Holiday;
-            self.current_habit = habit;
-            self.holiday_input = HolidayInput::default();
+    pub fn decrement_row(&mut self) {
+        let order = self.navigable_rows();
+        self.step_selection(&order, false);
+    }
+
+    fn habits_of(&self, habit_type: HabitType) -> &[Habit] {
+        match habit_type {
+            HabitType::Build => &self.build_habits,
+            HabitType::Avoid => &self.avoid_habits,
         }
     }
 
-    pub fn toggle_holiday_focus(&mut self) {
-        self.holiday_input.focus_end = !self.holiday_input.focus_end;
+    /// Whether `habit` is on screen: not archived and passing the tag filter.
+    pub fn shows(&self, habit: &Habit) -> bool {
+        habit.archived.is_none() && self.matches_tag(habit)
     }
 
-    pub fn push_holiday_char(&mut self, value: char) {
-        if self.holiday_input.focus_end {
-            self.holiday_input.end.push(value);
-        } else {
-            self.holiday_input.start.push(value);
-        }
+    fn matches_tag(&self, habit: &Habit) -> bool {
+        self.tag_filter.as_ref().is_none_or(|tag| habit.has_tag(tag))
     }
 
-    pub fn pop_holiday_char(&mut self) {
-        if self.holiday_input.focus_end {
-            self.holiday_input.end.pop();
-        } else {
-            self.holiday_input.start.pop();
-        }
+    pub fn shown_habits(&self) -> impl Iterator<Item = &Habit> {
+        self.all_habits().filter(|h| self.shows(h))
     }
 
-    pub fn add_holiday(&mut self) {
-        let parse = |s: &str| NaiveDate::parse_from_str(s.trim(), "%Y-%m-%d");
-        match (parse(&self.holiday_input.start), parse(&self.holiday_input.end)) {
-            (Ok(start), Ok(end)) => {
-                let habit = if !self.counter.switch {
-                    &mut self.build_habits[self.counter.build_counter]
-                } else {
-                    &mut self.avoid_habits[self.counter.avoid_counter]
-                };
-                habit.add_holiday(start, end);
-                self.toggle_normal_mode();
-            }
-            _ => {
-                self.holiday_input.error = Some("Use YYYY-MM-DD for both dates".to_string());
+    /// Every tag on a habit still tracked, alphabetically, spelled as
+    /// first seen.
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = Vec::new();
+        let active = self.all_habits().filter(|h| h.archived.is_none());
+        for tag in active.flat_map(|h| h.tags.iter()) {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.clone());
             }
         }
+        tags.sort_by_key(|t| t.to_lowercase());
+        tags
     }
 
-    pub fn toggle_delete_mode(&mut self) {
-        if let ScreenMode::Normal = self.screen_mode {
-            self.screen_mode = ScreenMode::Deleting;
+    /// A list's shown habits by category, alphabetically with untagged
+    /// habits last, each group in the current sort order. Until some habit
+    /// in the list has a tag, it's a single unnamed group.
+    fn groups(&self, habit_type: HabitType) -> Vec<(Option<String>, Vec<usize>)> {
+        let mut groups = self.unsorted_groups(habit_type);
+        let habits = self.habits_of(habit_type);
+        for (_, rows) in &mut groups {
+            self.sort_rows(habits, rows);
         }
+        groups
     }
 
-    pub fn toggle_reset_mode(&mut self) {
-        if let ScreenMode::Normal = self.screen_mode {
-            self.screen_mode = ScreenMode::Reset;
+    fn unsorted_groups(&self, habit_type: HabitType) -> Vec<(Option<String>, Vec<usize>)> {
+        let habits = self.habits_of(habit_type);
+        let shown = habits.iter().enumerate().filter(|(_, h)| self.shows(h));
+        if habits.iter().all(|h| h.tags.is_empty()) {
+            return vec![(None, shown.map(|(i, _)| i).collect())];
+        }
+        let mut groups: Vec<(String, Vec<usize>)> = Vec::new();
+        for (i, habit) in shown {
+            let name = habit.category().unwrap_or(UNTAGGED);
+            match groups.iter_mut().find(|(g, _)| g.eq_ignore_ascii_case(name)) {
+                Some((_, rows)) => rows.push(i),
+                None => groups.push((name.to_string(), vec![i])),
+            }
         }
+        groups.sort_by_key(|(name, _)| (name == UNTAGGED, name.to_lowercase()));
+        groups.into_iter().map(|(name, rows)| (Some(name), rows)).collect()
     }
 
-    pub fn toggle_normal_mode(&mut self) {
-        if !matches!(self.screen_mode, ScreenMode::Normal) {
-            self.screen_mode = ScreenMode::Normal;
-            self.current_habit = Habit::default();
-            self.holiday_input = HolidayInput::default();
+    /// Orders `rows` of `habits` by the sort mode. The sorts are stable, so
+    /// ties keep the user's own order.
+    fn sort_rows(&self, habits: &[Habit], rows: &mut [usize]) {
+        let date = self.acting_date();
+        match self.sort_mode {
+            SortMode::Manual => {}
+            SortMode::Name => rows.sort_by_key(|i| habits[*i].name.to_lowercase()),
+            SortMode::Tier => rows.sort_by_key(|i| {
+                std::cmp::Reverse(habits[*i].check_pattern(self.day_cutoff_hour))
+            }),
+            SortMode::Pending => rows.sort_by_key(|i| {
+                let habit = &habits[*i];
+                habit.completed_on(date, self.day_cutoff_hour) || habit.is_on_holiday(date)
+            }),
+            SortMode::TimeOfDay => rows.sort_by_key(|i| {
+                habits[*i]
+                    .time_of_day
+                    .map_or(TimeOfDay::ALL.len(), |time| time as usize)
+            }),
         }
     }
 
-    pub fn toggle_build_habits(&mut self) {
+    pub fn cycle_sort_mode(&mut self) {
+        self.sort_mode = self.sort_mode.next();
+    }
+
+    /// Moves the selected habit one place up or down within its group,
+    /// switching to the user's own order first, seeded from what's on
+    /// screen so nothing jumps.
+    pub fn move_selected_habit(&mut self, forward: bool) {
+        if self.sort_mode != SortMode::Manual {
+            self.adopt_sorted_order();
+            self.sort_mode = SortMode::Manual;
+        }
+        let (habit_type, index) = if self.counter.switch {
+            (HabitType::Avoid, self.counter.avoid_counter)
+        } else {
+            (HabitType::Build, self.counter.build_counter)
+        };
+        let Some((_, rows)) = self
+            .groups(habit_type)
+            .into_iter()
+            .find(|(_, rows)| rows.contains(&index))
+        else {
+            return;
+        };
+        let pos = rows.iter().position(|i| *i == index).unwrap_or(0);
+        let target = if forward {
+            rows.get(pos + 1)
+        } else {
+            pos.checked_sub(1).and_then(|p| rows.get(p))
+        };
+        let Some(target) = target.copied() else {
+            return;
+        };
         if self.counter.switch {
-            self.counter.switch = !self.counter.switch;
+            self.avoid_habits.swap(index, target);
+            self.counter.avoid_counter = target;
+        } else {
+            self.build_habits.swap(index, target);
+            self.counter.build_counter = target;
         }
     }
 
-    pub fn toggle_avoid_habit(&mut self) {
-        if !self.counter.switch {
-            self.counter.switch = !self.counter.switch;
+    /// Rewrites both lists in their sorted order. Hidden habits keep their
+    /// places and the selection stays on the same habit.
+    fn adopt_sorted_order(&mut self) {
+        for habit_type in [HabitType::Build, HabitType::Avoid] {
+            let sorted: Vec<usize> = self
+                .groups(habit_type)
+                .into_iter()
+                .flat_map(|(_, rows)| rows)
+                .collect();
+            let mut slots = sorted.clone();
+            slots.sort_unstable();
+            let (habits, selected) = match habit_type {
+                HabitType::Build => (&mut self.build_habits, &mut self.counter.build_counter),
+                HabitType::Avoid => (&mut self.avoid_habits, &mut self.counter.avoid_counter),
+            };
+            let old = habits.clone();
+            let was_selected = *selected;
+            for (slot, from) in slots.iter().zip(&sorted) {
+                habits[*slot] = old[*from].clone();
+                if *from == was_selected {
+                    *selected = *slot;
+                }
+            }
         }
     }
 
-    pub fn increment_habits_counter(&mut self) {
-        if !self.counter.switch {
-            if self.counter.build_counter + 1 < self.build_habits.len() {
-                self.counter.build_counter += 1;
+    pub fn is_collapsed(&self, group: &str) -> bool {
+        self.collapsed.contains(&group.to_lowercase())
+    }
+
+    /// The lines of a Today list or week grid section: group headings,
+    /// and the habits of groups that aren't folded.
+    pub fn list_rows(&self, habit_type: HabitType) -> Vec<ListRow> {
+        let mut rows = Vec::new();
+        for (name, habits) in self.groups(habit_type) {
+            if let Some(name) = name {
+                let collapsed = self.is_collapsed(&name);
+                rows.push(ListRow::Group(name, habits.len()));
+                if collapsed {
+                    continue;
+                }
             }
-        } else {
-            if self.counter.avoid_counter + 1 < self.avoid_habits.len() {
-                self.counter.avoid_counter += 1;
+            rows.extend(habits.into_iter().map(ListRow::Habit));
+        }
+        rows
+    }
+
+    /// Shown habits as rows (build habits first), in display order.
+    /// `folded` leaves out the habits of folded groups.
+    pub fn row_order(&self, folded: bool) -> Vec<usize> {
+        let mut order = Vec::new();
+        for (habit_type, first_row) in [(HabitType::Build, 0), (HabitType::Avoid, self.build_habits.len())] {
+            for (name, habits) in self.groups(habit_type) {
+                if folded && name.is_some_and(|n| self.is_collapsed(&n)) {
+                    continue;
+                }
+                order.extend(habits.into_iter().map(|i| first_row + i));
             }
         }
+        order
     }
 
-    pub fn decrement_habits_counter(&mut self) {
-        if self.counter.switch {
-            if self.counter.avoid_counter > 0 {
-                self.counter.avoid_counter -= 1;
+    /// The day breakdown's rows: the lists' order, plus archived habits
+    /// that were still tracked that day, build habits before avoid habits.
+    pub fn detail_rows(&self) -> Vec<usize> {
+        let date = self.detail_date;
+        let mut rows = self.row_order(false);
+        rows.extend(
+            self.all_habits()
+                .enumerate()
+                .filter(|(_, h)| h.archived.is_some() && h.tracked_on(date) && self.matches_tag(h))
+                .map(|(row, _)| row),
+        );
+        rows.sort_by_key(|row| *row >= self.build_habits.len());
+        rows
+    }
+
+    /// The rows up/down step through: the breakdown's while it is open,
+    /// otherwise the lists' unfolded ones.
+    fn navigable_rows(&self) -> Vec<usize> {
+        if matches!(self.screen_mode, ScreenMode::DayDetail) {
+            self.detail_rows()
+        } else {
+            self.row_order(true)
+        }
+    }
+
+    /// Whether the selected habit is on screen, and so safe to act on.
+    pub fn selection_shown(&self) -> bool {
+        self.navigable_rows().contains(&self.selected_row())
+    }
+
+    /// Moves each list's selection onto a shown habit when filtering or
+    /// folding hid the one it was on.
+    fn ensure_selection_shown(&mut self) {
+        let switch = self.counter.switch;
+        for side in [false, true] {
+            self.counter.switch = side;
+            if !self.selection_shown() {
+                if let Some(row) = self.side_order().first().copied() {
+                    self.select_row(row);
+                }
             }
+        }
+        self.counter.switch = switch;
+    }
+
+    /// Folds or unfolds the selected habit's group.
+    pub fn toggle_group(&mut self) {
+        let habit_type = if self.counter.switch { HabitType::Avoid } else { HabitType::Build };
+        let index = if self.counter.switch {
+            self.counter.avoid_counter
         } else {
-            if self.counter.build_counter > 0 {
-                self.counter.build_counter -= 1;
+            self.counter.build_counter
+        };
+        let group = self
+            .groups(habit_type)
+            .into_iter()
+            .find(|(_, habits)| habits.contains(&index))
+            .and_then(|(name, _)| name);
+        if let Some(group) = group {
+            self.toggle_group_named(&group);
+        }
+    }
+
+    pub fn toggle_group_named(&mut self, group: &str) {
+        let key = group.to_lowercase();
+        if !self.collapsed.remove(&key) {
+            self.collapsed.insert(key);
+        }
+        self.ensure_selection_shown();
+    }
+
+    /// Unfolds everything if anything is folded, otherwise folds every group.
+    pub fn toggle_all_groups(&mut self) {
+        if !self.collapsed.is_empty() {
+            self.collapsed.clear();
+            return;
+        }
+        for habit_type in [HabitType::Build, HabitType::Avoid] {
+            for (name, _) in self.groups(habit_type) {
+                if let Some(name) = name {
+                    self.collapsed.insert(name.to_lowercase());
+                }
             }
         }
     }
+
+    /// Steps the tag filter through every tag and back to showing all.
+    pub fn cycle_tag_filter(&mut self) {
+        let tags = self.tags();
+        let next = match &self.tag_filter {
+            None => 0,
+            Some(current) => tags
+                .iter()
+                .position(|t| t.eq_ignore_ascii_case(current))
+                .map_or(0, |i| i + 1),
+        };
+        self.tag_filter = tags.get(next).cloned();
+        self.ensure_selection_shown();
+    }
+
+    /// The last 365 days sit after the newest year, so `]` from this year
+    /// switches to them.
     pub fn increment_year_counter(&mut self) {
         if self.counter.year_counter + 1 < self.years.len() {
-            self.counter.year_counter += 1;
+            self.select_year(self.counter.year_counter + 1);
+        } else if !self.heatmap_rolling {
+            self.select_rolling_year();
         }
     }
 
     pub fn decrement_year_counter(&mut self) {
-        if self.counter.year_counter > 0 {
-            self.counter.year_counter -= 1;
+        if self.heatmap_rolling {
+            self.select_year(self.years.len().saturating_sub(1));
+        } else if self.counter.year_counter > 0 {
+            self.select_year(self.counter.year_counter - 1);
+        }
+    }
+
+    /// Shows the 365 days ending today instead of a calendar year.
+    pub fn select_rolling_year(&mut self) {
+        self.heatmap_rolling = true;
+        let (start, _) = self.heatmap_range();
+        self.heatmap_cursor = self.heatmap_cursor.max(start);
+    }
+
+    /// First and last day the heatmap covers: a whole calendar year, or the
+    /// 365 days ending today.
+    pub fn heatmap_range(&self) -> (NaiveDate, NaiveDate) {
+        let today = self.today();
+        if self.heatmap_rolling {
+            return (today - Duration::days(364), today);
+        }
+        let year = self
+            .years
+            .get(self.counter.year_counter)
+            .and_then(|y| y.parse().ok())
+            .unwrap_or(today.year());
+        (
+            NaiveDate::from_ymd_opt(year, 1, 1).unwrap(),
+            NaiveDate::from_ymd_opt(year, 12, 31).unwrap(),
+        )
+    }
+
+    /// Shows another year on the heatmap, taking the cursor to the same
+    /// day there (or today, if that's still ahead).
+    pub fn select_year(&mut self, index: usize) {
+        let Some(year) = self.years.get(index).and_then(|y| y.parse::<i32>().ok()) else {
+            return;
+        };
+        self.heatmap_rolling = false;
+        self.counter.year_counter = index;
+        let months = (year - self.heatmap_cursor.year()) * 12;
+        let date = if months < 0 {
+            self.heatmap_cursor.checked_sub_months(Months::new(months.unsigned_abs()))
+        } else {
+            self.heatmap_cursor.checked_add_months(Months::new(months as u32))
+        };
+        if let Some(date) = date {
+            self.heatmap_cursor = date.min(self.today());
+        }
+    }
+
+    /// Points the heatmap at its cursor's year, if it has one, leaving the
+    /// last-365-days view once the cursor falls outside it.
+    fn sync_year_counter(&mut self) {
+        if self.heatmap_rolling && self.heatmap_cursor < self.heatmap_range().0 {
+            self.heatmap_rolling = false;
+        }
+        let year = self.heatmap_cursor.year().to_string();
+        if let Some(index) = self.years.iter().position(|y| *y == year) {
+            self.counter.year_counter = index;
+        }
+    }
+
+    /// Moves the heatmap cursor, staying within the years it can show.
+    pub fn move_heatmap_cursor(&mut self, days: i64) {
+        let date = self.heatmap_cursor + Duration::days(days);
+        let year = date.year().to_string();
+        if self.heatmap_rolling {
+            let (start, end) = self.heatmap_range();
+            if (start..=end).contains(&date) {
+                self.heatmap_cursor = date;
+            }
+        } else if date <= self.today() && self.years.contains(&year) {
+            self.heatmap_cursor = date;
+            self.sync_year_counter();
+        }
+    }
+
+    pub fn cycle_heatmap_filter(&mut self) {
+        // Single habits come in list order, build habits first.
+        let names: Vec<String> = self.all_habits().map(|h| h.name.clone()).collect();
+        self.heatmap_filter = match &self.heatmap_filter {
+            HeatmapFilter::All => HeatmapFilter::Build,
+            HeatmapFilter::Build => HeatmapFilter::Avoid,
+            HeatmapFilter::Avoid => names
+                .first()
+                .cloned()
+                .map_or(HeatmapFilter::All, HeatmapFilter::Habit),
+            HeatmapFilter::Habit(name) => names
+                .iter()
+                .skip_while(|n| *n != name)
+                .nth(1)
+                .cloned()
+                .map_or(HeatmapFilter::All, HeatmapFilter::Habit),
+        };
+    }
+
+    /// The habits the heatmap filter lets through.
+    pub fn heatmap_habits(&self) -> Vec<&Habit> {
+        let habits: Vec<&Habit> = match &self.heatmap_filter {
+            HeatmapFilter::All => self.all_habits().collect(),
+            HeatmapFilter::Build => self.build_habits.iter().collect(),
+            HeatmapFilter::Avoid => self.avoid_habits.iter().collect(),
+            HeatmapFilter::Habit(name) => self.all_habits().filter(|h| h.name == *name).collect(),
+        };
+        habits.into_iter().filter(|h| self.matches_tag(h)).collect()
+    }
+
+    pub fn heatmap_filter_label(&self) -> String {
+        let label = match &self.heatmap_filter {
+            HeatmapFilter::All => "All habits".to_string(),
+            HeatmapFilter::Build => "Build habits".to_string(),
+            HeatmapFilter::Avoid => "Avoid habits".to_string(),
+            HeatmapFilter::Habit(name) => name.clone(),
+        };
+        match &self.tag_filter {
+            Some(tag) => format!("{label} · #{tag}"),
+            None => label,
         }
     }
 
+    /// Habits done on `date` and habits counted, under the heatmap filter.
+    /// Archived habits count up to the day they were archived.
+    pub fn heatmap_counts(&self, date: NaiveDate) -> (usize, usize) {
+        let mut habits = self.heatmap_habits();
+        habits.retain(|h| h.tracked_on(date));
+        let done = habits
+            .iter()
+            .filter(|h| h.completed_on(date, self.day_cutoff_hour))
+            .count();
+        (done, habits.len())
+    }
+
+    /// Adds the form's habit, or leaves the form open with the reason it
+    /// can't be.
     pub fn add_habit(&mut self) {
-        if !self.current_habit.name.trim().is_empty() {
-            self.current_habit.created = today_with_cutoff(self.day_cutoff_hour);
-            match self.current_habit.habit_type {
-                HabitType::Build => self.build_habits.push(self.current_habit.clone()),
-                HabitType::Avoid => self.avoid_habits.push(self.current_habit.clone()),
+        match self.validate_habit_name(None) {
+            Ok(name) => self.current_habit.name = name,
+            Err(error) => {
+                self.habit_input.error = Some(error);
+                return;
             }
         }
+        self.current_habit.created = today_with_cutoff(self.day_cutoff_hour);
+        self.current_habit.tags = parse_tags(self.habit_input.tags.text());
+        match self.current_habit.habit_type {
+            HabitType::Build => self.build_habits.push(self.current_habit.clone()),
+            HabitType::Avoid => self.avoid_habits.push(self.current_habit.clone()),
+        }
         self.toggle_normal_mode();
+        self.ensure_selection_shown();
     }
 
     pub fn edit_habit(&mut self) {
-        if !self.current_habit.name.trim().is_empty() {
-            match (self.counter.switch, &self.current_habit.habit_type) {
-                (false, HabitType::Build) => {
-                    self.build_habits[self.counter.build_counter] = self.current_habit.clone();
-                }
-                (false, HabitType::Avoid) => {
-                    self.build_habits.remove(self.counter.build_counter);
-                    self.avoid_habits.push(self.current_habit.clone());
-                }
-                (true, HabitType::Build) => {
-                    self.avoid_habits.remove(self.counter.avoid_counter);
-                    self.build_habits.push(self.current_habit.clone());
-                }
-                (true, HabitType::Avoid) => {
-                    self.avoid_habits[self.counter.avoid_counter] = self.current_habit.clone();
+        match self.validate_habit_name(Some(self.selected_row())) {
+            Ok(name) => {
+                // A one-habit heatmap follows the habit to its new name.
+                if self.heatmap_filter == HeatmapFilter::Habit(self.get_selected_habit().name) {
+                    self.heatmap_filter = HeatmapFilter::Habit(name.clone());
                 }
+                self.current_habit.name = name;
+            }
+            Err(error) => {
+                self.habit_input.error = Some(error);
+                return;
+            }
+        }
+        self.current_habit.tags = parse_tags(self.habit_input.tags.text());
+        match (self.counter.switch, &self.current_habit.habit_type) {
+            (false, HabitType::Build) => {
+                self.build_habits[self.counter.build_counter] = self.current_habit.clone();
+            }
+            // A habit changing type keeps its place rather than
+            // dropping to the bottom of the other list, and stays selected.
+            (false, HabitType::Avoid) => {
+                let index = self.counter.build_counter;
+                self.build_habits.remove(index);
+                let index = index.min(self.avoid_habits.len());
+                self.avoid_habits.insert(index, self.current_habit.clone());
+                self.counter.avoid_counter = index;
+                self.counter.switch = true;
+            }
+            (true, HabitType::Build) => {
+                let index = self.counter.avoid_counter;
+                self.avoid_habits.remove(index);
+                let index = index.min(self.build_habits.len());
+                self.build_habits.insert(index, self.current_habit.clone());
+                self.counter.build_counter = index;
+                self.counter.switch = false;
+            }
+            (true, HabitType::Avoid) => {
+                self.avoid_habits[self.counter.avoid_counter] = self.current_habit.clone();
             }
         }
         self.toggle_normal_mode();
+        self.ensure_selection_shown();
     }
 
     pub fn delete_current_habit(&mut self) {
+        if self.heatmap_filter == HeatmapFilter::Habit(self.get_selected_habit().name) {
+            self.heatmap_filter = HeatmapFilter::All;
+        }
         if !self.counter.switch {
             self.build_habits.remove(self.counter.build_counter);
             // Adjust counter to stay in bounds
@@ -353,6 +1916,7 @@ impl App {
             }
         }
         self.toggle_normal_mode();
+        self.ensure_selection_shown();
     }
 
     pub fn reset_current_habit(&mut self) {
@@ -364,13 +1928,92 @@ impl App {
         self.toggle_normal_mode();
     }
 
-    pub fn toggle_current_habit(&mut self) {
+    fn selected_habit_mut(&mut self) -> &mut Habit {
         if !self.counter.switch {
-            self.build_habits[self.counter.build_counter]
-                .toggle_complete(&self.current_day, self.day_cutoff_hour);
+            &mut self.build_habits[self.counter.build_counter]
+        } else {
+            &mut self.avoid_habits[self.counter.avoid_counter]
+        }
+    }
+
+    pub fn toggle_pause_current(&mut self) {
+        let today = self.today();
+        self.selected_habit_mut().toggle_pause(today);
+    }
+
+    pub fn archive_current_habit(&mut self) {
+        let today = self.today();
+        self.selected_habit_mut().archive(today);
+        self.ensure_selection_shown();
+    }
+
+    pub fn toggle_archive_mode(&mut self) {
+        if let ScreenMode::Normal = self.screen_mode {
+            self.archive_selected = 0;
+            self.screen_mode = ScreenMode::Archive;
+        }
+    }
+
+    /// Archived habits as rows (build habits first).
+    pub fn archived_rows(&self) -> Vec<usize> {
+        self.all_habits()
+            .enumerate()
+            .filter(|(_, h)| h.archived.is_some())
+            .map(|(row, _)| row)
+            .collect()
+    }
+
+    pub fn step_archive_selection(&mut self, forward: bool) {
+        let last = self.archived_rows().len().saturating_sub(1);
+        self.archive_selected = if forward {
+            (self.archive_selected + 1).min(last)
+        } else {
+            self.archive_selected.saturating_sub(1)
+        };
+    }
+
+    pub fn restore_selected_habit(&mut self) {
+        let Some(row) = self.archived_rows().get(self.archive_selected).copied() else {
+            return;
+        };
+        let today = self.today();
+        let build = self.build_habits.len();
+        let habit = if row < build {
+            &mut self.build_habits[row]
         } else {
-            self.avoid_habits[self.counter.avoid_counter]
-                .toggle_complete(&self.current_day, self.day_cutoff_hour);
+            &mut self.avoid_habits[row - build]
+        };
+        habit.restore(today);
+        self.archive_selected = self
+            .archive_selected
+            .min(self.archived_rows().len().saturating_sub(1));
+        self.ensure_selection_shown();
+    }
+
+    /// Checks a build habit in or out. Avoid habits log slips instead, see
+    /// `toggle_slip_mode`.
+    pub fn toggle_current_habit(&mut self) {
+        if !self.counter.switch {
+            let date = self.acting_date();
+            let habit = &mut self.build_habits[self.counter.build_counter];
+            // Marks before the habit existed would inflate its tier; one
+            // already there can still be cleared.
+            let before_created = date < habit.created && !habit.days_completed.contains(&date);
+            if date > today_with_cutoff(self.day_cutoff_hour) || before_created {
+                return;
+            }
+            habit.toggle_complete(date);
+            if habit.days_completed.contains(&date) {
+                self.note_acted_on(date);
+            }
+        }
+    }
+
+    /// Queues today's notifications to be marked acted on. Backfilled and
+    /// undone days don't count, and the scheduler does the writing.
+    fn note_acted_on(&self, date: NaiveDate) {
+        if date == self.today() {
+            self.notif.lock().unwrap().acted_on = Some(date);
         }
     }
 
@@ -382,18 +2025,27 @@ impl App {
         }
     }
 
-    fn all_habits(&self) -> impl Iterator<Item = &Habit> {
+    pub fn all_habits(&self) -> impl Iterator<Item = &Habit> {
         self.build_habits.iter().chain(self.avoid_habits.iter())
     }
 
-    pub fn count_completed_on(&self, date: chrono::NaiveDate) -> usize {
+    /// Habits that were being tracked on `date` and pass the tag filter.
+    /// Archived habits still count for the days before they were archived,
+    /// as on the heatmap.
+    pub fn habits_on(&self, date: NaiveDate) -> impl Iterator<Item = &Habit> {
         self.all_habits()
-            .filter(|h| h.days_completed.contains(&date))
+            .filter(move |h| h.tracked_on(date) && self.matches_tag(h))
+    }
+
+    /// Habits done on `date`, among `habits_on(date)`.
+    pub fn count_completed_on(&self, date: NaiveDate) -> usize {
+        self.habits_on(date)
+            .filter(|h| h.completed_on(date, self.day_cutoff_hour))
             .count()
     }
 
-    pub fn completion_rate_for_date(&self, date: chrono::NaiveDate) -> f32 {
-        let total = self.build_habits.len() + self.avoid_habits.len();
+    pub fn completion_rate_for_date(&self, date: NaiveDate) -> f32 {
+        let total = self.habits_on(date).count();
         if total == 0 {
             return 0.0;
         }
@@ -401,42 +2053,41 @@ impl App {
     }
 
     fn display_gauge(&self, progress: f32) -> String {
-        let segments = [
-            "▱▱▱▱▱▱▱▱▱▱", // 0%
-            "▰▱▱▱▱▱▱▱▱▱", // 10%
-            "▰▰▱▱▱▱▱▱▱▱", // 20%
-            "▰▰▰▱▱▱▱▱▱▱", // 30%
-            "▰▰▰▰▱▱▱▱▱▱", // 40%
-            "▰▰▰▰▰▱▱▱▱▱", // 50%
-            "▰▰▰▰▰▰▱▱▱▱", // 60%
-            "▰▰▰▰▰▰▰▱▱▱", // 70%
-            "▰▰▰▰▰▰▰▰▱▱", // 80%
-            "▰▰▰▰▰▰▰▰▰▱", // 90%
-            "▰▰▰▰▰▰▰▰▰▰", // 100%
-        ];
-        let index = ((progress / 10.0) as usize).min(10);
-        format!("{} {:.1}%", segments[index], progress)
+        // Ten segments, one per 10%.
+        let filled = ((progress / 10.0) as usize).min(10);
+        let glyphs = self.theme.glyphs;
+        format!(
+            "{}{} {:.1}%",
+            glyphs.gauge_full.repeat(filled),
+            glyphs.gauge_empty.repeat(10 - filled),
+            progress
+        )
     }
 
     pub fn set_notification(&self) {
-        let total = self.build_habits.len() + self.avoid_habits.len();
-        let date = Day::Today.resolve_date(self.day_cutoff_hour);
-        let completed = self.count_completed_on(date);
+        // Reminders cover every tracked habit, whatever the screen is
+        // filtered to.
+        let date = self.today();
+        let active: Vec<&Habit> = self.all_habits().filter(|h| h.archived.is_none()).collect();
+        let total = active.len();
+        let completed = active
+            .iter()
+            .filter(|h| h.completed_on(date, self.day_cutoff_hour))
+            .count();
 
         {
             let mut notif = self.notif.lock().unwrap();
-            (*notif).done = completed;
-            (*notif).total = total;
+            notif.done = completed;
+            notif.total = total;
         }
     }
 
-    pub fn check_todays_progress(&self, day: &Day) -> String {
-        let total = self.build_habits.len() + self.avoid_habits.len();
+    pub fn check_todays_progress(&self, date: NaiveDate) -> String {
+        let total = self.habits_on(date).count();
         self.set_notification();
         if total == 0 {
             return format!("{}  ({}/{})", self.display_gauge(0.0), 0, total);
         }
-        let date = day.resolve_date(self.day_cutoff_hour);
         let completed = self.count_completed_on(date);
         let progress = (completed as f32 / total as f32) * 100.0;
         format!(
@@ -447,24 +2098,20 @@ impl App {
         )
     }
     
+    /// Done of due this week so far: days yet to come, holidays and days
+    /// outside a habit's life don't count.
     pub fn check_weeks_progress(&self) -> String {
-        let total_habits = self.build_habits.len() + self.avoid_habits.len();
-        if total_habits == 0 {
+        let dates = self.week_dates();
+        let (completed, total_possible) = self
+            .all_habits()
+            .filter(|h| self.matches_tag(h))
+            .map(|h| h.tally(dates[0], dates[6].min(self.today())))
+            .fold((0, 0), |(done, due), (d, p)| (done + d, due + p));
+        if total_possible == 0 {
             return format!("{}  ({}/{})", self.display_gauge(0.0), 0, 0);
         }
 
         self.set_notification();
-        let date = today_with_cutoff(self.day_cutoff_hour);
-        let days_since_monday = date.weekday().num_days_from_monday();
-        let week_start = date - Duration::days(days_since_monday as i64);
-
-        let total_possible = total_habits * 7;
-        let completed: usize = (0..7)
-            .map(|i| {
-                let check_date = week_start + Duration::days(i);
-                self.count_completed_on(check_date)
-            })
-            .sum();
 
         let progress = (completed as f32) / (total_possible as f32) * 100.0;
         format!(
@@ -474,31 +2121,253 @@ impl App {
             total_possible
         )
     }
+    /// Every year from the first recorded day to the last, always taking in
+    /// this one so a fresh install still has a year to show.
     pub fn get_heatmap_years(&self) -> Vec<String> {
-        let mut min = 3000;
-        let mut max = 2000;
-        for i in &self.build_habits {
-            let year = i.created.year();
-            if year > max {
-                max = year;
-            }
-            if year < min {
-                min = year;
-            }
+        let this_year = self.today().year();
+        let days = self.all_habits().flat_map(|h| {
+            std::iter::once(h.created)
+                .chain(h.days_completed.iter().copied())
+                .chain(h.slips.iter().map(|s| s.date))
+        });
+        let (first, last) = days.fold((this_year, this_year), |(first, last), d| {
+            (first.min(d.year()), last.max(d.year()))
+        });
+        (first..=last).map(|y| y.to_string()).collect()
+    }
+}
+
+/// Now, without seconds.
+fn current_minute() -> NaiveDateTime {
+    let now = Local::now().naive_local();
+    now.with_second(0).and_then(|t| t.with_nanosecond(0)).unwrap_or(now)
+}
+
+/// Splits the form's comma-separated tags, dropping blanks, a leading `#`
+/// and repeats.
+fn parse_tags(input: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in input.split(',') {
+        let tag = tag.trim().trim_start_matches('#').trim();
+        if !tag.is_empty() && !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.to_string());
         }
-        for i in &self.avoid_habits {
-            let year = i.created.year();
-            if year > max {
-                max = year;
-            }
-            if year < min {
-                min = year;
-            }
+    }
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn habit(name: &str, habit_type: HabitType) -> Habit {
+        Habit {
+            name: name.to_string(),
+            habit_type,
+            ..Habit::default()
         }
-        if min == max {
-            vec![min.to_string()]
-        } else {
-            (min..max + 1).map(|i| i.to_string()).collect()
+    }
+
+    fn app_with(build: &[&str], avoid: &[&str]) -> App {
+        let mut app = App::new();
+        app.build_habits = build.iter().map(|n| habit(n, HabitType::Build)).collect();
+        app.avoid_habits = avoid.iter().map(|n| habit(n, HabitType::Avoid)).collect();
+        app
+    }
+
+    fn names(app: &App, habit_type: HabitType) -> Vec<&str> {
+        app.habits_of(habit_type)
+            .iter()
+            .map(|h| h.name.as_str())
+            .collect()
+    }
+
+    fn shown_names(app: &App) -> Vec<&str> {
+        let build = app.build_habits.len();
+        app.row_order(true)
+            .into_iter()
+            .map(|row| match row.checked_sub(build) {
+                Some(i) => app.avoid_habits[i].name.as_str(),
+                None => app.build_habits[row].name.as_str(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn moving_a_habit_swaps_it_with_its_neighbour() {
+        let mut app = app_with(&["Read", "Run", "Stretch"], &[]);
+        app.counter.build_counter = 1;
+        app.move_selected_habit(false);
+        assert_eq!(names(&app, HabitType::Build), ["Run", "Read", "Stretch"]);
+        assert_eq!(app.counter.build_counter, 0);
+
+        app.move_selected_habit(true);
+        app.move_selected_habit(true);
+        assert_eq!(names(&app, HabitType::Build), ["Read", "Stretch", "Run"]);
+        assert_eq!(app.counter.build_counter, 2);
+    }
+
+    #[test]
+    fn moving_stops_at_the_ends_of_the_list() {
+        let mut app = app_with(&["Read", "Run"], &["Doomscroll", "Snacks"]);
+        app.move_selected_habit(false);
+        assert_eq!(names(&app, HabitType::Build), ["Read", "Run"]);
+        assert_eq!(app.counter.build_counter, 0);
+
+        app.counter.switch = true;
+        app.counter.avoid_counter = 1;
+        app.move_selected_habit(true);
+        assert_eq!(names(&app, HabitType::Avoid), ["Doomscroll", "Snacks"]);
+        assert_eq!(app.counter.avoid_counter, 1);
+    }
+
+    #[test]
+    fn moving_stays_within_the_group_and_skips_hidden_habits() {
+        let mut app = app_with(&["Read", "Old", "Run", "Gym"], &[]);
+        app.build_habits[0].tags = vec!["mind".to_string()];
+        app.build_habits[1].archived = Some(app.current_date);
+        app.build_habits[2].tags = vec!["mind".to_string()];
+        app.build_habits[3].tags = vec!["body".to_string()];
+        // "body" sorts first, so Gym heads the list and Read leads "mind".
+        app.counter.build_counter = 0;
+        app.move_selected_habit(false);
+        assert_eq!(names(&app, HabitType::Build), ["Read", "Old", "Run", "Gym"]);
+
+        app.move_selected_habit(true);
+        assert_eq!(names(&app, HabitType::Build), ["Run", "Old", "Read", "Gym"]);
+        assert_eq!(app.counter.build_counter, 2);
+    }
+
+    #[test]
+    fn sort_modes_order_each_list() {
+        let mut app = app_with(&["stretch", "Read", "Meditate"], &["Snacks", "Doomscroll"]);
+        assert_eq!(
+            shown_names(&app),
+            ["stretch", "Read", "Meditate", "Snacks", "Doomscroll"]
+        );
+
+        app.sort_mode = SortMode::Name;
+        assert_eq!(
+            shown_names(&app),
+            ["Meditate", "Read", "stretch", "Doomscroll", "Snacks"]
+        );
+
+        app.sort_mode = SortMode::TimeOfDay;
+        app.build_habits[0].time_of_day = Some(TimeOfDay::Morning);
+        app.build_habits[2].time_of_day = Some(TimeOfDay::Evening);
+        assert_eq!(
+            shown_names(&app),
+            ["stretch", "Meditate", "Read", "Snacks", "Doomscroll"]
+        );
+
+        app.sort_mode = SortMode::Pending;
+        let today = app.current_date;
+        app.build_habits[0].days_completed.insert(today);
+        assert_eq!(
+            shown_names(&app),
+            ["Read", "Meditate", "stretch", "Snacks", "Doomscroll"]
+        );
+    }
+
+    #[test]
+    fn sort_modes_cycle_back_to_manual() {
+        let mut app = App::new();
+        let mut seen = vec![app.sort_mode];
+        for _ in 0..4 {
+            app.cycle_sort_mode();
+            assert!(!seen.contains(&app.sort_mode));
+            seen.push(app.sort_mode);
         }
+        app.cycle_sort_mode();
+        assert!(app.sort_mode == SortMode::Manual);
+    }
+
+    #[test]
+    fn moving_while_sorted_keeps_the_sorted_order() {
+        let mut app = app_with(&["Run", "Meditate", "Read"], &[]);
+        app.sort_mode = SortMode::Name;
+        // Run is last by name; moving it up puts it above Read.
+        app.counter.build_counter = 0;
+        app.move_selected_habit(false);
+        assert!(app.sort_mode == SortMode::Manual);
+        assert_eq!(names(&app, HabitType::Build), ["Meditate", "Run", "Read"]);
+        assert_eq!(app.counter.build_counter, 1);
+    }
+
+    #[test]
+    fn page_rows_keeps_a_row_of_overlap() {
+        let app = App::new();
+        assert_eq!(app.page_rows(), 1);
+        app.hitboxes.borrow_mut().page_rows = 1;
+        assert_eq!(app.page_rows(), 1);
+        app.hitboxes.borrow_mut().page_rows = 10;
+        assert_eq!(app.page_rows(), 9);
+    }
+
+    #[test]
+    fn paging_stops_at_the_ends_of_the_focused_list() {
+        let mut app = app_with(&["A", "B", "C", "D", "E"], &["X", "Y"]);
+        app.page_selection(true, Some(3));
+        assert_eq!(app.selected_row(), 3);
+        app.page_selection(true, Some(3));
+        assert_eq!(app.selected_row(), 4);
+        assert!(!app.counter.switch);
+
+        app.page_selection(false, Some(3));
+        assert_eq!(app.selected_row(), 1);
+        app.page_selection(false, Some(3));
+        assert_eq!(app.selected_row(), 0);
+
+        app.page_selection(true, None);
+        assert_eq!(app.selected_row(), 4);
+        app.page_selection(false, None);
+        assert_eq!(app.selected_row(), 0);
+    }
+
+    #[test]
+    fn paging_the_week_grid_crosses_into_avoid_habits() {
+        let mut app = app_with(&["A", "B"], &["X", "Y"]);
+        app.current_screen = CurrentScreen::Week;
+        app.page_selection(true, Some(3));
+        assert!(app.counter.switch);
+        assert_eq!(app.counter.avoid_counter, 1);
+
+        app.page_selection(false, None);
+        assert!(!app.counter.switch);
+        assert_eq!(app.counter.build_counter, 0);
+    }
+
+    #[test]
+    fn paging_skips_folded_groups() {
+        let mut app = app_with(&["A", "B", "C"], &[]);
+        app.build_habits[0].tags = vec!["a".to_string()];
+        app.build_habits[1].tags = vec!["b".to_string()];
+        app.build_habits[2].tags = vec!["c".to_string()];
+        app.collapsed.insert("b".to_string());
+        app.page_selection(true, Some(1));
+        assert_eq!(app.selected_row(), 2);
+        app.page_selection(false, Some(1));
+        assert_eq!(app.selected_row(), 0);
+    }
+
+    #[test]
+    fn stepping_stops_at_the_ends() {
+        let mut app = app_with(&["A", "B"], &["X"]);
+        app.decrement_row();
+        assert_eq!(app.selected_row(), 0);
+        app.increment_row();
+        app.increment_row();
+        assert_eq!(app.selected_row(), 2);
+        app.increment_row();
+        assert_eq!(app.selected_row(), 2);
+        assert!(app.counter.switch);
+    }
+
+    #[test]
+    fn paging_an_empty_list_leaves_the_selection() {
+        let mut app = app_with(&[], &[]);
+        app.page_selection(true, None);
+        assert_eq!(app.selected_row(), 0);
+        assert!(!app.counter.switch);
     }
 }
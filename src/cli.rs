@@ -0,0 +1,204 @@
+use std::io::{self, Result};
+
+use crate::digest::{Digest, DigestPeriod};
+use crate::habit::{today_with_cutoff, week_start};
+use crate::notifications::{format_record, summarize, NotificationTrigger};
+use crate::sinks::{self, Notice};
+use crate::storage;
+
+const USAGE: &str = "\
+Usage: flow_state [COMMAND]
+
+Commands:
+  (none)    Open the habit tracker
+  log       Show the notification history
+  digest [week|month]
+            Print the reflection digest for last week or last month
+  config check
+            Validate notification.toml, theme.toml and keymap.toml
+  notify    Send a test notification through every configured sink
+  help      Show this message
+
+Options:
+  --ascii          Draw with plain ASCII instead of emoji and box glyphs
+  --screen-reader  Linear text layout that announces the selection
+  --no-mouse       Leave the mouse to the terminal, e.g. for selecting text";
+
+/// Display options, accepted anywhere on the command line.
+pub struct Flags {
+    pub ascii: bool,
+    pub screen_reader: bool,
+    pub no_mouse: bool,
+}
+
+/// Removes the display options from `args`, leaving only the command.
+pub fn take_flags(args: &mut Vec<String>) -> Flags {
+    let mut flags = Flags {
+        ascii: false,
+        screen_reader: false,
+        no_mouse: false,
+    };
+    args.retain(|arg| match arg.as_str() {
+        "--ascii" => {
+            flags.ascii = true;
+            false
+        }
+        "--screen-reader" => {
+            flags.screen_reader = true;
+            false
+        }
+        "--no-mouse" => {
+            flags.no_mouse = true;
+            false
+        }
+        _ => true,
+    });
+    flags
+}
+
+/// Runs a subcommand given on the command line. Returns `Ok(false)` when no
+/// subcommand was given and the TUI should start instead.
+pub fn run(args: &[String]) -> Result<bool> {
+    match args.first().map(String::as_str) {
+        None => Ok(false),
+        Some("log") => {
+            print_notification_log()?;
+            Ok(true)
+        }
+        Some("digest") => {
+            let period = match args.get(1).map(String::as_str) {
+                Some("month") => DigestPeriod::Month,
+                _ => DigestPeriod::Week,
+            };
+            print_digest(period)?;
+            Ok(true)
+        }
+        Some("config") => match args.get(1).map(String::as_str) {
+            Some("check") => check_config(),
+            _ => {
+                eprintln!("usage: flow_state config check");
+                std::process::exit(2);
+            }
+        },
+        Some("notify") => {
+            send_test_notification()?;
+            Ok(true)
+        }
+        Some("help") | Some("-h") | Some("--help") => {
+            println!("{USAGE}");
+            Ok(true)
+        }
+        Some(other) => {
+            eprintln!("unknown command `{other}`\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    }
+}
+
+fn print_notification_log() -> Result<()> {
+    let records = storage::load_notification_log()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    let cutoff_hour = storage::load_notification_settings()
+        .map(|s| s.day_cutoff_hour.min(23))
+        .unwrap_or(0);
+
+    if records.is_empty() {
+        println!("No notifications sent yet.");
+        return Ok(());
+    }
+
+    for record in &records {
+        println!("{}", format_record(record));
+    }
+    println!();
+
+    let this_week = week_start(today_with_cutoff(cutoff_hour));
+    println!("{}", summarize(&records, this_week).describe("this week"));
+    println!("{}", summarize(&records, records[0].sent_at.date()).describe("in total"));
+    Ok(())
+}
+
+fn send_test_notification() -> Result<()> {
+    let settings = storage::load_notification_settings()
+        .map_err(|e| io::Error::other(e.to_string()))?;
+    let errors = sinks::dispatch(
+        &settings.sinks,
+        &Notice {
+            title: "flow_state test",
+            message: "If you can read this, notifications reach you here.",
+            trigger: NotificationTrigger::High,
+            done: 0,
+            total: 0,
+        },
+    );
+    if errors.is_empty() {
+        println!("Sent through {} sink(s).", settings.sinks.len());
+    } else {
+        for error in &errors {
+            eprintln!("failed: {error}");
+        }
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn print_digest(period: DigestPeriod) -> Result<()> {
+    let (build, avoid) = storage::load_habits().map_err(|e| io::Error::other(e.to_string()))?;
+    let habits: Vec<_> = build.into_iter().chain(avoid).collect();
+    let cutoff_hour = storage::load_notification_settings()
+        .map(|s| s.day_cutoff_hour.min(23))
+        .unwrap_or(0);
+
+    let (start, end) = period.previous(today_with_cutoff(cutoff_hour));
+    print!("{}", Digest::build(period, start, end, &habits).to_markdown());
+    Ok(())
+}
+
+fn check_config() -> Result<bool> {
+    let path = storage::notification_settings_path().map_err(|e| io::Error::other(e.to_string()))?;
+    let mut valid = true;
+    if !path.exists() {
+        println!("{} not found; using defaults (notifications off).", path.display());
+    } else {
+        match storage::load_notification_settings() {
+            Ok(settings) => {
+                println!("{} is valid.", path.display());
+                if settings.enable {
+                    println!(
+                        "Daily reminder at {:02}:{:02} through {} sink(s).",
+                        settings.hour,
+                        settings.minute,
+                        settings.sinks.len()
+                    );
+                } else {
+                    println!("Notifications are disabled (enable = false).");
+                }
+            }
+            Err(e) => {
+                eprintln!("{e}");
+                valid = false;
+            }
+        }
+    }
+
+    match storage::load_theme() {
+        Ok(_) => println!("Theme is valid."),
+        Err(e) => {
+            eprintln!("{e}");
+            valid = false;
+        }
+    }
+
+    match storage::load_keymap() {
+        Ok(_) => println!("Keymap is valid."),
+        Err(e) => {
+            eprintln!("{e}");
+            valid = false;
+        }
+    }
+
+    if !valid {
+        std::process::exit(1);
+    }
+    Ok(true)
+}
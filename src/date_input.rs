@@ -0,0 +1,108 @@
+use chrono::{Datelike, Duration, Months, NaiveDate, Weekday};
+
+use crate::habit::week_start;
+
+/// Reads a typed date relative to `today`: `2026-12-20`, `today`,
+/// `tomorrow`, `yesterday`, offsets such as `+7d`, `-2w` or `+1m`, and
+/// weekdays (`fri`, `next monday`, both the first one after today), plus
+/// `next week` (its Monday) and `next month` (its 1st). Case is ignored.
+pub fn parse_date(input: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let input = input.trim().to_lowercase();
+    if let Ok(date) = NaiveDate::parse_from_str(&input, "%Y-%m-%d") {
+        return Some(date);
+    }
+    match input.as_str() {
+        "today" | "now" => return Some(today),
+        "tomorrow" => return Some(today + Duration::days(1)),
+        "yesterday" => return Some(today - Duration::days(1)),
+        "next week" => return Some(week_start(today) + Duration::days(7)),
+        "next month" => return today.with_day(1)?.checked_add_months(Months::new(1)),
+        _ => {}
+    }
+    if let Some(sign @ ('+' | '-')) = input.chars().next() {
+        return offset(today, sign == '-', input[1..].trim());
+    }
+    let day = input.strip_prefix("next ").unwrap_or(&input).parse::<Weekday>().ok()?;
+    let ahead = (day.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+    Some(today + Duration::days(if ahead == 0 { 7 } else { ahead as i64 }))
+}
+
+/// `7d`, `2w` or `1m` (a bare number is days) added to or taken from
+/// `today`.
+fn offset(today: NaiveDate, back: bool, amount: &str) -> Option<NaiveDate> {
+    let split = amount.find(|c: char| !c.is_ascii_digit()).unwrap_or(amount.len());
+    let count: u32 = amount[..split].parse().ok()?;
+    let days = |n: u32| {
+        let n = Duration::days(n as i64);
+        if back { today.checked_sub_signed(n) } else { today.checked_add_signed(n) }
+    };
+    match amount[split..].trim() {
+        "" | "d" | "day" | "days" => days(count),
+        "w" | "week" | "weeks" => days(count.checked_mul(7)?),
+        "m" | "month" | "months" if back => today.checked_sub_months(Months::new(count)),
+        "m" | "month" | "months" => today.checked_add_months(Months::new(count)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// A Monday.
+    fn today() -> NaiveDate {
+        date(2026, 10, 19)
+    }
+
+    #[test]
+    fn named_days() {
+        assert_eq!(parse_date("2026-12-20", today()), Some(date(2026, 12, 20)));
+        assert_eq!(parse_date(" Today ", today()), Some(today()));
+        assert_eq!(parse_date("TOMORROW", today()), Some(date(2026, 10, 20)));
+        assert_eq!(parse_date("yesterday", today()), Some(date(2026, 10, 18)));
+        assert_eq!(parse_date("next week", date(2026, 10, 22)), Some(date(2026, 10, 26)));
+        assert_eq!(parse_date("next month", date(2026, 12, 31)), Some(date(2027, 1, 1)));
+    }
+
+    #[test]
+    fn offsets() {
+        assert_eq!(parse_date("+7", today()), Some(date(2026, 10, 26)));
+        assert_eq!(parse_date("+3 days", today()), Some(date(2026, 10, 22)));
+        assert_eq!(parse_date("-2w", today()), Some(date(2026, 10, 5)));
+        assert_eq!(parse_date("- 1 week", today()), Some(date(2026, 10, 12)));
+        assert_eq!(offset(today(), true, "0d"), Some(today()));
+    }
+
+    #[test]
+    fn month_offsets_clamp_to_the_month_end() {
+        assert_eq!(parse_date("+1m", date(2026, 1, 31)), Some(date(2026, 2, 28)));
+        assert_eq!(parse_date("+1m", date(2028, 1, 31)), Some(date(2028, 2, 29)));
+        assert_eq!(parse_date("-1m", date(2026, 3, 31)), Some(date(2026, 2, 28)));
+        assert_eq!(parse_date("+2 months", date(2026, 12, 31)), Some(date(2027, 2, 28)));
+        assert_eq!(parse_date("+1m", date(2026, 4, 30)), Some(date(2026, 5, 30)));
+    }
+
+    #[test]
+    fn weekdays_are_always_ahead() {
+        assert_eq!(parse_date("fri", today()), Some(date(2026, 10, 23)));
+        assert_eq!(parse_date("next friday", today()), Some(date(2026, 10, 23)));
+        assert_eq!(parse_date("mon", today()), Some(date(2026, 10, 26)));
+        assert_eq!(parse_date("next monday", today()), Some(date(2026, 10, 26)));
+        assert_eq!(parse_date("sun", today()), Some(date(2026, 10, 25)));
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        for input in [
+            "", "+", "-", "+d", "+x", "+1y", "+-1d", "1d", "2026-13-01", "2026-02-30", "next",
+            "next fortnight", "someday",
+        ] {
+            assert_eq!(parse_date(input, today()), None, "{input:?}");
+        }
+        assert_eq!(offset(today(), false, "99999999999d"), None);
+    }
+}
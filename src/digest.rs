@@ -0,0 +1,412 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+use crate::habit::{week_start, Encouragement, Habit, HabitPattern};
+use crate::notifications::NotificationTrigger;
+use crate::sinks::{self, Notice, SinkConfig};
+use crate::storage;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum DigestDelivery {
+    /// Shown as a popup the first time the TUI is opened in a new period.
+    Tui,
+    /// Sent through the configured notification sinks.
+    Notification,
+    /// Written to `digests/` in the config directory.
+    Markdown,
+}
+
+/// The `[digest]` table in `notification.toml`.
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DigestSettings {
+    #[serde(default = "default_true")]
+    pub weekly: bool,
+    #[serde(default)]
+    pub monthly: bool,
+    #[serde(default = "default_delivery")]
+    pub delivery: Vec<DigestDelivery>,
+}
+
+impl Default for DigestSettings {
+    fn default() -> Self {
+        DigestSettings {
+            weekly: true,
+            monthly: false,
+            delivery: default_delivery(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_delivery() -> Vec<DigestDelivery> {
+    vec![DigestDelivery::Tui]
+}
+
+/// Which periods have already been delivered, so a digest only goes out once.
+#[derive(Serialize, Deserialize, Clone, Default)]
+pub struct DigestState {
+    pub weekly_sent: Option<NaiveDate>,
+    pub weekly_shown: Option<NaiveDate>,
+    pub monthly_sent: Option<NaiveDate>,
+    pub monthly_shown: Option<NaiveDate>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum DigestPeriod {
+    Week,
+    Month,
+}
+
+impl DigestPeriod {
+    /// The most recently finished period before `today`, as (start, end).
+    pub fn previous(&self, today: NaiveDate) -> (NaiveDate, NaiveDate) {
+        match self {
+            DigestPeriod::Week => {
+                let start = week_start(today) - Duration::days(7);
+                (start, start + Duration::days(6))
+            }
+            DigestPeriod::Month => {
+                let end = today.with_day(1).unwrap() - Duration::days(1);
+                (end.with_day(1).unwrap(), end)
+            }
+        }
+    }
+
+    fn noun(&self) -> &'static str {
+        match self {
+            DigestPeriod::Week => "week",
+            DigestPeriod::Month => "month",
+        }
+    }
+}
+
+pub struct TierChange {
+    pub name: String,
+    pub from: HabitPattern,
+    pub to: HabitPattern,
+}
+
+pub struct Digest {
+    pub period: DigestPeriod,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    pub completed: usize,
+    pub possible: usize,
+    pub tier_changes: Vec<TierChange>,
+    pub most_improved: Option<(String, i32)>,
+    pub on_holiday: Vec<String>,
+    pub encouragement: Encouragement,
+}
+
+impl Digest {
+    /// Builds the digest for [start, end]. Tiers are compared as they stood
+    /// the day before `start` and at `end`, using the Stats tab's logic.
+    pub fn build(period: DigestPeriod, start: NaiveDate, end: NaiveDate, habits: &[Habit]) -> Self {
+        let before = start - Duration::days(1);
+        // Habits archived before the period began have nothing to report.
+        let tracked: Vec<&Habit> = habits
+            .iter()
+            .filter(|h| h.created <= end && h.archived.is_none_or(|a| a > start))
+            .collect();
+
+        let (completed, possible) = tracked
+            .iter()
+            .map(|h| h.tally(start, end))
+            .fold((0, 0), |(done, due), (d, p)| (done + d, due + p));
+
+        let tier_changes = tracked
+            .iter()
+            .filter(|h| h.created < start)
+            .filter_map(|h| {
+                let (from, to) = (h.pattern_on(before), h.pattern_on(end));
+                (from != to).then(|| TierChange {
+                    name: h.name.clone(),
+                    from,
+                    to,
+                })
+            })
+            .collect();
+
+        let most_improved = tracked
+            .iter()
+            .filter(|h| h.created < start)
+            .map(|h| (h, h.raw_pattern_on(end) - h.raw_pattern_on(before)))
+            .filter(|(_, gain)| *gain > 0)
+            .max_by_key(|(_, gain)| *gain)
+            .map(|(h, gain)| (h.name.clone(), gain));
+
+        // Holidays taken while the habit was being tracked.
+        let on_holiday = tracked
+            .iter()
+            .filter(|h| {
+                (0..=(end - start).num_days())
+                    .map(|i| start + Duration::days(i))
+                    .any(|d| d >= h.created && h.tracked_on(d) && h.is_on_holiday(d))
+            })
+            .map(|h| h.name.clone())
+            .collect();
+
+        let owned: Vec<Habit> = tracked.into_iter().cloned().collect();
+        Digest {
+            period,
+            start,
+            end,
+            completed,
+            possible,
+            tier_changes,
+            most_improved,
+            on_holiday,
+            encouragement: Encouragement::for_habits(&owned, end),
+        }
+    }
+
+    pub fn title(&self) -> String {
+        match self.period {
+            DigestPeriod::Week => format!(
+                "Weekly reflection · {} – {}",
+                self.start.format("%b %-d"),
+                self.end.format("%b %-d, %Y")
+            ),
+            DigestPeriod::Month => format!("Monthly reflection · {}", self.start.format("%B %Y")),
+        }
+    }
+
+    fn percent(&self) -> f32 {
+        if self.possible == 0 {
+            return 0.0;
+        }
+        100.0 * self.completed as f32 / self.possible as f32
+    }
+
+    /// One-paragraph version for notification sinks.
+    pub fn summary(&self) -> String {
+        let mut text = format!(
+            "Last {}: {}/{} check-ins ({:.0}%), {} tier change(s).",
+            self.period.noun(),
+            self.completed,
+            self.possible,
+            self.percent(),
+            self.tier_changes.len()
+        );
+        if let Some((name, _)) = &self.most_improved {
+            text.push_str(&format!(" Most improved: {name}."));
+        }
+        text.push(' ');
+        text.push_str(self.encouragement.message());
+        text
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!(
+                "Checked in {} of {} times ({:.0}%).",
+                self.completed,
+                self.possible,
+                self.percent()
+            ),
+            String::new(),
+            "## Tier changes".to_string(),
+        ];
+        if self.tier_changes.is_empty() {
+            lines.push(format!("- No tier changes — a steady {}.", self.period.noun()));
+        }
+        for change in &self.tier_changes {
+            lines.push(format!("- {}: {} → {}", change.name, change.from, change.to));
+        }
+
+        lines.push(String::new());
+        lines.push("## Most improved".to_string());
+        match &self.most_improved {
+            Some((name, gain)) => lines.push(format!("- {name} (+{gain})")),
+            None => lines.push("- Nothing moved up this time, and that's okay.".to_string()),
+        }
+
+        if !self.on_holiday.is_empty() {
+            lines.push(String::new());
+            lines.push("## On holiday".to_string());
+            for name in &self.on_holiday {
+                lines.push(format!("- {name}"));
+            }
+        }
+
+        lines.push(String::new());
+        lines.push(format!("> {}", self.encouragement.message()));
+        lines
+    }
+
+    pub fn to_markdown(&self) -> String {
+        format!("# {}\n\n{}\n", self.title(), self.lines().join("\n"))
+    }
+
+    /// File name for the Markdown copy, e.g. `2026-W41.md` or `2026-10.md`.
+    pub fn file_name(&self) -> String {
+        match self.period {
+            DigestPeriod::Week => {
+                let week = self.start.iso_week();
+                format!("{}-W{:02}.md", week.year(), week.week())
+            }
+            DigestPeriod::Month => format!("{}.md", self.start.format("%Y-%m")),
+        }
+    }
+}
+
+/// What [`deliver_due`] did: the digest to show in the TUI, if one is due
+/// there, and one message per channel that failed.
+#[derive(Default)]
+pub struct Delivery {
+    pub shown: Option<Digest>,
+    pub errors: Vec<String>,
+}
+
+/// Held from loading the digest state to saving it, so the launch thread
+/// and the scheduler can't both find a digest unsent and send it twice.
+static DELIVERING: Mutex<()> = Mutex::new(());
+
+/// Sends digests that are due through the notification and Markdown
+/// channels, and returns the digest to show in the TUI if one is due there.
+pub fn deliver_due(
+    settings: &DigestSettings,
+    sinks: &[SinkConfig],
+    habits: &[Habit],
+    today: NaiveDate,
+    in_tui: bool,
+) -> Delivery {
+    let mut delivery = Delivery::default();
+    if habits.is_empty() {
+        return delivery;
+    }
+    let _guard = DELIVERING.lock().unwrap_or_else(|e| e.into_inner());
+    let mut state = storage::load_digest_state().unwrap_or_default();
+
+    let periods = [
+        (DigestPeriod::Week, settings.weekly),
+        (DigestPeriod::Month, settings.monthly),
+    ];
+    for (period, enabled) in periods {
+        if !enabled {
+            continue;
+        }
+        let (start, end) = period.previous(today);
+        if habits.iter().all(|h| h.created > end) {
+            continue;
+        }
+        let (sent, seen) = match period {
+            DigestPeriod::Week => (&mut state.weekly_sent, &mut state.weekly_shown),
+            DigestPeriod::Month => (&mut state.monthly_sent, &mut state.monthly_shown),
+        };
+        let digest = Digest::build(period, start, end, habits);
+
+        if *sent != Some(start) {
+            if settings.delivery.contains(&DigestDelivery::Notification) {
+                let summary = digest.summary();
+                let errors = sinks::dispatch(
+                    sinks,
+                    &Notice {
+                        title: &digest.title(),
+                        message: &summary,
+                        trigger: NotificationTrigger::Digest,
+                        done: digest.completed,
+                        total: digest.possible,
+                    },
+                );
+                delivery.errors.extend(errors);
+            }
+            if settings.delivery.contains(&DigestDelivery::Markdown) {
+                if let Err(e) =
+                    storage::save_digest_markdown(&digest.file_name(), &digest.to_markdown())
+                {
+                    delivery.errors.push(format!("markdown: {e}"));
+                }
+            }
+            *sent = Some(start);
+        }
+
+        // One popup per launch; a monthly digest due the same day waits for
+        // the next launch.
+        if in_tui
+            && delivery.shown.is_none()
+            && *seen != Some(start)
+            && settings.delivery.contains(&DigestDelivery::Tui)
+        {
+            *seen = Some(start);
+            delivery.shown = Some(digest);
+        }
+    }
+
+    let _ = storage::save_digest_state(&state);
+    delivery
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::habit::HabitType;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    /// Monday to Sunday.
+    fn week() -> (NaiveDate, NaiveDate) {
+        (date(10, 12), date(10, 18))
+    }
+
+    fn habit(name: &str, habit_type: HabitType) -> Habit {
+        Habit {
+            name: name.to_string(),
+            habit_type,
+            created: date(9, 1),
+            ..Habit::default()
+        }
+    }
+
+    fn build(habits: &[Habit]) -> Digest {
+        let (start, end) = week();
+        Digest::build(DigestPeriod::Week, start, end, habits)
+    }
+
+    #[test]
+    fn archived_build_habit_stops_counting() {
+        let mut run = habit("Run", HabitType::Build);
+        for day in 12..=14 {
+            run.toggle_complete(date(10, day));
+        }
+        run.archived = Some(date(10, 15));
+        let digest = build(&[run]);
+        assert_eq!((digest.completed, digest.possible), (3, 3));
+    }
+
+    #[test]
+    fn archived_avoid_habit_is_not_clean_after_archiving() {
+        let mut snacks = habit("Snacks", HabitType::Avoid);
+        snacks.archived = Some(date(10, 15));
+        let digest = build(&[snacks]);
+        assert_eq!((digest.completed, digest.possible), (3, 3));
+    }
+
+    #[test]
+    fn holidays_and_late_creation_are_left_out() {
+        let mut read = habit("Read", HabitType::Build);
+        read.created = date(10, 14);
+        read.add_holiday(date(10, 16), date(10, 17), None);
+        read.toggle_complete(date(10, 16));
+        read.toggle_complete(date(10, 18));
+        let digest = build(&[read]);
+        assert_eq!((digest.completed, digest.possible), (1, 3));
+        assert_eq!(digest.on_holiday, ["Read"]);
+    }
+
+    #[test]
+    fn habits_archived_before_the_period_are_skipped() {
+        let mut old = habit("Old", HabitType::Build);
+        old.archived = Some(date(10, 12));
+        let digest = build(&[old, habit("New", HabitType::Build)]);
+        assert_eq!((digest.completed, digest.possible), (0, 7));
+    }
+}
@@ -0,0 +1,172 @@
+use chrono::{Duration, NaiveDate};
+
+use crate::habit::{Habit, HabitType};
+
+/// One row per habit per tracked day up to `today`, for spreadsheets:
+/// `habit,type,tags,date,status,slips`. Status is done/missed for build
+/// habits, clean/slipped for avoid habits, or holiday. A check-in or slip
+/// recorded outside the tracked days (before `created`, or from the day
+/// the habit was archived) gets a row too, so nothing logged goes missing.
+pub fn habits_csv(habits: &[Habit], today: NaiveDate) -> String {
+    let mut out = String::from("habit,type,tags,date,status,slips\n");
+    for habit in habits {
+        let kind = match habit.habit_type {
+            HabitType::Build => "build",
+            HabitType::Avoid => "avoid",
+        };
+        let end = habit
+            .archived
+            .map_or(today, |since| (since - Duration::days(1)).min(today));
+        let tracked = habit.created..=end;
+        let logged: Vec<NaiveDate> = match habit.habit_type {
+            HabitType::Build => habit.days_completed.iter().copied().collect(),
+            HabitType::Avoid => habit.slips.iter().map(|s| s.date).collect(),
+        };
+        let mut dates: Vec<NaiveDate> = habit
+            .created
+            .iter_days()
+            .take_while(|d| *d <= end)
+            .chain(logged.into_iter().filter(|d| !tracked.contains(d) && *d <= today))
+            .collect();
+        dates.sort();
+        dates.dedup();
+        let tags = habit.tags.join(";");
+        for date in dates {
+            let slips = habit.slip_count_on(date);
+            let status = match habit.habit_type {
+                HabitType::Build if !tracked.contains(&date) => "done",
+                HabitType::Avoid if !tracked.contains(&date) => "slipped",
+                HabitType::Build if habit.succeeded_on(date) => "done",
+                HabitType::Avoid if slips > 0 => "slipped",
+                _ if habit.is_on_holiday(date) => "holiday",
+                HabitType::Build => "missed",
+                HabitType::Avoid if habit.succeeded_on(date) => "clean",
+                // An unmarked day from before slips existed.
+                HabitType::Avoid => "slipped",
+            };
+            out.push_str(&format!(
+                "{},{kind},{},{date},{status},{slips}\n",
+                field(&habit.name),
+                field(&tags)
+            ));
+        }
+    }
+    out
+}
+
+/// Quotes a field when it holds a comma, quote or newline. One that a
+/// spreadsheet would read as a formula (starting with `=`, `+`, `-` or
+/// `@`) gets a leading `'` so it stays text.
+fn field(value: &str) -> String {
+    let value = if value.starts_with(['=', '+', '-', '@']) {
+        format!("'{value}")
+    } else {
+        value.to_string()
+    };
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::habit::Slip;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn rows(csv: &str) -> Vec<&str> {
+        csv.lines().skip(1).collect()
+    }
+
+    #[test]
+    fn field_quotes_separators_and_quotes() {
+        assert_eq!(field("Read"), "Read");
+        assert_eq!(field("Read, write"), "\"Read, write\"");
+        assert_eq!(field("The \"big\" one"), "\"The \"\"big\"\" one\"");
+        assert_eq!(field("two\nlines"), "\"two\nlines\"");
+    }
+
+    #[test]
+    fn field_defuses_formulas() {
+        assert_eq!(field("=SUM(A1:A9)"), "'=SUM(A1:A9)");
+        assert_eq!(field("+1 push-up"), "'+1 push-up");
+        assert_eq!(field("-5 minutes"), "'-5 minutes");
+        assert_eq!(field("@home"), "'@home");
+        assert_eq!(field("=A1,B1"), "\"'=A1,B1\"");
+        assert_eq!(field("Stretch - 5 min"), "Stretch - 5 min");
+    }
+
+    #[test]
+    fn build_rows_cover_every_tracked_day() {
+        let mut habit = Habit {
+            name: "Read".to_string(),
+            created: date(3, 1),
+            tags: vec!["mind".to_string(), "evening".to_string()],
+            ..Habit::default()
+        };
+        habit.toggle_complete(date(3, 2));
+        habit.add_holiday(date(3, 3), date(3, 3), None);
+        let csv = habits_csv(&[habit], date(3, 4));
+        assert_eq!(csv.lines().next(), Some("habit,type,tags,date,status,slips"));
+        assert_eq!(
+            rows(&csv),
+            [
+                "Read,build,mind;evening,2026-03-01,missed,0",
+                "Read,build,mind;evening,2026-03-02,done,0",
+                "Read,build,mind;evening,2026-03-03,holiday,0",
+                "Read,build,mind;evening,2026-03-04,missed,0",
+            ]
+        );
+    }
+
+    #[test]
+    fn avoid_rows_show_slips() {
+        let mut habit = Habit {
+            name: "Doomscroll".to_string(),
+            habit_type: HabitType::Avoid,
+            created: date(3, 1),
+            ..Habit::default()
+        };
+        habit.log_slip(Slip {
+            date: date(3, 2),
+            time: None,
+            count: 3,
+            trigger: None,
+        });
+        let csv = habits_csv(&[habit], date(3, 2));
+        assert_eq!(
+            rows(&csv),
+            [
+                "Doomscroll,avoid,,2026-03-01,clean,0",
+                "Doomscroll,avoid,,2026-03-02,slipped,3",
+            ]
+        );
+    }
+
+    #[test]
+    fn logged_days_outside_tracking_are_kept() {
+        let mut habit = Habit {
+            name: "Run".to_string(),
+            created: date(3, 2),
+            archived: Some(date(3, 3)),
+            ..Habit::default()
+        };
+        habit.toggle_complete(date(3, 1));
+        habit.toggle_complete(date(3, 3));
+        habit.toggle_complete(date(3, 9));
+        let csv = habits_csv(&[habit], date(3, 5));
+        assert_eq!(
+            rows(&csv),
+            [
+                "Run,build,,2026-03-01,done,0",
+                "Run,build,,2026-03-02,missed,0",
+                "Run,build,,2026-03-03,done,0",
+            ]
+        );
+    }
+}
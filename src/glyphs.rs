@@ -0,0 +1,135 @@
+use ratatui::symbols::{border, scrollbar};
+
+use crate::habit::{HabitPattern, HabitStatus};
+
+/// Every non-ASCII symbol the UI draws, so limited terminals and screen
+/// readers can get a plain-ASCII set instead.
+pub struct Glyphs {
+    pub done: &'static str,
+    pub missed: &'static str,
+    pub gauge_full: &'static str,
+    pub gauge_empty: &'static str,
+    pub selected: &'static str,
+    pub holiday: &'static str,
+    /// Holiday marker that fits in one week-grid cell.
+    pub holiday_cell: &'static str,
+    /// Shown instead of the holiday marker while a habit is paused.
+    pub paused: &'static str,
+    /// Group headings in the habit lists, folded and open.
+    pub folded: &'static str,
+    pub unfolded: &'static str,
+    pub left: &'static str,
+    pub right: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+    pub border: border::Set,
+    /// Drawn on a list's right edge once it holds more than fits.
+    pub scrollbar: scrollbar::Set,
+    /// Heatmap fill per bucket when cues are on: denser means more done.
+    pub shades: [char; 5],
+    /// Tier symbols when cues are on, from Chaotic to Mastered.
+    pub tiers: [&'static str; 5],
+    pub ascii: bool,
+}
+
+pub const UNICODE: Glyphs = Glyphs {
+    done: "✔",
+    missed: "✘",
+    gauge_full: "▰",
+    gauge_empty: "▱",
+    selected: "▶",
+    holiday: "🌴",
+    holiday_cell: "🌴",
+    paused: "⏸",
+    folded: "▸",
+    unfolded: "▾",
+    left: "←",
+    right: "→",
+    up: "↑",
+    down: "↓",
+    border: border::ROUNDED,
+    scrollbar: scrollbar::VERTICAL,
+    shades: ['·', '░', '▒', '▓', '█'],
+    tiers: ["○", "◔", "◑", "◕", "●"],
+    ascii: false,
+};
+
+pub const ASCII: Glyphs = Glyphs {
+    done: "+",
+    missed: "-",
+    gauge_full: "#",
+    gauge_empty: ".",
+    selected: ">",
+    holiday: "(holiday)",
+    holiday_cell: "h",
+    paused: "(paused)",
+    folded: "+",
+    unfolded: "-",
+    left: "Left",
+    right: "Right",
+    up: "Up",
+    down: "Down",
+    border: border::Set {
+        top_left: "+",
+        top_right: "+",
+        bottom_left: "+",
+        bottom_right: "+",
+        vertical_left: "|",
+        vertical_right: "|",
+        horizontal_top: "-",
+        horizontal_bottom: "-",
+    },
+    scrollbar: scrollbar::Set {
+        track: "|",
+        thumb: "#",
+        begin: "^",
+        end: "v",
+    },
+    shades: ['.', ':', '+', '*', '#'],
+    tiers: ["-", "~", "+", "*", "#"],
+    ascii: true,
+};
+
+impl Glyphs {
+    pub fn status(&self, status: &HabitStatus) -> &'static str {
+        match status {
+            HabitStatus::Complete => self.done,
+            HabitStatus::InComplete => self.missed,
+        }
+    }
+
+    pub fn tier(&self, pattern: HabitPattern) -> &'static str {
+        self.tiers[pattern as usize]
+    }
+
+    /// `text` with punctuation and arrows swapped for ASCII and emoji
+    /// dropped. Unchanged in Unicode mode.
+    pub fn plain(&self, text: &str) -> String {
+        if !self.ascii {
+            return text.to_string();
+        }
+        let mut out = String::with_capacity(text.len());
+        for c in text.chars() {
+            match c {
+                '–' | '—' | '·' => out.push('-'),
+                '→' => out.push_str("->"),
+                '…' => out.push_str("..."),
+                '•' => out.push('*'),
+                '✔' => out.push('+'),
+                '✘' => out.push('-'),
+                '⚠' => out.push('!'),
+                '▶' => out.push('>'),
+                '↑' => out.push_str("Up"),
+                '↓' => out.push_str("Down"),
+                '←' => out.push_str("Left"),
+                c if is_symbol(c) => {}
+                c => out.push(c),
+            }
+        }
+        out.trim().to_string()
+    }
+}
+
+fn is_symbol(c: char) -> bool {
+    matches!(c as u32, 0x2190..=0x2BFF | 0xFE00..=0xFE0F | 0x1F000..=0x1FAFF)
+}
@@ -1,6 +1,6 @@
 use std::{collections::HashSet, fmt};
 
-use chrono::{Duration, Local, NaiveDate};
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveTime, Timelike};
 use serde::{Deserialize, Serialize};
 
 /// "Today" shifted by `cutoff_hour` hours, so a habit's day can run past
@@ -9,27 +9,9 @@ pub fn today_with_cutoff(cutoff_hour: u32) -> NaiveDate {
     (Local::now().naive_local() - Duration::hours(cutoff_hour as i64)).date()
 }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
-pub enum Day {
-    Today,
-    Yesterday,
-}
-
-impl Day {
-    pub fn as_str(&self) -> &'static str {
-        match self {
-            Day::Today => "Today",
-            Day::Yesterday => "Yesterday",
-        }
-    }
-
-    pub fn resolve_date(&self, cutoff_hour: u32) -> NaiveDate {
-        let today = today_with_cutoff(cutoff_hour);
-        match self {
-            Day::Today => today,
-            Day::Yesterday => today - Duration::days(1),
-        }
-    }
+/// Monday of the week containing `date`.
+pub fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Duration::days(date.weekday().num_days_from_monday() as i64)
 }
 
 pub enum HabitStatus {
@@ -37,16 +19,8 @@ pub enum HabitStatus {
     InComplete,
 }
 
-impl fmt::Display for HabitStatus {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            HabitStatus::Complete => write!(f, "✔"),
-            HabitStatus::InComplete => write!(f, "✘"),
-        }
-    }
-}
-
-#[derive(PartialEq)]
+/// Ordered from weakest to strongest.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
 pub enum HabitPattern {
     Chaotic,
     Struggling,
@@ -67,7 +41,7 @@ impl fmt::Display for HabitPattern {
     }
 }
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum HabitType {
     Build,
     Avoid,
@@ -77,22 +51,103 @@ pub enum HabitType {
 pub struct HolidayRange {
     pub start: NaiveDate,
     pub end: NaiveDate,
+    /// What the break is for, e.g. "Trip to Lisbon".
+    #[serde(default)]
+    pub reason: Option<String>,
 }
 
 impl HolidayRange {
     fn contains(&self, date: NaiveDate) -> bool {
         date >= self.start && date <= self.end
     }
+
+    /// Whether any day of `start..=end` falls in this holiday.
+    pub fn overlaps(&self, start: NaiveDate, end: NaiveDate) -> bool {
+        self.start <= end && self.end >= start
+    }
+}
+
+/// A logged lapse on an avoid habit.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+pub struct Slip {
+    pub date: NaiveDate,
+    #[serde(default)]
+    pub time: Option<NaiveTime>,
+    #[serde(default = "default_slip_count")]
+    pub count: u32,
+    /// Short note on what set it off, e.g. "stress" or "boredom".
+    #[serde(default)]
+    pub trigger: Option<String>,
+}
+
+fn default_slip_count() -> u32 {
+    1
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeOfDay {
+    Morning,
+    Afternoon,
+    Evening,
+    Night,
+}
+
+impl TimeOfDay {
+    pub const ALL: [TimeOfDay; 4] = [
+        TimeOfDay::Morning,
+        TimeOfDay::Afternoon,
+        TimeOfDay::Evening,
+        TimeOfDay::Night,
+    ];
+
+    pub fn of(time: NaiveTime) -> Self {
+        match time.hour() {
+            5..=11 => TimeOfDay::Morning,
+            12..=16 => TimeOfDay::Afternoon,
+            17..=21 => TimeOfDay::Evening,
+            _ => TimeOfDay::Night,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeOfDay::Morning => "Morning",
+            TimeOfDay::Afternoon => "Afternoon",
+            TimeOfDay::Evening => "Evening",
+            TimeOfDay::Night => "Night",
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub struct Habit {
     pub name: String,
     pub habit_type: HabitType,
+    /// Check-ins for build habits. Avoid habits record `slips` instead;
+    /// marks left on one from before slips existed meant "avoided", and
+    /// are still read that way (see `avoided`).
     pub days_completed: HashSet<NaiveDate>,
     pub created: NaiveDate,
     #[serde(default)]
     pub holidays: Vec<HolidayRange>,
+    #[serde(default)]
+    pub slips: Vec<Slip>,
+    /// Free-form labels such as "health" or "work". The first one is the
+    /// habit's category, which the lists group by.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Paused since this day, with no end set yet. Counts as a holiday
+    /// until resumed.
+    #[serde(default)]
+    pub paused: Option<NaiveDate>,
+    /// Retired on this day: out of the lists and totals, but its history
+    /// stays on the heatmap and it can be restored.
+    #[serde(default)]
+    pub archived: Option<NaiveDate>,
+    /// When in the day the habit is meant to happen, if it has a set time.
+    #[serde(default)]
+    pub time_of_day: Option<TimeOfDay>,
 }
 
 impl Default for Habit {
@@ -103,22 +158,83 @@ impl Default for Habit {
             days_completed: HashSet::default(),
             created: NaiveDate::default(),
             holidays: Vec::new(),
+            slips: Vec::new(),
+            tags: Vec::new(),
+            paused: None,
+            archived: None,
+            time_of_day: None,
         }
     }
 }
 
 impl Habit {
-    pub fn check_status(&self, day: &Day, cutoff_hour: u32) -> HabitStatus {
-        let date = day.resolve_date(cutoff_hour);
-        if self.days_completed.contains(&date) {
+    /// Whether the habit counts as done on `date`: checked in for build
+    /// habits, slip-free for avoid habits. Days that haven't happened yet
+    /// never count.
+    pub fn completed_on(&self, date: NaiveDate, cutoff_hour: u32) -> bool {
+        date <= today_with_cutoff(cutoff_hour) && self.succeeded_on(date)
+    }
+
+    /// Like `completed_on`, for dates already known to be in the past.
+    pub fn succeeded_on(&self, date: NaiveDate) -> bool {
+        match self.habit_type {
+            HabitType::Build => date >= self.created && self.days_completed.contains(&date),
+            HabitType::Avoid => self.avoided(date, self.slipped_on(date), self.last_mark()),
+        }
+    }
+
+    /// The one rule for an avoid habit's day: tracked, not a holiday (those
+    /// are left out of the tier, like a build habit's), and no slip. Up to
+    /// `last_mark`, the last check mark from before slips existed, the
+    /// day also needs a mark, so old data reads as it did without being
+    /// rewritten.
+    fn avoided(&self, date: NaiveDate, slipped: bool, last_mark: Option<NaiveDate>) -> bool {
+        date >= self.created
+            && !slipped
+            && !self.is_on_holiday(date)
+            && last_mark.is_none_or(|last| date > last || self.days_completed.contains(&date))
+    }
+
+    fn last_mark(&self) -> Option<NaiveDate> {
+        self.days_completed.iter().max().copied()
+    }
+
+    pub fn check_status(&self, date: NaiveDate, cutoff_hour: u32) -> HabitStatus {
+        if self.completed_on(date, cutoff_hour) {
             HabitStatus::Complete
         } else {
             HabitStatus::InComplete
         }
     }
 
-    pub fn toggle_complete(&mut self, day: &Day, cutoff_hour: u32) {
-        let date = day.resolve_date(cutoff_hour);
+    pub fn category(&self) -> Option<&str> {
+        self.tags.first().map(String::as_str)
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    pub fn slipped_on(&self, date: NaiveDate) -> bool {
+        self.slips.iter().any(|s| s.date == date)
+    }
+
+    pub fn slip_count_on(&self, date: NaiveDate) -> u32 {
+        self.slips.iter().filter(|s| s.date == date).map(|s| s.count).sum()
+    }
+
+    pub fn log_slip(&mut self, slip: Slip) {
+        self.slips.push(slip);
+    }
+
+    /// Removes the most recently logged slip on `date`.
+    pub fn undo_slip(&mut self, date: NaiveDate) {
+        if let Some(pos) = self.slips.iter().rposition(|s| s.date == date) {
+            self.slips.remove(pos);
+        }
+    }
+
+    pub fn toggle_complete(&mut self, date: NaiveDate) {
         if !self.days_completed.insert(date) {
             self.days_completed.remove(&date);
         }
@@ -126,73 +242,203 @@ impl Habit {
 
     pub fn reset(&mut self, cutoff_hour: u32) {
         self.days_completed.clear();
+        self.slips.clear();
         self.created = today_with_cutoff(cutoff_hour);
     }
 
-    pub fn add_holiday(&mut self, start: NaiveDate, end: NaiveDate) {
+    /// Adds a holiday, merged with any it overlaps.
+    pub fn add_holiday(&mut self, start: NaiveDate, end: NaiveDate, reason: Option<String>) {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
-        self.holidays.push(HolidayRange { start, end });
+        self.holidays.push(HolidayRange { start, end, reason });
+        self.normalize_holidays();
+    }
+
+    /// Sorts the holidays by start and merges overlapping ones, keeping
+    /// both reasons, so no day is in two ranges. Back-to-back holidays stay
+    /// apart, each with its own reason.
+    pub fn normalize_holidays(&mut self) {
+        let mut ranges = std::mem::take(&mut self.holidays);
+        ranges.sort_by_key(|h| (h.start, h.end));
+        for range in ranges {
+            match self.holidays.last_mut() {
+                Some(last) if range.start <= last.end => {
+                    last.end = last.end.max(range.end);
+                    last.reason = match (last.reason.take(), range.reason) {
+                        (Some(a), Some(b)) if a != b => Some(format!("{a}; {b}")),
+                        (a, b) => a.or(b),
+                    };
+                }
+                _ => self.holidays.push(range),
+            }
+        }
     }
 
     pub fn is_on_holiday(&self, date: NaiveDate) -> bool {
         self.holidays.iter().any(|h| h.contains(date))
+            || self.paused.is_some_and(|since| date >= since)
+    }
+
+    /// Whether the habit was still being tracked on `date`, i.e. not yet
+    /// archived.
+    pub fn tracked_on(&self, date: NaiveDate) -> bool {
+        self.archived.is_none_or(|since| date < since)
+    }
+
+    /// Whether `date` is one the habit owes a result for: created by then,
+    /// not yet archived and not on a holiday. Totals such as "done of
+    /// possible" count these days only.
+    pub fn due_on(&self, date: NaiveDate) -> bool {
+        date >= self.created && self.tracked_on(date) && !self.is_on_holiday(date)
+    }
+
+    /// How many days from `from` to `to` the habit succeeded on, and how
+    /// many it was due: the "done of possible" behind every total. Pass a
+    /// `to` no later than today.
+    pub fn tally(&self, from: NaiveDate, to: NaiveDate) -> (usize, usize) {
+        let first = from.max(self.created);
+        (0..=(to - first).num_days())
+            .map(|i| first + Duration::days(i))
+            .filter(|d| self.due_on(*d))
+            .fold((0, 0), |(done, due), d| (done + self.succeeded_on(d) as usize, due + 1))
     }
 
-    /// Holiday days already elapsed, clipped to [created, today] so future or
-    /// pre-creation holiday entries can't inflate the count.
-    fn holiday_days_elapsed(&self, cutoff_hour: u32) -> i64 {
-        let today = today_with_cutoff(cutoff_hour);
-        self.holidays
+    /// Pauses from `today` on, or resumes a paused habit. The pause becomes
+    /// an ordinary holiday ending yesterday, so the history reads the same
+    /// either way.
+    pub fn toggle_pause(&mut self, today: NaiveDate) {
+        match self.paused.take() {
+            Some(since) => self.close_break(since, today),
+            None => self.paused = Some(today),
+        }
+    }
+
+    pub fn archive(&mut self, today: NaiveDate) {
+        if let Some(since) = self.paused.take() {
+            self.close_break(since, today);
+        }
+        self.archived = Some(today);
+    }
+
+    /// Brings an archived habit back. The time away is kept as a holiday
+    /// so it doesn't count against the habit's tier.
+    pub fn restore(&mut self, today: NaiveDate) {
+        if let Some(since) = self.archived.take() {
+            self.close_break(since, today);
+        }
+    }
+
+    /// Records [since, today) as a holiday, if it covers any days.
+    fn close_break(&mut self, since: NaiveDate, today: NaiveDate) {
+        if since < today {
+            self.add_holiday(since, today - Duration::days(1), None);
+        }
+    }
+
+    /// Holiday days already elapsed by `as_of`, clipped to [created, as_of] so
+    /// future or pre-creation holiday entries can't inflate the count. A
+    /// day in several ranges (or in a holiday and the current pause)
+    /// counts once.
+    fn holiday_days_elapsed(&self, as_of: NaiveDate) -> i64 {
+        let paused = self.paused.map(|start| (start, as_of));
+        let mut ranges: Vec<(NaiveDate, NaiveDate)> = self
+            .holidays
             .iter()
-            .map(|h| {
-                let start = h.start.max(self.created);
-                let end = h.end.min(today);
-                if end >= start {
-                    (end - start).num_days() + 1
-                } else {
-                    0
-                }
-            })
-            .sum()
+            .map(|h| (h.start, h.end))
+            .chain(paused)
+            .map(|(start, end)| (start.max(self.created), end.min(as_of)))
+            .filter(|(start, end)| end >= start)
+            .collect();
+        ranges.sort();
+        let mut days = 0;
+        let mut counted_until: Option<NaiveDate> = None;
+        for (start, end) in ranges {
+            let start = counted_until.map_or(start, |until| start.max(until + Duration::days(1)));
+            if end >= start {
+                days += (end - start).num_days() + 1;
+                counted_until = Some(end);
+            }
+        }
+        days
     }
 
-    fn days_since_creation(&self, cutoff_hour: u32) -> i64 {
-        let raw_days = today_with_cutoff(cutoff_hour)
-            .signed_duration_since(self.created)
-            .num_days();
-        (raw_days - self.holiday_days_elapsed(cutoff_hour)).max(1)
+    fn days_since_creation(&self, as_of: NaiveDate) -> i64 {
+        let raw_days = as_of.signed_duration_since(self.created).num_days();
+        (raw_days - self.holiday_days_elapsed(as_of)).max(1)
+    }
+
+    fn check_ins_until(&self, as_of: NaiveDate) -> usize {
+        match self.habit_type {
+            HabitType::Build => self
+                .days_completed
+                .iter()
+                .filter(|d| **d >= self.created && **d <= as_of)
+                .count(),
+            HabitType::Avoid => {
+                let slipped: HashSet<NaiveDate> = self.slips.iter().map(|s| s.date).collect();
+                let last_mark = self.last_mark();
+                self.created
+                    .iter_days()
+                    .take_while(|d| *d <= as_of)
+                    .filter(|d| self.avoided(*d, slipped.contains(d), last_mark))
+                    .count()
+            }
+        }
     }
 
     pub fn check_raw_pattern(&self, cutoff_hour: u32) -> i32 {
-        let days = self.days_since_creation(cutoff_hour);
-        let check_ins = self.days_completed.len();
-        ((check_ins as f32 / days as f32 * 5.0).round() as i32)
-            .max(0)
-            .min(5)
+        self.raw_pattern_on(today_with_cutoff(cutoff_hour))
+    }
+
+    /// The raw 0–5 score as it stood at the end of `as_of`.
+    pub fn raw_pattern_on(&self, as_of: NaiveDate) -> i32 {
+        let days = self.days_since_creation(as_of);
+        let check_ins = self.check_ins_until(as_of);
+        ((check_ins as f32 / days as f32 * 5.0).round() as i32).clamp(0, 5)
     }
 
     pub fn check_pattern(&self, cutoff_hour: u32) -> HabitPattern {
-        let days = self.days_since_creation(cutoff_hour);
-        let check_ins = self.days_completed.len();
-        let pattern = ((check_ins as f32 / days as f32 * 5.0).round() as u32)
-            .max(1)
-            .min(5);
-        match pattern {
-            2 => HabitPattern::Struggling,
-            3 => HabitPattern::Developing,
-            4 => HabitPattern::Established,
-            5 => {
-                if days < 7 {
-                    HabitPattern::Developing
-                } else {
-                    HabitPattern::Mastered
-                }
+        self.pattern_on(today_with_cutoff(cutoff_hour))
+    }
+
+    /// The tier as it stood at the end of `as_of`, so past periods can be
+    /// compared using the same numbers the Stats tab shows.
+    pub fn pattern_on(&self, as_of: NaiveDate) -> HabitPattern {
+        let days = self.days_since_creation(as_of);
+        tier(self.check_ins_until(as_of), days, days)
+    }
+}
+
+/// The tier for `check_ins` out of `days`. Mastery needs at least a week
+/// behind it, counted as `longest` days.
+fn tier(check_ins: usize, days: i64, longest: i64) -> HabitPattern {
+    let pattern = ((check_ins as f32 / days as f32 * 5.0).round() as u32).clamp(1, 5);
+    match pattern {
+        2 => HabitPattern::Struggling,
+        3 => HabitPattern::Developing,
+        4 => HabitPattern::Established,
+        5 => {
+            if longest < 7 {
+                HabitPattern::Developing
+            } else {
+                HabitPattern::Mastered
             }
-            _ => HabitPattern::Chaotic,
         }
+        _ => HabitPattern::Chaotic,
     }
 }
 
+/// One tier for a group of habits, pooling their check-ins and days so a
+/// tag reads on the same scale as a single habit.
+pub fn group_pattern(habits: &[&Habit], as_of: NaiveDate) -> HabitPattern {
+    let days: Vec<i64> = habits.iter().map(|h| h.days_since_creation(as_of)).collect();
+    let check_ins = habits.iter().map(|h| h.check_ins_until(as_of)).sum();
+    tier(
+        check_ins,
+        days.iter().sum::<i64>().max(1),
+        days.iter().copied().max().unwrap_or(0),
+    )
+}
+
 /// Find the habit with the highest raw pattern score from a slice.
 pub fn find_best_habit(habits: &[Habit], cutoff_hour: u32) -> Option<&Habit> {
     habits.iter().max_by_key(|h| h.check_raw_pattern(cutoff_hour))
@@ -202,3 +448,195 @@ pub fn find_best_habit(habits: &[Habit], cutoff_hour: u32) -> Option<&Habit> {
 pub fn find_worst_habit(habits: &[Habit], cutoff_hour: u32) -> Option<&Habit> {
     habits.iter().min_by_key(|h| h.check_raw_pattern(cutoff_hour))
 }
+
+/// How often each slip trigger was logged across `habits`, most common
+/// first. Untagged slips are left out.
+pub fn slip_triggers(habits: &[Habit]) -> Vec<(String, u32)> {
+    let mut counts: Vec<(String, u32)> = Vec::new();
+    for slip in habits.iter().flat_map(|h| &h.slips) {
+        let Some(trigger) = slip.trigger.as_deref().map(str::trim).filter(|t| !t.is_empty()) else {
+            continue;
+        };
+        let trigger = trigger.to_lowercase();
+        match counts.iter_mut().find(|(t, _)| *t == trigger) {
+            Some((_, n)) => *n += slip.count,
+            None => counts.push((trigger, slip.count)),
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts
+}
+
+/// Slips per time of day across `habits`, for slips logged with a time.
+pub fn slip_times(habits: &[Habit]) -> [(TimeOfDay, u32); 4] {
+    TimeOfDay::ALL.map(|period| {
+        let count = habits
+            .iter()
+            .flat_map(|h| &h.slips)
+            .filter(|s| s.time.map(TimeOfDay::of) == Some(period))
+            .map(|s| s.count)
+            .sum();
+        (period, count)
+    })
+}
+
+/// The closing line shown under the Stats tab and in reflection digests,
+/// picked from how many habits are Established or better.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Encouragement {
+    Strong,
+    Steady,
+    Gentle,
+}
+
+impl Encouragement {
+    pub fn for_habits(habits: &[Habit], as_of: NaiveDate) -> Self {
+        if habits.is_empty() {
+            return Encouragement::Gentle;
+        }
+        let strong = habits
+            .iter()
+            .filter(|h| matches!(h.pattern_on(as_of), HabitPattern::Established | HabitPattern::Mastered))
+            .count();
+        let ratio = strong as f32 / habits.len() as f32;
+        if ratio >= 0.6 {
+            Encouragement::Strong
+        } else if ratio >= 0.3 {
+            Encouragement::Steady
+        } else {
+            Encouragement::Gentle
+        }
+    }
+
+    pub fn message(&self) -> &'static str {
+        match self {
+            Encouragement::Strong => "Strong patterns forming — keep flowing 🌊",
+            Encouragement::Steady => "Progress isn't a straight line — you're doing fine",
+            Encouragement::Gentle => "Patterns take time to form — show up when you can, no pressure",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, month, day).unwrap()
+    }
+
+    fn avoid_habit() -> Habit {
+        Habit {
+            name: "Late snacks".to_string(),
+            habit_type: HabitType::Avoid,
+            created: date(1, 1),
+            ..Habit::default()
+        }
+    }
+
+    fn slip(on: NaiveDate) -> Slip {
+        Slip {
+            date: on,
+            time: None,
+            count: 1,
+            trigger: None,
+        }
+    }
+
+    #[test]
+    fn tally_counts_only_due_days() {
+        // Created Wednesday, holiday Friday, archived Sunday.
+        let mut habit = Habit {
+            name: "Read".to_string(),
+            created: date(10, 14),
+            days_completed: [date(10, 12), date(10, 14), date(10, 16), date(10, 17)].into(),
+            ..Habit::default()
+        };
+        habit.add_holiday(date(10, 16), date(10, 16), None);
+        habit.archive(date(10, 18));
+        assert_eq!(habit.tally(date(10, 12), date(10, 18)), (2, 3));
+        // Up to Thursday, as when the week isn't over yet.
+        assert_eq!(habit.tally(date(10, 12), date(10, 15)), (1, 2));
+        assert_eq!(habit.tally(date(10, 12), date(10, 13)), (0, 0));
+
+        let mut habit = avoid_habit();
+        habit.log_slip(slip(date(10, 13)));
+        assert_eq!(habit.tally(date(10, 12), date(10, 18)), (6, 7));
+    }
+
+    #[test]
+    fn legacy_avoid_marks_are_read_up_to_the_last_one() {
+        let mut habit = avoid_habit();
+        habit.days_completed = [date(1, 1), date(1, 3)].into();
+        assert!(habit.succeeded_on(date(1, 1)));
+        assert!(!habit.succeeded_on(date(1, 2)));
+        assert!(habit.succeeded_on(date(1, 3)));
+        assert!(habit.succeeded_on(date(1, 4)));
+        habit.log_slip(slip(date(1, 4)));
+        assert!(!habit.succeeded_on(date(1, 4)));
+    }
+
+    #[test]
+    fn avoid_check_ins_follow_succeeded_on() {
+        let mut habit = avoid_habit();
+        habit.days_completed = [date(1, 1), date(1, 3)].into();
+        habit.log_slip(slip(date(1, 7)));
+        habit.add_holiday(date(1, 5), date(1, 6), None);
+        let as_of = date(1, 10);
+        let expected = habit
+            .created
+            .iter_days()
+            .take_while(|d| *d <= as_of)
+            .filter(|d| habit.succeeded_on(*d))
+            .count();
+        assert_eq!(habit.check_ins_until(as_of), expected);
+        assert_eq!(expected, 6);
+        assert!(!habit.succeeded_on(date(1, 5)));
+    }
+
+    #[test]
+    fn build_check_ins_before_creation_are_ignored() {
+        let mut habit = Habit {
+            created: date(1, 5),
+            ..Habit::default()
+        };
+        habit.days_completed = [date(1, 1), date(1, 5), date(1, 6)].into();
+        assert!(!habit.succeeded_on(date(1, 1)));
+        assert_eq!(habit.check_ins_until(date(1, 10)), 2);
+    }
+
+    #[test]
+    fn only_overlapping_holidays_merge() {
+        let mut habit = avoid_habit();
+        habit.add_holiday(date(2, 1), date(2, 5), Some("Trip".to_string()));
+        habit.add_holiday(date(2, 6), date(2, 8), Some("Flu".to_string()));
+        assert_eq!(habit.holidays.len(), 2);
+
+        habit.add_holiday(date(2, 4), date(2, 2), Some("Visit".to_string()));
+        habit.add_holiday(date(2, 8), date(2, 10), None);
+        let ranges: Vec<_> = habit
+            .holidays
+            .iter()
+            .map(|h| (h.start, h.end, h.reason.as_deref()))
+            .collect();
+        assert_eq!(
+            ranges,
+            [
+                (date(2, 1), date(2, 5), Some("Trip; Visit")),
+                (date(2, 6), date(2, 10), Some("Flu")),
+            ]
+        );
+    }
+
+    #[test]
+    fn changing_type_keeps_history() {
+        let mut habit = avoid_habit();
+        habit.log_slip(slip(date(1, 2)));
+        habit.habit_type = HabitType::Build;
+        habit.toggle_complete(date(1, 3));
+        habit.habit_type = HabitType::Avoid;
+        assert_eq!(habit.slips.len(), 1);
+        assert!(habit.succeeded_on(date(1, 3)));
+        assert!(!habit.succeeded_on(date(1, 2)));
+    }
+}
@@ -1,159 +1,614 @@
 use std::io::Result;
+use std::time::{Duration, Instant};
 
 use ratatui::{
     backend::Backend,
-    crossterm::event::{self, Event, KeyCode},
+    crossterm::event::{
+        self, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    },
+    layout::{Position, Rect},
     Terminal,
 };
 
 use crate::{
-    app::{App, CurrentScreen, ScreenMode},
+    app::{App, CurrentScreen, HolidayField, ListRow, ScreenMode, DOUBLE_CLICK},
+    habit::HabitType,
+    keymap::{Action, KeyMode},
+    text_field::Edit,
     ui::ui,
 };
 
+/// How long the loop waits for input before checking the clock again.
+const TICK: Duration = Duration::from_millis(500);
+
 pub fn run_app<B: Backend>(terminal: &mut Terminal<B>, app: &mut App) -> Result<()> {
+    // Drawn after input, a new minute or day, and config changes, rather
+    // than on every tick.
+    let mut redraw = true;
     loop {
-        terminal.draw(|f| ui(f, app))?;
+        redraw |= app.reload_config_if_changed();
+        redraw |= app.tick();
+        if redraw {
+            terminal.draw(|f| ui(f, app))?;
+            redraw = false;
+        }
 
-        if let Event::Key(key) = event::read()? {
-            if key.kind == event::KeyEventKind::Release {
-                continue;
+        if !event::poll(TICK)? {
+            continue;
+        }
+        redraw = true;
+        match event::read()? {
+            Event::Key(key) => {
+                if key.kind == event::KeyEventKind::Release {
+                    continue;
+                }
+                if handle_key(key, app) {
+                    break;
+                }
             }
+            Event::Mouse(mouse) => handle_mouse(mouse, app),
+            Event::Paste(text) => handle_paste(&text, app),
+            // Clears the screen too, so nothing of the old layout lingers.
+            Event::Resize(width, height) => terminal.resize(Rect::new(0, 0, width, height))?,
+            _ => {}
+        }
+    }
+    Ok(())
+}
 
-            if handle_global_keys(key.code, app) {
-                break;
-            }
+/// Pastes land in the focused text field in one go, with line breaks
+/// turned to spaces so a trailing newline doesn't submit.
+fn handle_paste(text: &str, app: &mut App) {
+    app.status = None;
+    if app.hitboxes.borrow().too_small {
+        return;
+    }
+    if let Some(field) = app.focused_field() {
+        let edit = field.insert_str(text);
+        app.field_edited(edit);
+    }
+}
 
-            match app.current_screen {
-                CurrentScreen::Today => handle_today_keys(key.code, app),
-                CurrentScreen::Stats => handle_stats_keys(key.code, app),
-                CurrentScreen::Heatmap => handle_heatmap_keys(key.code, app),
+/// Routes a key to the bindings of the current mode. Returns true to quit.
+fn handle_key(key: KeyEvent, app: &mut App) -> bool {
+    app.status = None;
+    // Keys typed at the "too small" message would act on a screen nobody
+    // can see, so only the global ones (quit, back, ...) get through.
+    if app.hitboxes.borrow().too_small {
+        return match app.keymap.action(KeyMode::Global, &key) {
+            Some(action) => handle_global_action(action, app),
+            None => false,
+        };
+    }
+    match app.screen_mode {
+        ScreenMode::Adding
+        | ScreenMode::Editing
+        | ScreenMode::Holiday
+        | ScreenMode::Slip
+        | ScreenMode::GoTo
+        | ScreenMode::Search
+        | ScreenMode::Command => {
+            handle_form_key(key, app);
+            false
+        }
+        ScreenMode::Deleting | ScreenMode::Reset => {
+            handle_confirm(key, app);
+            false
+        }
+        _ => {
+            if let Some(action) = app.keymap.action(KeyMode::Global, &key) {
+                return handle_global_action(action, app);
+            }
+            let mode = match (&app.screen_mode, app.current_screen) {
+                (ScreenMode::Digest | ScreenMode::Help, _) => KeyMode::Popup,
+                (ScreenMode::DayDetail, _) => KeyMode::Day,
+                (ScreenMode::Archive, _) => KeyMode::Archive,
+                (ScreenMode::Holidays, _) => KeyMode::Holidays,
+                (ScreenMode::Normal, CurrentScreen::Today) => KeyMode::Today,
+                (ScreenMode::Normal, CurrentScreen::Week) => KeyMode::Week,
+                (ScreenMode::Normal, CurrentScreen::Month) => KeyMode::Month,
+                (ScreenMode::Normal, CurrentScreen::Heatmap) => KeyMode::Heatmap,
+                _ => return false,
+            };
+            if let Some(action) = app.keymap.action(mode, &key) {
+                handle_action(action, app);
             }
+            false
         }
     }
-    Ok(())
 }
 
-fn handle_global_keys(code: KeyCode, app: &mut App) -> bool {
-    match code {
-        KeyCode::Char('q') => {
-            if let ScreenMode::Normal = app.screen_mode {
-                let _ = app.save_habits();
-                return true;
-            }
+fn handle_global_action(action: Action, app: &mut App) -> bool {
+    let normal = matches!(app.screen_mode, ScreenMode::Normal);
+    match action {
+        Action::Quit if normal => {
+            let _ = app.save_habits();
+            return true;
         }
-        KeyCode::Esc => {
-            if !matches!(app.screen_mode, ScreenMode::Normal) {
+        Action::Back => {
+            if !normal {
                 app.toggle_normal_mode();
             } else {
                 let _ = app.save_habits();
                 return true;
             }
         }
-        KeyCode::Char('?') => {
+        Action::Help => {
             if matches!(app.screen_mode, ScreenMode::Normal | ScreenMode::Help) {
                 app.toggle_help_mode();
             }
         }
+        Action::Inbox => {
+            if matches!(app.screen_mode, ScreenMode::Normal | ScreenMode::Inbox) {
+                app.toggle_inbox_mode();
+            }
+        }
+        Action::NextScreen if normal => app.toggle_page(),
+        Action::NextTag if normal => app.cycle_tag_filter(),
+        Action::Search if normal => app.toggle_search_mode(),
+        Action::Command if normal => app.toggle_command_mode(),
         _ => {}
     }
     false
 }
 
-fn handle_today_keys(code: KeyCode, app: &mut App) {
-    match app.screen_mode {
-        ScreenMode::Normal => handle_normal_mode(code, app),
-        ScreenMode::Adding => handle_text_input(code, app, false),
-        ScreenMode::Editing => handle_text_input(code, app, true),
-        ScreenMode::Deleting => handle_confirm(code, app, true),
-        ScreenMode::Reset => handle_confirm(code, app, false),
-        ScreenMode::Holiday => handle_holiday_input(code, app),
-        ScreenMode::Help => {}
+fn handle_action(action: Action, app: &mut App) {
+    // The week grid and day breakdown are one list of rows, build habits
+    // then avoid habits.
+    let rows = app.current_screen == CurrentScreen::Week
+        || matches!(app.screen_mode, ScreenMode::DayDetail);
+    // On the heatmap, days run down the columns and weeks across them.
+    let heatmap = app.current_screen == CurrentScreen::Heatmap
+        && matches!(app.screen_mode, ScreenMode::Normal);
+    // Editing a habit the filter or a folded group hides would be a surprise.
+    let shown = app.selection_shown();
+    let archive = matches!(app.screen_mode, ScreenMode::Archive);
+    let holidays = matches!(app.screen_mode, ScreenMode::Holidays);
+    match action {
+        Action::MoveDown if archive => app.step_archive_selection(true),
+        Action::MoveUp if archive => app.step_archive_selection(false),
+        Action::MoveDown if holidays => app.step_holiday_selection(true),
+        Action::MoveUp if holidays => app.step_holiday_selection(false),
+        Action::Add if holidays => app.open_holiday_form(false),
+        Action::Edit if holidays => app.open_holiday_form(true),
+        Action::Delete if holidays => {
+            app.delete_selected_holiday();
+            let _ = app.save_habits();
+        }
+        Action::Restore => {
+            app.restore_selected_habit();
+            let _ = app.save_habits();
+        }
+        Action::PrevDay if heatmap => app.move_heatmap_cursor(-1),
+        Action::NextDay if heatmap => app.move_heatmap_cursor(1),
+        Action::PrevWeek if heatmap => app.move_heatmap_cursor(-7),
+        Action::NextWeek if heatmap => app.move_heatmap_cursor(7),
+
+        Action::MoveDown if rows => app.increment_row(),
+        Action::MoveUp if rows => app.decrement_row(),
+        Action::MoveDown => app.increment_habits_counter(),
+        Action::MoveUp => app.decrement_habits_counter(),
+        Action::PageDown => app.page_selection(true, Some(app.page_rows())),
+        Action::PageUp => app.page_selection(false, Some(app.page_rows())),
+        Action::First => app.page_selection(false, None),
+        Action::Last => app.page_selection(true, None),
+        Action::FocusBuild => app.toggle_build_habits(),
+        Action::FocusAvoid => app.toggle_avoid_habit(),
+        Action::Add => app.toggle_add_mode(),
+        Action::Edit if shown => {
+            let habit = app.get_selected_habit();
+            app.toggle_edit_mode(habit);
+        }
+        Action::Delete if shown => app.toggle_delete_mode(),
+        Action::Reset if shown => app.toggle_reset_mode(),
+        Action::Pause if shown => {
+            app.toggle_pause_current();
+            let _ = app.save_habits();
+        }
+        Action::Archive if shown => {
+            app.archive_current_habit();
+            let _ = app.save_habits();
+        }
+        Action::ShowArchive => app.toggle_archive_mode(),
+        Action::MoveHabitDown | Action::MoveHabitUp if shown => {
+            app.move_selected_habit(action == Action::MoveHabitDown);
+            let _ = app.save_habits();
+        }
+        Action::Sort => {
+            app.cycle_sort_mode();
+            let _ = app.save_habits();
+        }
+        Action::Holiday if shown => {
+            let habit = app.get_selected_habit();
+            app.toggle_holidays_mode(habit);
+        }
+        Action::PrevDay => app.step_day(-1),
+        Action::NextDay => app.step_day(1),
+        Action::GoToDate => app.toggle_goto_mode(),
+        Action::GoToToday => app.go_to_today(),
+        Action::PrevWeek => app.step_week(-1),
+        Action::NextWeek => app.step_week(1),
+        Action::PrevMonth => app.step_month(-1),
+        Action::NextMonth => app.step_month(1),
+        Action::OpenDay if heatmap => app.show_day_detail(app.heatmap_cursor),
+        Action::OpenDay => app.show_day_detail(app.current_date),
+        Action::Activate => activate_selected(app),
+        Action::UndoSlip if shown => {
+            app.undo_slip();
+            let _ = app.save_habits();
+        }
+        Action::NextFilter => app.cycle_heatmap_filter(),
+        Action::ToggleGroup => app.toggle_group(),
+        Action::ToggleAllGroups => app.toggle_all_groups(),
+        Action::NextYear => app.increment_year_counter(),
+        Action::PrevYear => app.decrement_year_counter(),
+        Action::Close => app.toggle_normal_mode(),
+        Action::ScrollDown if matches!(app.screen_mode, ScreenMode::Help) => app.scroll_help(true),
+        Action::ScrollUp if matches!(app.screen_mode, ScreenMode::Help) => app.scroll_help(false),
+        _ => {}
     }
 }
 
-fn handle_stats_keys(code: KeyCode, app: &mut App) {
-    if let KeyCode::Tab = code {
-        app.toggle_page();
+/// Toggles the selected build habit, or opens the slip form for an avoid habit.
+fn activate_selected(app: &mut App) {
+    if !app.selection_shown() {
+        return;
+    }
+    if app.counter.switch {
+        let habit = app.get_selected_habit();
+        app.toggle_slip_mode(habit);
+    } else {
+        app.toggle_current_habit();
+        let _ = app.save_habits();
     }
 }
 
-fn handle_heatmap_keys(code: KeyCode, app: &mut App) {
-    match code {
-        KeyCode::Tab => app.toggle_page(),
-        KeyCode::Char('j') | KeyCode::Down => app.increment_year_counter(),
-        KeyCode::Char('k') | KeyCode::Up => app.decrement_year_counter(),
+fn handle_mouse(mouse: MouseEvent, app: &mut App) {
+    let position = Position::new(mouse.column, mouse.row);
+    match app.screen_mode {
+        ScreenMode::Normal => {}
+        ScreenMode::DayDetail => {
+            if let MouseEventKind::Down(MouseButton::Left) = mouse.kind {
+                handle_detail_click(position, app);
+            }
+            return;
+        }
+        ScreenMode::Archive => {
+            if let MouseEventKind::Down(MouseButton::Left) = mouse.kind {
+                handle_archive_click(position, app);
+            }
+            return;
+        }
+        ScreenMode::Holidays => {
+            if let MouseEventKind::Down(MouseButton::Left) = mouse.kind {
+                handle_holidays_click(position, app);
+            }
+            return;
+        }
+        // Read-only popups close on any click; forms ignore the mouse.
+        ScreenMode::Help | ScreenMode::Inbox | ScreenMode::Digest => {
+            match mouse.kind {
+                MouseEventKind::Down(MouseButton::Left) => app.toggle_normal_mode(),
+                MouseEventKind::ScrollDown if matches!(app.screen_mode, ScreenMode::Help) => {
+                    app.scroll_help(true)
+                }
+                MouseEventKind::ScrollUp if matches!(app.screen_mode, ScreenMode::Help) => {
+                    app.scroll_help(false)
+                }
+                _ => {}
+            }
+            return;
+        }
+        _ => return,
+    }
+
+    match mouse.kind {
+        MouseEventKind::Down(MouseButton::Left) => handle_click(position, app),
+        MouseEventKind::ScrollDown => handle_scroll(position, app, true),
+        MouseEventKind::ScrollUp => handle_scroll(position, app, false),
         _ => {}
     }
 }
 
-fn handle_normal_mode(code: KeyCode, app: &mut App) {
-    match code {
-        KeyCode::Tab => app.toggle_page(),
-        KeyCode::Char('j') | KeyCode::Down => app.increment_habits_counter(),
-        KeyCode::Char('k') | KeyCode::Up => app.decrement_habits_counter(),
-        KeyCode::Char('h') | KeyCode::Left => app.toggle_build_habits(),
-        KeyCode::Char('l') | KeyCode::Right => app.toggle_avoid_habit(),
-        KeyCode::Char('a') => app.toggle_add_mode(),
-        KeyCode::Char('e') => {
-            let habit = app.get_selected_habit();
-            app.toggle_edit_mode(habit);
+/// Records a left click and reports whether it completes a double-click.
+fn register_click(position: Position, app: &mut App) -> bool {
+    let double = matches!(app.last_click,
+        Some((at, last)) if last == position && at.elapsed() < DOUBLE_CLICK);
+    app.last_click = if double {
+        None
+    } else {
+        Some((Instant::now(), position))
+    };
+    double
+}
+
+/// Rows select a habit and a double-click toggles it; outside the popup closes it.
+fn handle_detail_click(position: Position, app: &mut App) {
+    let double = register_click(position, app);
+    let hitboxes = app.hitboxes.borrow();
+    let (popup, list) = (hitboxes.popup, hitboxes.detail_list);
+    drop(hitboxes);
+    if !popup.contains(position) {
+        app.toggle_normal_mode();
+    } else if list.contains(position) {
+        let line = (position.y - list.y) as usize;
+        if let Some(row) = app.detail_rows().get(line).copied() {
+            app.select_row(row);
+            if double {
+                activate_selected(app);
+            }
         }
-        KeyCode::Char('d') => app.toggle_delete_mode(),
-        KeyCode::Char('r') => app.toggle_reset_mode(),
-        KeyCode::Char('H') => {
-            let habit = app.get_selected_habit();
-            app.toggle_holiday_mode(habit);
+    }
+}
+
+/// Rows select a habit and a double-click restores it; outside the popup closes it.
+fn handle_archive_click(position: Position, app: &mut App) {
+    let double = register_click(position, app);
+    let hitboxes = app.hitboxes.borrow();
+    let (popup, list) = (hitboxes.popup, hitboxes.archive_list);
+    drop(hitboxes);
+    if !popup.contains(position) {
+        app.toggle_normal_mode();
+    } else if list.contains(position) {
+        let line = (position.y - list.y) as usize;
+        if line < app.archived_rows().len() {
+            app.archive_selected = line;
+            if double {
+                app.restore_selected_habit();
+                let _ = app.save_habits();
+            }
         }
-        KeyCode::Char('y') => app.toggle_day(),
-        KeyCode::Enter | KeyCode::Char(' ') => {
-            app.toggle_current_habit();
-            let _ = app.save_habits();
+    }
+}
+
+fn handle_holidays_click(position: Position, app: &mut App) {
+    let double = register_click(position, app);
+    let hitboxes = app.hitboxes.borrow();
+    let (popup, list) = (hitboxes.popup, hitboxes.holiday_list);
+    drop(hitboxes);
+    if !popup.contains(position) {
+        app.toggle_normal_mode();
+    } else if list.contains(position) {
+        let line = (position.y - list.y) as usize;
+        if line < app.current_habit.holidays.len() {
+            app.holiday_selected = line;
+            if double {
+                app.open_holiday_form(true);
+            }
         }
-        _ => {}
     }
 }
 
-fn handle_text_input(code: KeyCode, app: &mut App, is_editing: bool) {
-    match code {
-        KeyCode::Tab => app.toggle_habit_type(),
-        KeyCode::Backspace => {
-            app.current_habit.name.pop();
+fn handle_click(position: Position, app: &mut App) {
+    let double = register_click(position, app);
+
+    let hitboxes = app.hitboxes.borrow();
+    if let Some((_, screen)) = hitboxes.tabs.iter().find(|(r, _)| r.contains(position)) {
+        let screen = *screen;
+        drop(hitboxes);
+        app.show_screen(screen);
+        return;
+    }
+
+    match app.current_screen {
+        CurrentScreen::Today => {
+            let (build, avoid) = (hitboxes.build_list, hitboxes.avoid_list);
+            drop(hitboxes);
+            let (area, habit_type) = if build.contains(position) {
+                (build, HabitType::Build)
+            } else if avoid.contains(position) {
+                (avoid, HabitType::Avoid)
+            } else {
+                return;
+            };
+            let states = app.list_states.borrow();
+            let offset = match habit_type {
+                HabitType::Build => states.build.offset(),
+                HabitType::Avoid => states.avoid.offset(),
+            };
+            drop(states);
+            let line = offset + (position.y - area.y) as usize;
+            match app.list_rows(habit_type).get(line) {
+                Some(ListRow::Group(name, _)) => {
+                    let name = name.clone();
+                    app.toggle_group_named(&name);
+                }
+                Some(ListRow::Habit(index)) => {
+                    let is_avoid = habit_type == HabitType::Avoid;
+                    app.counter.switch = is_avoid;
+                    if is_avoid {
+                        app.counter.avoid_counter = *index;
+                    } else {
+                        app.counter.build_counter = *index;
+                    }
+                    if double {
+                        activate_selected(app);
+                    }
+                }
+                None => {}
+            }
+        }
+        CurrentScreen::Week => {
+            let cell = hitboxes
+                .week_cells
+                .iter()
+                .find(|(r, _, _)| r.contains(position))
+                .map(|(_, row, date)| (*row, *date));
+            drop(hitboxes);
+            let Some((row, date)) = cell else {
+                return;
+            };
+            if date > app.today() {
+                return;
+            }
+            app.select_row(row);
+            app.current_date = date;
+            if double {
+                activate_selected(app);
+            }
+        }
+        CurrentScreen::Month => {
+            let date = hitboxes
+                .month_cells
+                .iter()
+                .find(|(r, _)| r.contains(position))
+                .map(|(_, date)| *date);
+            drop(hitboxes);
+            if let Some(date) = date.filter(|d| *d <= app.today()) {
+                app.current_date = date;
+                if double {
+                    app.show_day_detail(date);
+                }
+            }
         }
-        KeyCode::Enter => {
-            if is_editing {
-                app.edit_habit();
+        CurrentScreen::Heatmap => {
+            let years = hitboxes.years;
+            let date = hitboxes
+                .heatmap_cells
+                .iter()
+                .find(|(r, _)| r.contains(position))
+                .map(|(_, date)| *date);
+            drop(hitboxes);
+            if let Some(date) = date.filter(|d| *d <= app.today()) {
+                app.heatmap_cursor = date;
+                if double {
+                    app.show_day_detail(date);
+                }
+            } else if years.contains(position) {
+                let offset = app.list_states.borrow().years.offset();
+                match offset + (position.y - years.y) as usize {
+                    row if row == app.years.len() => app.select_rolling_year(),
+                    row => app.select_year(row),
+                }
+            }
+        }
+        CurrentScreen::Stats => {}
+    }
+}
+
+fn handle_scroll(position: Position, app: &mut App, down: bool) {
+    match app.current_screen {
+        CurrentScreen::Today => {
+            let hitboxes = app.hitboxes.borrow();
+            let (build, avoid) = (hitboxes.build_list, hitboxes.avoid_list);
+            drop(hitboxes);
+            if build.contains(position) {
+                app.toggle_build_habits();
+            } else if avoid.contains(position) {
+                app.toggle_avoid_habit();
+            }
+            if down {
+                app.increment_habits_counter();
+            } else {
+                app.decrement_habits_counter();
+            }
+        }
+        CurrentScreen::Week => {
+            if down {
+                app.increment_row();
             } else {
-                app.add_habit();
+                app.decrement_row();
             }
         }
-        KeyCode::Char(value) => {
-            app.current_habit.name.push(value);
+        CurrentScreen::Heatmap => {
+            if down {
+                app.increment_year_counter();
+            } else {
+                app.decrement_year_counter();
+            }
         }
-        _ => {}
+        CurrentScreen::Month => {
+            if down {
+                app.step_week(1);
+            } else {
+                app.step_week(-1);
+            }
+        }
+        CurrentScreen::Stats => {}
     }
 }
 
-fn handle_holiday_input(code: KeyCode, app: &mut App) {
-    match code {
-        KeyCode::Tab => app.toggle_holiday_focus(),
-        KeyCode::Backspace => app.pop_holiday_char(),
-        KeyCode::Enter => app.add_holiday(),
-        KeyCode::Char(value) => app.push_holiday_char(value),
-        _ => {}
+/// Forms take their bound actions first; any other key is typed.
+fn handle_form_key(key: KeyEvent, app: &mut App) {
+    // The picker keys move the dates; plain arrows still move the cursor,
+    // and the reason is plain text.
+    if matches!(app.screen_mode, ScreenMode::Holiday)
+        && app.holiday_input.focus != HolidayField::Reason
+    {
+        let (days, months) = match app.keymap.action(KeyMode::Picker, &key) {
+            Some(Action::PrevDay) => (-1, 0),
+            Some(Action::NextDay) => (1, 0),
+            Some(Action::PrevWeek) => (-7, 0),
+            Some(Action::NextWeek) => (7, 0),
+            Some(Action::PrevMonth) => (0, -1),
+            Some(Action::NextMonth) => (0, 1),
+            _ => (0, 0),
+        };
+        if (days, months) != (0, 0) {
+            app.move_picked_date(days, months);
+            return;
+        }
+    }
+    match app.keymap.action(KeyMode::Form, &key) {
+        Some(Action::Cancel) => match app.screen_mode {
+            ScreenMode::Holiday => app.close_holiday_form(),
+            ScreenMode::Slip => app.close_slip_form(),
+            ScreenMode::Search => app.cancel_search(),
+            _ => app.toggle_normal_mode(),
+        },
+        Some(Action::NextField) => match app.screen_mode {
+            ScreenMode::Holiday => app.toggle_holiday_focus(),
+            ScreenMode::Slip => app.toggle_slip_focus(),
+            ScreenMode::GoTo => {}
+            ScreenMode::Search => app.next_search_match(),
+            ScreenMode::Command => app.complete_command(),
+            _ => app.toggle_habit_focus(),
+        },
+        Some(Action::Save) => match app.screen_mode {
+            ScreenMode::Adding => app.add_habit(),
+            ScreenMode::Editing => app.edit_habit(),
+            ScreenMode::Holiday => {
+                app.save_holiday();
+                let _ = app.save_habits();
+            }
+            ScreenMode::GoTo => app.go_to_date(),
+            ScreenMode::Search => app.confirm_search(),
+            ScreenMode::Command => {
+                app.run_command();
+                let _ = app.save_habits();
+            }
+            _ => {
+                app.log_slip();
+                let _ = app.save_habits();
+            }
+        },
+        _ => {
+            if let Some(field) = app.focused_field() {
+                let edit = field.handle_key(&key);
+                if edit != Edit::Ignored {
+                    app.field_edited(edit);
+                    return;
+                }
+            }
+            if let KeyCode::Char(value) = key.code {
+                if !key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) {
+                    app.push_habit_char(value);
+                }
+            }
+        }
     }
 }
 
-fn handle_confirm(code: KeyCode, app: &mut App, is_delete: bool) {
-    match code {
-        KeyCode::Char('y') => {
-            if is_delete {
+fn handle_confirm(key: KeyEvent, app: &mut App) {
+    match app.keymap.action(KeyMode::Confirm, &key) {
+        Some(Action::Confirm) => {
+            if let ScreenMode::Deleting = app.screen_mode {
                 app.delete_current_habit();
             } else {
                 app.reset_current_habit();
             }
         }
-        KeyCode::Char('n') => app.toggle_normal_mode(),
+        Some(Action::Cancel) => app.toggle_normal_mode(),
         _ => {}
     }
 }
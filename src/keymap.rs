@@ -0,0 +1,762 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::{Deserialize, Serialize};
+
+use crate::glyphs::Glyphs;
+
+/// Everything a key can be bound to.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Back,
+    Help,
+    Inbox,
+    NextScreen,
+    MoveDown,
+    MoveUp,
+    PageDown,
+    PageUp,
+    First,
+    Last,
+    FocusBuild,
+    FocusAvoid,
+    Activate,
+    UndoSlip,
+    PrevDay,
+    NextDay,
+    GoToDate,
+    GoToToday,
+    PrevWeek,
+    NextWeek,
+    PrevMonth,
+    NextMonth,
+    OpenDay,
+    ScrollDown,
+    ScrollUp,
+    NextFilter,
+    NextTag,
+    Search,
+    Command,
+    ToggleGroup,
+    ToggleAllGroups,
+    Pause,
+    Archive,
+    ShowArchive,
+    Restore,
+    MoveHabitDown,
+    MoveHabitUp,
+    Sort,
+    Add,
+    Edit,
+    Delete,
+    Reset,
+    Holiday,
+    NextYear,
+    PrevYear,
+    Close,
+    NextField,
+    Save,
+    Cancel,
+    Confirm,
+}
+
+impl Action {
+    /// Name used in `keymap.toml`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Back => "back",
+            Action::Help => "help",
+            Action::Inbox => "inbox",
+            Action::NextScreen => "next-screen",
+            Action::MoveDown => "move-down",
+            Action::MoveUp => "move-up",
+            Action::PageDown => "page-down",
+            Action::PageUp => "page-up",
+            Action::First => "first",
+            Action::Last => "last",
+            Action::FocusBuild => "focus-build",
+            Action::FocusAvoid => "focus-avoid",
+            Action::Activate => "activate",
+            Action::UndoSlip => "undo-slip",
+            Action::PrevDay => "prev-day",
+            Action::NextDay => "next-day",
+            Action::GoToDate => "go-to-date",
+            Action::GoToToday => "go-to-today",
+            Action::PrevWeek => "prev-week",
+            Action::NextWeek => "next-week",
+            Action::PrevMonth => "prev-month",
+            Action::NextMonth => "next-month",
+            Action::OpenDay => "open-day",
+            Action::ScrollDown => "scroll-down",
+            Action::ScrollUp => "scroll-up",
+            Action::NextFilter => "next-filter",
+            Action::NextTag => "next-tag",
+            Action::Search => "search",
+            Action::Command => "command",
+            Action::ToggleGroup => "toggle-group",
+            Action::ToggleAllGroups => "toggle-all-groups",
+            Action::Pause => "pause",
+            Action::Archive => "archive",
+            Action::ShowArchive => "show-archive",
+            Action::Restore => "restore",
+            Action::MoveHabitDown => "move-habit-down",
+            Action::MoveHabitUp => "move-habit-up",
+            Action::Sort => "sort",
+            Action::Add => "add",
+            Action::Edit => "edit",
+            Action::Delete => "delete",
+            Action::Reset => "reset",
+            Action::Holiday => "holiday",
+            Action::NextYear => "next-year",
+            Action::PrevYear => "prev-year",
+            Action::Close => "close",
+            Action::NextField => "next-field",
+            Action::Save => "save",
+            Action::Cancel => "cancel",
+            Action::Confirm => "confirm",
+        }
+    }
+
+    /// Line shown next to the keys in `mode`'s section of the help popup.
+    pub fn description(&self, mode: KeyMode) -> &'static str {
+        // Holidays are planned ahead, so the picker isn't held to today,
+        // and the holiday manager's actions act on holidays, not habits.
+        match (self, mode) {
+            (Action::NextDay, KeyMode::Picker) => return "Next day",
+            (Action::NextWeek, KeyMode::Picker) => return "Next week",
+            (Action::NextMonth, KeyMode::Picker) => return "Next month",
+            (Action::Add, KeyMode::Holidays) => return "Add holiday",
+            (Action::Edit, KeyMode::Holidays) => return "Edit selected holiday",
+            (Action::Delete, KeyMode::Holidays) => return "Delete selected holiday",
+            _ => {}
+        }
+        match self {
+            Action::Quit => "Quit",
+            Action::Back => "Close a popup, or quit",
+            Action::Help => "Toggle this help",
+            Action::Inbox => "Toggle notification inbox",
+            Action::NextScreen => "Switch view",
+            Action::MoveDown => "Move selection down",
+            Action::MoveUp => "Move selection up",
+            Action::PageDown => "Page down",
+            Action::PageUp => "Page up",
+            Action::First => "First habit",
+            Action::Last => "Last habit",
+            Action::FocusBuild => "Focus Build column",
+            Action::FocusAvoid => "Focus Avoid column",
+            Action::Activate => "Toggle habit / log a slip",
+            Action::UndoSlip => "Undo last slip",
+            Action::PrevDay => "Previous day",
+            Action::NextDay => "Next day (up to today)",
+            Action::GoToDate => "Jump to a date",
+            Action::GoToToday => "Back to today",
+            Action::PrevWeek => "Previous week",
+            Action::NextWeek => "Next week (up to today)",
+            Action::PrevMonth => "Previous month",
+            Action::NextMonth => "Next month (up to today)",
+            Action::OpenDay => "Show and edit the day's habits",
+            Action::ScrollDown => "Scroll down",
+            Action::ScrollUp => "Scroll up",
+            Action::NextFilter => "Filter: all, build, avoid, one habit",
+            Action::NextTag => "Show one tag at a time, or all",
+            Action::Search => "Search habits by name",
+            Action::Command => "Command palette",
+            Action::ToggleGroup => "Fold / unfold the habit's group",
+            Action::ToggleAllGroups => "Fold / unfold every group",
+            Action::Pause => "Pause / resume selected habit",
+            Action::Archive => "Archive selected habit",
+            Action::ShowArchive => "Show archived habits",
+            Action::Restore => "Restore habit",
+            Action::MoveHabitDown => "Move selected habit down",
+            Action::MoveHabitUp => "Move selected habit up",
+            Action::Sort => "Change sort order",
+            Action::Add => "Add habit",
+            Action::Edit => "Edit selected habit",
+            Action::Delete => "Delete selected habit",
+            Action::Reset => "Reset selected habit",
+            Action::Holiday => "Holidays: add, edit, delete",
+            Action::NextYear => "Next year",
+            Action::PrevYear => "Previous year",
+            Action::Close => "Close",
+            Action::NextField => "Next field",
+            Action::Save => "Save",
+            Action::Cancel => "Cancel",
+            Action::Confirm => "Confirm",
+        }
+    }
+}
+
+/// A group of bindings that are active at the same time.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum KeyMode {
+    /// Always checked first outside forms and confirmations.
+    Global,
+    Today,
+    Week,
+    Month,
+    Heatmap,
+    /// Day breakdown popup, from the Month tab or the heatmap.
+    Day,
+    /// Help and digest popups.
+    Popup,
+    /// Archived habits popup.
+    Archive,
+    /// A habit's holidays popup.
+    Holidays,
+    /// Add/edit habit, holiday, slip and go-to-date forms. Unbound keys are typed.
+    Form,
+    /// Moving the holiday form's dates on its calendar, checked before `Form`.
+    Picker,
+    /// Delete and reset confirmations.
+    Confirm,
+}
+
+impl KeyMode {
+    pub const ALL: [KeyMode; 12] = [
+        KeyMode::Global,
+        KeyMode::Today,
+        KeyMode::Week,
+        KeyMode::Month,
+        KeyMode::Heatmap,
+        KeyMode::Day,
+        KeyMode::Popup,
+        KeyMode::Archive,
+        KeyMode::Holidays,
+        KeyMode::Form,
+        KeyMode::Picker,
+        KeyMode::Confirm,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            KeyMode::Global => "global",
+            KeyMode::Today => "today",
+            KeyMode::Week => "week",
+            KeyMode::Month => "month",
+            KeyMode::Heatmap => "heatmap",
+            KeyMode::Day => "day",
+            KeyMode::Popup => "popup",
+            KeyMode::Archive => "archive",
+            KeyMode::Holidays => "holidays",
+            KeyMode::Form => "form",
+            KeyMode::Picker => "picker",
+            KeyMode::Confirm => "confirm",
+        }
+    }
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            KeyMode::Global => "Global",
+            KeyMode::Today => "Today",
+            KeyMode::Week => "Week",
+            KeyMode::Month => "Month",
+            KeyMode::Heatmap => "Heatmap",
+            KeyMode::Day => "Day breakdown",
+            KeyMode::Popup => "Popups",
+            KeyMode::Archive => "Archived habits",
+            KeyMode::Holidays => "Holidays",
+            KeyMode::Form => "Forms",
+            KeyMode::Picker => "Holiday dates",
+            KeyMode::Confirm => "Delete / Reset confirm",
+        }
+    }
+
+    /// Modes whose bindings are live together with this one, so a key
+    /// bound in both would shadow one of them.
+    fn shares_keys_with(&self, other: KeyMode) -> bool {
+        *self == other
+            || matches!(
+                (self, other),
+                (
+                    KeyMode::Global,
+                    KeyMode::Today
+                        | KeyMode::Week
+                        | KeyMode::Month
+                        | KeyMode::Heatmap
+                        | KeyMode::Day
+                        | KeyMode::Popup
+                        | KeyMode::Archive
+                        | KeyMode::Holidays
+                ) | (
+                    KeyMode::Today
+                        | KeyMode::Week
+                        | KeyMode::Month
+                        | KeyMode::Heatmap
+                        | KeyMode::Day
+                        | KeyMode::Popup
+                        | KeyMode::Archive
+                        | KeyMode::Holidays,
+                    KeyMode::Global
+                ) | (KeyMode::Form, KeyMode::Picker)
+                    | (KeyMode::Picker, KeyMode::Form)
+            )
+    }
+}
+
+/// The default bindings, in help-popup order.
+const DEFAULTS: &[(KeyMode, Action, &[&str])] = &[
+    (KeyMode::Global, Action::Quit, &["q"]),
+    (KeyMode::Global, Action::Back, &["esc"]),
+    (KeyMode::Global, Action::NextScreen, &["tab"]),
+    (KeyMode::Global, Action::Help, &["?"]),
+    (KeyMode::Global, Action::Inbox, &["n"]),
+    (KeyMode::Global, Action::NextTag, &["#"]),
+    (KeyMode::Global, Action::Search, &["/"]),
+    (KeyMode::Global, Action::Command, &[":"]),
+    (KeyMode::Today, Action::MoveDown, &["j", "down"]),
+    (KeyMode::Today, Action::MoveUp, &["k", "up"]),
+    (KeyMode::Today, Action::PageDown, &["pgdown"]),
+    (KeyMode::Today, Action::PageUp, &["pgup"]),
+    (KeyMode::Today, Action::First, &["home"]),
+    (KeyMode::Today, Action::Last, &["end"]),
+    (KeyMode::Today, Action::FocusBuild, &["h", "left"]),
+    (KeyMode::Today, Action::FocusAvoid, &["l", "right"]),
+    (KeyMode::Today, Action::Activate, &["enter", "space"]),
+    (KeyMode::Today, Action::UndoSlip, &["x"]),
+    (KeyMode::Today, Action::PrevDay, &["["]),
+    (KeyMode::Today, Action::NextDay, &["]"]),
+    (KeyMode::Today, Action::GoToDate, &["g"]),
+    (KeyMode::Today, Action::GoToToday, &["t"]),
+    (KeyMode::Today, Action::Add, &["a"]),
+    (KeyMode::Today, Action::Edit, &["e"]),
+    (KeyMode::Today, Action::Delete, &["d"]),
+    (KeyMode::Today, Action::Reset, &["r"]),
+    (KeyMode::Today, Action::Holiday, &["H"]),
+    (KeyMode::Today, Action::ToggleGroup, &["z"]),
+    (KeyMode::Today, Action::ToggleAllGroups, &["Z"]),
+    (KeyMode::Today, Action::Pause, &["p"]),
+    (KeyMode::Today, Action::Archive, &["A"]),
+    (KeyMode::Today, Action::ShowArchive, &["v"]),
+    (KeyMode::Today, Action::MoveHabitDown, &["J"]),
+    (KeyMode::Today, Action::MoveHabitUp, &["K"]),
+    (KeyMode::Today, Action::Sort, &["s"]),
+    (KeyMode::Week, Action::MoveDown, &["j", "down"]),
+    (KeyMode::Week, Action::MoveUp, &["k", "up"]),
+    (KeyMode::Week, Action::PageDown, &["pgdown"]),
+    (KeyMode::Week, Action::PageUp, &["pgup"]),
+    (KeyMode::Week, Action::First, &["home"]),
+    (KeyMode::Week, Action::Last, &["end"]),
+    (KeyMode::Week, Action::PrevDay, &["h", "left"]),
+    (KeyMode::Week, Action::NextDay, &["l", "right"]),
+    (KeyMode::Week, Action::PrevWeek, &["["]),
+    (KeyMode::Week, Action::NextWeek, &["]"]),
+    (KeyMode::Week, Action::Activate, &["enter", "space"]),
+    (KeyMode::Week, Action::UndoSlip, &["x"]),
+    (KeyMode::Week, Action::GoToDate, &["g"]),
+    (KeyMode::Week, Action::GoToToday, &["t"]),
+    (KeyMode::Week, Action::ToggleGroup, &["z"]),
+    (KeyMode::Week, Action::ToggleAllGroups, &["Z"]),
+    (KeyMode::Month, Action::PrevDay, &["h", "left"]),
+    (KeyMode::Month, Action::NextDay, &["l", "right"]),
+    (KeyMode::Month, Action::PrevWeek, &["k", "up"]),
+    (KeyMode::Month, Action::NextWeek, &["j", "down"]),
+    (KeyMode::Month, Action::PrevMonth, &["["]),
+    (KeyMode::Month, Action::NextMonth, &["]"]),
+    (KeyMode::Month, Action::OpenDay, &["enter", "space"]),
+    (KeyMode::Month, Action::GoToDate, &["g"]),
+    (KeyMode::Month, Action::GoToToday, &["t"]),
+    (KeyMode::Heatmap, Action::PrevDay, &["k", "up"]),
+    (KeyMode::Heatmap, Action::NextDay, &["j", "down"]),
+    (KeyMode::Heatmap, Action::PrevWeek, &["h", "left"]),
+    (KeyMode::Heatmap, Action::NextWeek, &["l", "right"]),
+    (KeyMode::Heatmap, Action::PrevYear, &["["]),
+    (KeyMode::Heatmap, Action::NextYear, &["]"]),
+    (KeyMode::Heatmap, Action::OpenDay, &["enter", "space"]),
+    (KeyMode::Heatmap, Action::NextFilter, &["f"]),
+    (KeyMode::Heatmap, Action::GoToToday, &["t"]),
+    (KeyMode::Day, Action::MoveDown, &["j", "down"]),
+    (KeyMode::Day, Action::MoveUp, &["k", "up"]),
+    (KeyMode::Day, Action::Activate, &["enter", "space"]),
+    (KeyMode::Day, Action::UndoSlip, &["x"]),
+    (KeyMode::Popup, Action::Close, &["enter"]),
+    (KeyMode::Popup, Action::ScrollDown, &["j", "down"]),
+    (KeyMode::Popup, Action::ScrollUp, &["k", "up"]),
+    (KeyMode::Archive, Action::MoveDown, &["j", "down"]),
+    (KeyMode::Archive, Action::MoveUp, &["k", "up"]),
+    (KeyMode::Archive, Action::Restore, &["enter", "space"]),
+    (KeyMode::Holidays, Action::MoveDown, &["j", "down"]),
+    (KeyMode::Holidays, Action::MoveUp, &["k", "up"]),
+    (KeyMode::Holidays, Action::Add, &["a"]),
+    (KeyMode::Holidays, Action::Edit, &["e", "enter"]),
+    (KeyMode::Holidays, Action::Delete, &["d"]),
+    (KeyMode::Form, Action::NextField, &["tab"]),
+    (KeyMode::Form, Action::Save, &["enter"]),
+    (KeyMode::Form, Action::Cancel, &["esc"]),
+    (KeyMode::Picker, Action::PrevDay, &["alt+left"]),
+    (KeyMode::Picker, Action::NextDay, &["alt+right"]),
+    (KeyMode::Picker, Action::PrevWeek, &["up"]),
+    (KeyMode::Picker, Action::NextWeek, &["down"]),
+    (KeyMode::Picker, Action::PrevMonth, &["<"]),
+    (KeyMode::Picker, Action::NextMonth, &[">"]),
+    (KeyMode::Confirm, Action::Confirm, &["y"]),
+    (KeyMode::Confirm, Action::Cancel, &["n", "esc"]),
+];
+
+/// One key with its modifiers, e.g. `ctrl+n` or `H`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    pub fn parse(text: &str) -> Result<Self, String> {
+        // "+" is a key too: on its own, or last as in "ctrl++".
+        let (prefix, key) = if text == "+" {
+            (None, "+")
+        } else if let Some(prefix) = text.strip_suffix("++") {
+            (Some(prefix), "+")
+        } else {
+            match text.rsplit_once('+') {
+                Some((prefix, key)) => (Some(prefix), key),
+                None => (None, text),
+            }
+        };
+        if key.is_empty() {
+            return Err(format!("missing key in \"{text}\""));
+        }
+        let mut modifiers = KeyModifiers::NONE;
+        for part in prefix.into_iter().flat_map(|p| p.split('+')) {
+            modifiers |= match part.to_lowercase().as_str() {
+                "" => return Err(format!("empty modifier in \"{text}\"")),
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                other => return Err(format!("unknown modifier `{other}` in \"{text}\"")),
+            };
+        }
+
+        let code = match key.to_lowercase().as_str() {
+            "enter" | "return" => KeyCode::Enter,
+            "esc" | "escape" => KeyCode::Esc,
+            "tab" if modifiers.contains(KeyModifiers::SHIFT) => KeyCode::BackTab,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "delete" | "del" => KeyCode::Delete,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pgup" | "pageup" => KeyCode::PageUp,
+            "pgdown" | "pagedown" => KeyCode::PageDown,
+            name if name.len() > 1 && name.starts_with('f') => match name[1..].parse() {
+                Ok(n @ 1..=12) => KeyCode::F(n),
+                _ => return Err(format!("unknown key \"{text}\"")),
+            },
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(format!("unknown key \"{text}\"")),
+                }
+            }
+        };
+        Ok(KeyBinding { code, modifiers }.normalized())
+    }
+
+    /// Shift is part of the character for printable keys ("H", "?"), and
+    /// implied by BackTab, so it's dropped to make matching predictable.
+    fn normalized(mut self) -> Self {
+        if matches!(self.code, KeyCode::Char(_) | KeyCode::BackTab) {
+            self.modifiers.remove(KeyModifiers::SHIFT);
+        }
+        self
+    }
+
+    pub fn matches(&self, event: &KeyEvent) -> bool {
+        *self
+            == KeyBinding {
+                code: event.code,
+                modifiers: event.modifiers,
+            }
+            .normalized()
+    }
+
+    /// Label for the help popup and hints.
+    pub fn label(&self, glyphs: &Glyphs) -> String {
+        let key = match self.code {
+            KeyCode::Char(' ') => "Space".to_string(),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Enter => "Enter".to_string(),
+            KeyCode::Esc => "Esc".to_string(),
+            KeyCode::Tab => "Tab".to_string(),
+            KeyCode::BackTab => "Shift+Tab".to_string(),
+            KeyCode::Backspace => "Backspace".to_string(),
+            KeyCode::Delete => "Del".to_string(),
+            KeyCode::Up => glyphs.up.to_string(),
+            KeyCode::Down => glyphs.down.to_string(),
+            KeyCode::Left => glyphs.left.to_string(),
+            KeyCode::Right => glyphs.right.to_string(),
+            KeyCode::Home => "Home".to_string(),
+            KeyCode::End => "End".to_string(),
+            KeyCode::PageUp => "PgUp".to_string(),
+            KeyCode::PageDown => "PgDn".to_string(),
+            KeyCode::F(n) => format!("F{n}"),
+            _ => "?".to_string(),
+        };
+        let mut label = String::new();
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            label.push_str("Ctrl+");
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            label.push_str("Alt+");
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            label.push_str("Shift+");
+        }
+        label + &key
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.label(&crate::glyphs::ASCII))
+    }
+}
+
+/// `keymap.toml`: one table per mode mapping action names to key lists.
+/// Actions left out keep their default keys.
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeymapFile {
+    pub global: BTreeMap<String, Vec<String>>,
+    pub today: BTreeMap<String, Vec<String>>,
+    pub week: BTreeMap<String, Vec<String>>,
+    pub month: BTreeMap<String, Vec<String>>,
+    pub heatmap: BTreeMap<String, Vec<String>>,
+    pub day: BTreeMap<String, Vec<String>>,
+    pub popup: BTreeMap<String, Vec<String>>,
+    pub archive: BTreeMap<String, Vec<String>>,
+    pub holidays: BTreeMap<String, Vec<String>>,
+    pub form: BTreeMap<String, Vec<String>>,
+    pub picker: BTreeMap<String, Vec<String>>,
+    pub confirm: BTreeMap<String, Vec<String>>,
+}
+
+impl KeymapFile {
+    fn table(&self, mode: KeyMode) -> &BTreeMap<String, Vec<String>> {
+        match mode {
+            KeyMode::Global => &self.global,
+            KeyMode::Today => &self.today,
+            KeyMode::Week => &self.week,
+            KeyMode::Month => &self.month,
+            KeyMode::Heatmap => &self.heatmap,
+            KeyMode::Day => &self.day,
+            KeyMode::Popup => &self.popup,
+            KeyMode::Archive => &self.archive,
+            KeyMode::Holidays => &self.holidays,
+            KeyMode::Form => &self.form,
+            KeyMode::Picker => &self.picker,
+            KeyMode::Confirm => &self.confirm,
+        }
+    }
+}
+
+/// One mode's bindings as listed in the help popup.
+pub type KeymapSection<'a> = (KeyMode, Vec<(Action, &'a [KeyBinding])>);
+
+pub struct Keymap {
+    bindings: Vec<(KeyMode, Action, Vec<KeyBinding>)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::from_file(&KeymapFile::default()).expect("default keymap is valid")
+    }
+}
+
+impl Keymap {
+    pub fn from_file(file: &KeymapFile) -> Result<Self, String> {
+        for mode in KeyMode::ALL {
+            for name in file.table(mode).keys() {
+                let known = DEFAULTS
+                    .iter()
+                    .any(|(m, action, _)| *m == mode && action.name() == name);
+                if !known {
+                    return Err(format!("unknown action `{name}` in [{}]", mode.name()));
+                }
+            }
+        }
+
+        let mut bindings = Vec::new();
+        for (mode, action, default_keys) in DEFAULTS {
+            let keys = match file.table(*mode).get(action.name()) {
+                Some(keys) => keys
+                    .iter()
+                    .map(|k| KeyBinding::parse(k))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| format!("[{}] {}: {e}", mode.name(), action.name()))?,
+                None => default_keys
+                    .iter()
+                    .map(|k| KeyBinding::parse(k).expect("default keys parse"))
+                    .collect(),
+            };
+            bindings.push((*mode, *action, keys));
+        }
+
+        for (i, (mode, action, keys)) in bindings.iter().enumerate() {
+            for (other_mode, other_action, other_keys) in &bindings[i + 1..] {
+                if !mode.shares_keys_with(*other_mode) {
+                    continue;
+                }
+                if let Some(key) = keys.iter().find(|k| other_keys.contains(k)) {
+                    return Err(format!(
+                        "\"{key}\" is bound to both [{}] {} and [{}] {}",
+                        mode.name(),
+                        action.name(),
+                        other_mode.name(),
+                        other_action.name()
+                    ));
+                }
+            }
+        }
+        Ok(Keymap { bindings })
+    }
+
+    /// The action `event` triggers in `mode`, if any.
+    pub fn action(&self, mode: KeyMode, event: &KeyEvent) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|(m, _, keys)| *m == mode && keys.iter().any(|k| k.matches(event)))
+            .map(|(_, action, _)| *action)
+    }
+
+    /// Every key bound to `action` in `mode`, joined for display.
+    pub fn keys(&self, mode: KeyMode, action: Action, glyphs: &Glyphs) -> String {
+        self.bindings
+            .iter()
+            .find(|(m, a, _)| *m == mode && *a == action)
+            .map(|(_, _, keys)| {
+                keys.iter()
+                    .map(|k| k.label(glyphs))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default()
+    }
+
+    /// Bindings grouped by mode, in help-popup order.
+    pub fn sections(&self) -> Vec<KeymapSection<'_>> {
+        KeyMode::ALL
+            .iter()
+            .map(|mode| {
+                let entries = self
+                    .bindings
+                    .iter()
+                    .filter(|(m, _, _)| m == mode)
+                    .map(|(_, action, keys)| (*action, keys.as_slice()))
+                    .collect();
+                (*mode, entries)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(text: &str) -> KeyBinding {
+        KeyBinding::parse(text).unwrap()
+    }
+
+    fn file(mode: &str, action: &str, keys: &[&str]) -> KeymapFile {
+        let table = BTreeMap::from([(
+            action.to_string(),
+            keys.iter().map(|k| k.to_string()).collect(),
+        )]);
+        let mut file = KeymapFile::default();
+        match mode {
+            "today" => file.today = table,
+            "week" => file.week = table,
+            "picker" => file.picker = table,
+            _ => unreachable!(),
+        }
+        file
+    }
+
+    #[test]
+    fn parses_modifiers_and_named_keys() {
+        let ctrl_n = key("ctrl+n");
+        assert_eq!(
+            (ctrl_n.code, ctrl_n.modifiers),
+            (KeyCode::Char('n'), KeyModifiers::CONTROL)
+        );
+        let alt_left = key("Alt+Left");
+        assert_eq!(
+            (alt_left.code, alt_left.modifiers),
+            (KeyCode::Left, KeyModifiers::ALT)
+        );
+        assert!(key("shift+tab") == key("backtab"));
+        assert!(key("shift+h") == key("h"));
+        assert_eq!(key("f5").code, KeyCode::F(5));
+        assert_eq!(key("H").code, KeyCode::Char('H'));
+    }
+
+    #[test]
+    fn plus_is_a_key_alone_or_last() {
+        assert_eq!(key("+").code, KeyCode::Char('+'));
+        let ctrl_plus = key("ctrl++");
+        assert_eq!(
+            (ctrl_plus.code, ctrl_plus.modifiers),
+            (KeyCode::Char('+'), KeyModifiers::CONTROL)
+        );
+    }
+
+    #[test]
+    fn empty_segments_are_rejected() {
+        for text in ["", "a+", "ctrl+a+", "ctrl+", "+a", "ctrl++a", "++"] {
+            assert!(KeyBinding::parse(text).is_err(), "{text:?} parsed");
+        }
+        assert!(KeyBinding::parse("hyper+a").is_err());
+        assert!(KeyBinding::parse("f13").is_err());
+        assert!(KeyBinding::parse("ab").is_err());
+    }
+
+    #[test]
+    fn unknown_actions_are_named() {
+        let error = Keymap::from_file(&file("today", "fly", &["f"]))
+            .err()
+            .unwrap();
+        assert_eq!(error, "unknown action `fly` in [today]");
+        // Real actions, but not in this mode.
+        assert!(Keymap::from_file(&file("week", "add", &["a"])).is_err());
+    }
+
+    #[test]
+    fn keys_can_repeat_only_across_separate_modes() {
+        assert!(Keymap::from_file(&KeymapFile::default()).is_ok());
+        // Today and Week are never live together.
+        assert!(Keymap::from_file(&file("week", "undo-slip", &["a"])).is_ok());
+
+        let error = Keymap::from_file(&file("today", "add", &["n"]))
+            .err()
+            .unwrap();
+        assert_eq!(
+            error,
+            "\"n\" is bound to both [global] inbox and [today] add"
+        );
+        let error = Keymap::from_file(&file("today", "add", &["x"]))
+            .err()
+            .unwrap();
+        assert_eq!(
+            error,
+            "\"x\" is bound to both [today] undo-slip and [today] add"
+        );
+        // The picker's keys are live inside the holiday form.
+        let error = Keymap::from_file(&file("picker", "next-day", &["tab"]))
+            .err()
+            .unwrap();
+        assert_eq!(
+            error,
+            "\"Tab\" is bound to both [form] next-field and [picker] next-day"
+        );
+    }
+}
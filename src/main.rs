@@ -1,16 +1,12 @@
 use std::io::{self, Result};
-use std::sync::{Arc};
-use std::thread;
-use std::time::Duration;
-use std::str::FromStr;
-use chrono::Local;
-
-use cron::Schedule;
+use std::sync::Arc;
 
 use ratatui::{
     backend::CrosstermBackend,
     crossterm::{
-        event::{DisableMouseCapture, EnableMouseCapture},
+        event::{
+            DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
+        },
         execute,
         terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
     },
@@ -18,57 +14,79 @@ use ratatui::{
 };
 
 mod app;
+mod cli;
+mod date_input;
+mod digest;
+mod export;
 mod habit;
 mod input;
 mod storage;
 mod ui;
 mod notifications;
+mod sinks;
+mod theme;
+mod glyphs;
+mod keymap;
+mod palette;
+mod text_field;
 
 use crate::app::App;
+use crate::storage::NotificationSettings;
 
 fn main() -> Result<()> {
-
-    enable_raw_mode()?;
-    let mut stderr = io::stderr();
-    execute!(stderr, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stderr);
-    let mut terminal = Terminal::new(backend)?;
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let flags = cli::take_flags(&mut args);
+    if cli::run(&args)? {
+        return Ok(());
+    }
 
     let mut app = App::new();
     if let Err(e) = app.load_habits() {
         eprintln!("Warning: Failed to load habits: {}", e);
     }
 
-    let notification_settings = storage::load_notification_settings();
+    let notifications = match storage::load_notification_settings() {
+        Ok(settings) => settings,
+        Err(e) => {
+            app.config_error = Some(e.to_string());
+            NotificationSettings::default()
+        }
+    };
+    app.set_notifications(notifications.clone());
 
-    match notification_settings {
-        Err(ref e) => eprintln!("Warning: Failed to load notification settings: {}", e),
-        Ok(_) => {},
+    match storage::load_theme() {
+        Ok(theme) => app.theme = theme,
+        Err(e) => app.theme_error = Some(e.to_string()),
+    }
+    match storage::load_keymap() {
+        Ok(keymap) => app.keymap = keymap,
+        Err(e) => app.keymap_error = Some(e.to_string()),
+    }
+    if flags.ascii || flags.screen_reader {
+        app.theme.use_ascii(flags.screen_reader);
     }
 
-    let notifications = notification_settings.unwrap();
-    app.set_notifications(notifications.clone());
+    app.deliver_digests(&notifications);
 
-    if notifications.enable {
-        let app_clone = Arc::clone(&app.notif);
+    notifications::spawn_scheduler(Arc::clone(&app.notif));
 
-        thread::spawn(move || {
-            let expression = format!("0 {} {} * * * *", notifications.minute, notifications.hour);
-            let schedule = Schedule::from_str(&expression).unwrap();
-            let mut iterator = schedule.upcoming(Local).peekable();
-            loop {
-                thread::sleep(Duration::from_secs(30));
-                notifications::check_notification_trigger(app_clone.lock().unwrap().clone(), &mut iterator);
-            }
-        });
+    enable_raw_mode()?;
+    let mut stderr = io::stderr();
+    execute!(stderr, EnterAlternateScreen, EnableBracketedPaste)?;
+    if !flags.no_mouse {
+        execute!(stderr, EnableMouseCapture)?;
     }
+    let backend = CrosstermBackend::new(stderr);
+    let mut terminal = Terminal::new(backend)?;
 
     input::run_app(&mut terminal, &mut app)?;
+    notifications::flush_acted_on(&app.notif);
 
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
         LeaveAlternateScreen,
+        DisableBracketedPaste,
         DisableMouseCapture
     )?;
     terminal.show_cursor()?;
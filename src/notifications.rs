@@ -1,8 +1,24 @@
-use chrono::Local;
+use chrono::{Local, NaiveDate, NaiveDateTime};
 
-use notify_rust::Notification;
-use cron::ScheduleIterator;
+use cron::{OwnedScheduleIterator, Schedule};
+use serde::{Deserialize, Serialize};
 use std::iter::Peekable;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::digest::{self, DigestSettings};
+use crate::habit::today_with_cutoff;
+use crate::sinks::{self, Notice, SinkConfig};
+use crate::storage::{self, ConfigWatcher, NotificationSettings};
+
+type Reminders = Peekable<OwnedScheduleIterator<Local>>;
+
+/// Held for every read-modify-write of the notification log, so a reminder
+/// being appended can't race a check-in being marked. Kept apart from the
+/// shared data so the UI never waits on file IO.
+static LOG_WRITE: Mutex<()> = Mutex::new(());
 
 #[derive(Clone)]
 pub struct NotificationData {
@@ -10,6 +26,14 @@ pub struct NotificationData {
     pub total: usize,
     pub low_threshold: usize,
     pub high_threshold: usize,
+    pub sinks: Vec<SinkConfig>,
+    pub digest: DigestSettings,
+    pub day_cutoff_hour: u32,
+    /// A day the user checked in on, waiting for the scheduler to mark its
+    /// notifications as acted on.
+    pub acted_on: Option<NaiveDate>,
+    /// Failures from the last scheduled digest, for the TUI to show.
+    pub digest_errors: Vec<String>,
 }
 
 impl Default for NotificationData {
@@ -19,53 +43,342 @@ impl Default for NotificationData {
             total: 0,
             low_threshold: 20,
             high_threshold: 80,
+            sinks: sinks::default_sinks(),
+            digest: DigestSettings::default(),
+            day_cutoff_hour: 0,
+            acted_on: None,
+            digest_errors: Vec::new(),
+        }
+    }
+}
+
+/// Which threshold caused a notification to fire, or `Digest` for a
+/// weekly/monthly digest sent through the sinks.
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum NotificationTrigger {
+    Low,
+    High,
+    Digest,
+}
+
+impl NotificationTrigger {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationTrigger::Low => "low",
+            NotificationTrigger::High => "high",
+            NotificationTrigger::Digest => "digest",
         }
     }
 }
 
+/// One delivered notification, kept so thresholds can be tuned against what
+/// actually happened instead of guesswork.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct NotificationRecord {
+    pub sent_at: NaiveDateTime,
+    pub trigger: NotificationTrigger,
+    pub text: String,
+    pub done: usize,
+    pub total: usize,
+    /// Set once a habit is toggled later the same day.
+    #[serde(default)]
+    pub acted_on: bool,
+    /// Sinks that failed to deliver, as `"<sink>: <error>"`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
 impl NotificationData {
+    pub fn apply(&mut self, settings: &NotificationSettings) {
+        self.low_threshold = settings.low_threshold;
+        self.high_threshold = settings.high_threshold;
+        self.sinks = settings.sinks.clone();
+        self.digest = settings.digest.clone();
+        self.day_cutoff_hour = settings.day_cutoff_hour.min(23);
+    }
+
     pub fn get_percent(&self) -> f32 {
         if self.total == 0 {
             return 100.0;
         }
-        return 100.0 * (self.done as f32) / (self.total as f32);
+        100.0 * (self.done as f32) / (self.total as f32)
     }
 
-    pub fn get_notification_text(&self) -> String {
+    pub fn get_trigger(&self) -> Option<NotificationTrigger> {
         let progress = self.get_percent();
         if progress <= self.low_threshold as f32 {
-            return String::from("I know you're busy, but make sure to check your habit tracker today!");
+            Some(NotificationTrigger::Low)
         } else if progress >= self.high_threshold as f32 {
-            return String::from("You've done nearly all your tasks today, well done!");
+            Some(NotificationTrigger::High)
         } else {
-            return String::from("");
+            None
+        }
+    }
+
+    pub fn get_notification_text(&self) -> String {
+        match self.get_trigger() {
+            Some(NotificationTrigger::Low) => String::from(
+                "I know you're busy, but make sure to check your habit tracker today!",
+            ),
+            Some(NotificationTrigger::High) => {
+                String::from("You've done nearly all your tasks today, well done!")
+            }
+            Some(NotificationTrigger::Digest) | None => String::new(),
         }
     }
 }
 
-pub fn check_notification_trigger(count: NotificationData, iter: &mut Peekable<ScheduleIterator<'_, Local>>) {
-    let datetime = iter.peek();
+fn upcoming_reminders(settings: &NotificationSettings) -> Option<Reminders> {
+    let expression = format!("0 {} {} * * * *", settings.minute, settings.hour);
+    Schedule::from_str(&expression)
+        .ok()
+        .map(|schedule| schedule.upcoming_owned(Local).peekable())
+}
+
+/// Runs the daily reminder in the background. `notification.toml` is
+/// re-read whenever it changes, so edits (including `enable`) apply without
+/// a restart; an invalid edit keeps the previous settings. Digests go out
+/// at the reminder time even while the reminder itself is disabled.
+pub fn spawn_scheduler(shared: Arc<Mutex<NotificationData>>) {
+    thread::spawn(move || {
+        let mut watcher = ConfigWatcher::new();
+        let mut settings = storage::load_notification_settings().unwrap_or_default();
+        let mut reminders = upcoming_reminders(&settings);
+        loop {
+            thread::sleep(Duration::from_secs(30));
+            flush_acted_on(&shared);
+            if watcher.changed() {
+                if let Ok(reloaded) = storage::load_notification_settings() {
+                    reminders = upcoming_reminders(&reloaded);
+                    shared.lock().unwrap().apply(&reloaded);
+                    settings = reloaded;
+                }
+            }
+            if let Some(iter) = reminders.as_mut() {
+                let count = shared.lock().unwrap().clone();
+                check_notification_trigger(&shared, count, iter, settings.enable);
+            }
+        }
+    });
+}
+
+/// At the reminder time, sends any digest that is due, and the reminder
+/// itself when `remind` is set.
+pub fn check_notification_trigger(
+    shared: &Mutex<NotificationData>,
+    count: NotificationData,
+    iter: &mut Reminders,
+    remind: bool,
+) {
     let now = Local::now();
-    match datetime {
-        Some(x) => if *x <= now {
-            send_notification(count);
+    if let Some(x) = iter.peek() {
+        if *x <= now {
+            send_digests(shared, &count);
+            if remind {
+                send_notification(count);
+            }
             iter.next();
         }
-        None    => {},
     }
 }
 
 pub fn send_notification(count: NotificationData) {
+    let Some(trigger) = count.get_trigger() else {
+        return;
+    };
     let s = count.get_notification_text();
-    if !s.is_empty() {
-        let mut notification = Notification::new();
-        notification.summary("flow_state reminder").body(&s[..]).timeout(0);
-        // urgency() is a Linux/D-Bus-only builder method — macOS's
-        // notification backend doesn't expose it.
-        #[cfg(not(target_os = "macos"))]
-        notification.urgency(notify_rust::Urgency::Normal);
-        let _ = notification.show();
+    let errors = sinks::dispatch(
+        &count.sinks,
+        &Notice {
+            title: "flow_state reminder",
+            message: &s,
+            trigger,
+            done: count.done,
+            total: count.total,
+        },
+    );
+
+    let _guard = LOG_WRITE.lock().unwrap_or_else(|e| e.into_inner());
+    let _ = storage::append_notification_record(NotificationRecord {
+        sent_at: Local::now().naive_local(),
+        trigger,
+        text: s,
+        done: count.done,
+        total: count.total,
+        acted_on: false,
+        errors,
+    });
+}
+
+/// Delivers any weekly/monthly digest that has come due since the last one.
+fn send_digests(shared: &Mutex<NotificationData>, count: &NotificationData) {
+    if let Ok((build, avoid)) = storage::load_habits() {
+        let habits: Vec<_> = build.into_iter().chain(avoid).collect();
+        let today = today_with_cutoff(count.day_cutoff_hour);
+        let delivery = digest::deliver_due(&count.digest, &count.sinks, &habits, today, false);
+        shared.lock().unwrap().digest_errors.extend(delivery.errors);
     }
 }
 
+/// Writes out a check-in queued in `acted_on`. `shared` is only locked to
+/// take the date; the log is rewritten under `LOG_WRITE`.
+pub fn flush_acted_on(shared: &Mutex<NotificationData>) {
+    let queued = shared.lock().unwrap().acted_on.take();
+    if let Some(date) = queued {
+        mark_acted_on(date);
+    }
+}
 
+/// Marks every notification sent earlier on `date` as acted on, so the log
+/// shows which nudges actually worked.
+fn mark_acted_on(date: NaiveDate) {
+    let _guard = LOG_WRITE.lock().unwrap_or_else(|e| e.into_inner());
+    let Ok(mut records) = storage::load_notification_log() else {
+        return;
+    };
+    if mark_records(&mut records, date, Local::now().naive_local()) {
+        let _ = storage::save_notification_log(&records);
+    }
+}
+
+/// Marks the records sent on `date`, up to `now`, as acted on. Returns
+/// whether any changed.
+fn mark_records(records: &mut [NotificationRecord], date: NaiveDate, now: NaiveDateTime) -> bool {
+    let mut changed = false;
+    for record in records
+        .iter_mut()
+        .filter(|r| !r.acted_on && r.sent_at.date() == date && r.sent_at <= now)
+    {
+        record.acted_on = true;
+        changed = true;
+    }
+    changed
+}
+
+#[derive(Default)]
+pub struct NotificationSummary {
+    pub sent: usize,
+    pub low: usize,
+    pub high: usize,
+    pub acted_on: usize,
+}
+
+impl NotificationSummary {
+    pub fn describe(&self, period: &str) -> String {
+        let times = if self.sent == 1 { "time" } else { "times" };
+        format!(
+            "You were nudged {} {} {} · {} low · {} high · {} acted on",
+            self.sent, times, period, self.low, self.high, self.acted_on
+        )
+    }
+}
+
+/// Summarizes the records sent on or after `since`.
+pub fn summarize(records: &[NotificationRecord], since: NaiveDate) -> NotificationSummary {
+    records
+        .iter()
+        .filter(|r| r.sent_at.date() >= since)
+        .fold(NotificationSummary::default(), |mut summary, r| {
+            summary.sent += 1;
+            match r.trigger {
+                NotificationTrigger::Low => summary.low += 1,
+                NotificationTrigger::High => summary.high += 1,
+                NotificationTrigger::Digest => {}
+            }
+            if r.acted_on {
+                summary.acted_on += 1;
+            }
+            summary
+        })
+}
+
+pub fn format_record(record: &NotificationRecord) -> String {
+    let mut line = format!(
+        "{}  {:<4}  {}/{}  {:<8}  {}",
+        record.sent_at.format("%Y-%m-%d %H:%M"),
+        record.trigger.as_str(),
+        record.done,
+        record.total,
+        if record.acted_on { "acted on" } else { "ignored" },
+        record.text
+    );
+    for error in &record.errors {
+        line.push_str(&format!("\n    failed: {error}"));
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(day: u32, hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, day)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    fn record(sent_at: NaiveDateTime, trigger: NotificationTrigger) -> NotificationRecord {
+        NotificationRecord {
+            sent_at,
+            trigger,
+            text: String::new(),
+            done: 0,
+            total: 0,
+            acted_on: false,
+            errors: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn describe_counts_by_trigger() {
+        let records = [
+            record(at(18, 20), NotificationTrigger::Low),
+            record(at(19, 20), NotificationTrigger::Low),
+            NotificationRecord {
+                acted_on: true,
+                ..record(at(20, 20), NotificationTrigger::High)
+            },
+        ];
+        assert_eq!(
+            summarize(&records, at(19, 0).date()).describe("this week"),
+            "You were nudged 2 times this week · 1 low · 1 high · 1 acted on"
+        );
+        assert_eq!(
+            summarize(&records, at(20, 0).date()).describe("today"),
+            "You were nudged 1 time today · 0 low · 1 high · 1 acted on"
+        );
+    }
+
+    #[test]
+    fn check_ins_mark_only_that_days_earlier_notifications() {
+        let mut records = [
+            record(at(18, 20), NotificationTrigger::Low),
+            record(at(19, 9), NotificationTrigger::Low),
+            record(at(19, 20), NotificationTrigger::High),
+        ];
+        assert!(mark_records(&mut records, at(19, 0).date(), at(19, 12)));
+        let marked: Vec<bool> = records.iter().map(|r| r.acted_on).collect();
+        assert_eq!(marked, [false, true, false]);
+        assert!(!mark_records(&mut records, at(19, 0).date(), at(19, 12)));
+    }
+
+    #[test]
+    fn flush_writes_a_queued_check_in_once() {
+        let config = std::env::temp_dir().join(format!("flow_state-log-{}", std::process::id()));
+        std::env::set_var("XDG_CONFIG_HOME", &config);
+        let today = Local::now().naive_local() - chrono::Duration::minutes(1);
+        storage::save_notification_log(&[record(today, NotificationTrigger::Low)]).unwrap();
+
+        let shared = Mutex::new(NotificationData {
+            acted_on: Some(today.date()),
+            ..NotificationData::default()
+        });
+        flush_acted_on(&shared);
+        let records = storage::load_notification_log().unwrap();
+        let _ = std::fs::remove_dir_all(&config);
+        assert!(records[0].acted_on);
+        assert!(shared.lock().unwrap().acted_on.is_none());
+    }
+}
@@ -0,0 +1,159 @@
+use chrono::NaiveDate;
+
+use crate::app::SortMode;
+use crate::date_input::parse_date;
+
+/// Every command with its arguments and what it does, in the order the
+/// palette suggests them.
+pub const COMMANDS: [(&str, &str, &str); 10] = [
+    ("goto", "<date>", "Show a date"),
+    ("today", "", "Back to today"),
+    ("holiday", "<start> <end>", "Holiday for the selected habit"),
+    ("pause", "", "Pause / resume the selected habit"),
+    ("archive", "", "Archive the selected habit"),
+    ("archived", "", "Show archived habits"),
+    ("sort", "<manual|name|tier|pending|time>", "Order the habit lists"),
+    ("tag", "[name]", "Show one tag, or every habit"),
+    ("export", "csv", "Write every habit's days to a CSV file"),
+    ("help", "", "Show all keymaps"),
+];
+
+pub enum Command {
+    GoTo(NaiveDate),
+    Today,
+    Holiday(NaiveDate, NaiveDate),
+    Pause,
+    Archive,
+    ShowArchive,
+    Sort(SortMode),
+    Tag(Option<String>),
+    ExportCsv,
+    Help,
+}
+
+/// Parses a palette line such as `holiday 2026-12-20 +2w`; dates can be
+/// anything `parse_date` reads, relative to `today`. The error is shown
+/// under the input as is.
+pub fn parse(line: &str, today: NaiveDate) -> Result<Command, String> {
+    let mut words = line.split_whitespace();
+    let Some(name) = words.next() else {
+        return Err("Type a command".to_string());
+    };
+    let args: Vec<&str> = words.collect();
+    let date = |arg: Option<&&str>| {
+        arg.and_then(|a| parse_date(a, today))
+            .ok_or_else(|| "Use dates like 2026-12-20, today, +7d or fri".to_string())
+    };
+    let command = match name {
+        "goto" | "g" => Command::GoTo(date(args.first())?),
+        "today" => Command::Today,
+        "holiday" => {
+            let (start, end) = (date(args.first())?, date(args.get(1))?);
+            if end < start {
+                return Err("The holiday ends before it starts".to_string());
+            }
+            Command::Holiday(start, end)
+        }
+        "pause" | "resume" => Command::Pause,
+        "archive" => Command::Archive,
+        "archived" => Command::ShowArchive,
+        "sort" => Command::Sort(match args.first().copied() {
+            Some("manual") | Some("own") => SortMode::Manual,
+            Some("name") => SortMode::Name,
+            Some("tier") => SortMode::Tier,
+            Some("pending") => SortMode::Pending,
+            Some("time") => SortMode::TimeOfDay,
+            _ => return Err("Sort by manual, name, tier, pending or time".to_string()),
+        }),
+        "tag" => Command::Tag(args.first().map(|t| t.trim_start_matches('#').to_string())),
+        "export" => match args.first().copied() {
+            Some("csv") => Command::ExportCsv,
+            _ => return Err("Only `export csv` is supported".to_string()),
+        },
+        "help" => Command::Help,
+        other => return Err(format!("Unknown command `{other}`")),
+    };
+    Ok(command)
+}
+
+/// The commands whose name starts with the first word typed so far, or
+/// just the one being given arguments.
+pub fn suggestions(line: &str) -> Vec<(&'static str, &'static str, &'static str)> {
+    let line = line.trim_start();
+    let typed = line.split_whitespace().next().unwrap_or("");
+    let complete = line.len() > typed.len();
+    COMMANDS
+        .iter()
+        .filter(|(name, _, _)| if complete { *name == typed } else { name.starts_with(typed) })
+        .copied()
+        .collect()
+}
+
+/// How well `query` matches `text`: `None` unless every query character
+/// appears in order, higher for runs of adjacent characters and matches
+/// at the start of words. Case is ignored.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.to_lowercase().chars().collect();
+    let mut score = 0;
+    let mut from = 0;
+    let mut last: Option<usize> = None;
+    for q in query.to_lowercase().chars().filter(|c| !c.is_whitespace()) {
+        let found = from + text[from..].iter().position(|c| *c == q)?;
+        score += 1;
+        if last.is_some_and(|l| l + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        last = Some(found);
+        from = found + 1;
+    }
+    // Among equal matches, shorter names are closer to what was typed.
+    Some(score * 100 - text.len() as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_needs_every_character_in_order() {
+        assert!(fuzzy_score("mr", "Morning run").is_some());
+        assert!(fuzzy_score("MORN", "morning run").is_some());
+        assert!(fuzzy_score("rm", "Morning run").is_none());
+        assert!(fuzzy_score("runs", "Morning run").is_none());
+    }
+
+    #[test]
+    fn fuzzy_prefers_runs_and_word_starts() {
+        let run = fuzzy_score("med", "Meditate").unwrap();
+        let scattered = fuzzy_score("mdt", "Meditate").unwrap();
+        assert!(run > scattered);
+
+        let word_start = fuzzy_score("w", "Go walk").unwrap();
+        let mid_word = fuzzy_score("w", "Gowalks").unwrap();
+        assert!(word_start > mid_word);
+    }
+
+    #[test]
+    fn fuzzy_breaks_ties_by_length() {
+        let short = fuzzy_score("read", "Read").unwrap();
+        let long = fuzzy_score("read", "Read 10 pages").unwrap();
+        assert!(short > long);
+        assert_eq!(fuzzy_score("", "Read"), Some(-4));
+        assert_eq!(fuzzy_score(" r ", "Read"), fuzzy_score("r", "Read"));
+    }
+
+    #[test]
+    fn parses_commands_with_relative_dates() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 19).unwrap();
+        let Ok(Command::Holiday(start, end)) = parse("holiday today +1w", today) else {
+            panic!("expected a holiday");
+        };
+        assert_eq!((start, end), (today, today + chrono::Duration::days(7)));
+        assert!(parse("holiday +1w today", today).is_err());
+        assert!(parse("export pdf", today).is_err());
+        assert!(matches!(parse("sort tier", today), Ok(Command::Sort(SortMode::Tier))));
+    }
+}
@@ -0,0 +1,480 @@
+use std::process::Command;
+use std::time::Duration;
+
+use notify_rust::Notification;
+use serde::{Deserialize, Serialize};
+
+use crate::notifications::NotificationTrigger;
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What gets handed to every sink when a notification fires.
+pub struct Notice<'a> {
+    pub title: &'a str,
+    pub message: &'a str,
+    pub trigger: NotificationTrigger,
+    pub done: usize,
+    pub total: usize,
+}
+
+impl Notice<'_> {
+    fn percent(&self) -> f32 {
+        if self.total == 0 {
+            return 100.0;
+        }
+        100.0 * self.done as f32 / self.total as f32
+    }
+}
+
+pub trait NotificationSink {
+    /// Short label used in the notification log when delivery fails.
+    fn name(&self) -> String;
+    fn send(&self, notice: &Notice) -> Result<(), String>;
+}
+
+/// One `[[sinks]]` entry in `notification.toml`.
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SinkConfig {
+    /// Native desktop popup, the only sink before sinks were configurable.
+    Desktop,
+    /// POSTs a JSON payload to `url`.
+    Webhook { url: String },
+    /// ntfy-style push: POSTs the message as the body to a topic URL.
+    Ntfy {
+        url: String,
+        #[serde(default)]
+        token: Option<String>,
+    },
+    /// Gotify push: POSTs to the server's `/message` endpoint.
+    Gotify { url: String, token: String },
+    /// Runs `command` through the shell with the notice in `FLOW_STATE_*`
+    /// environment variables.
+    Command { command: String },
+}
+
+impl SinkConfig {
+    pub fn build(&self) -> Box<dyn NotificationSink + Send> {
+        match self.clone() {
+            SinkConfig::Desktop => Box::new(DesktopSink),
+            SinkConfig::Webhook { url } => Box::new(WebhookSink { url }),
+            SinkConfig::Ntfy { url, token } => Box::new(NtfySink { url, token }),
+            SinkConfig::Gotify { url, token } => Box::new(GotifySink { url, token }),
+            SinkConfig::Command { command } => Box::new(CommandSink { command }),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let check_url = |url: &str| {
+            if url.starts_with("http://") || url.starts_with("https://") {
+                Ok(())
+            } else {
+                Err(format!("url \"{url}\" must start with http:// or https://"))
+            }
+        };
+        match self {
+            SinkConfig::Desktop => Ok(()),
+            SinkConfig::Webhook { url } | SinkConfig::Ntfy { url, .. } => check_url(url),
+            SinkConfig::Gotify { url, token } => {
+                check_url(url)?;
+                if token.trim().is_empty() {
+                    return Err("gotify needs a non-empty token".to_string());
+                }
+                Ok(())
+            }
+            SinkConfig::Command { command } => {
+                if command.trim().is_empty() {
+                    Err("command is empty".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+        }
+    }
+}
+
+pub fn default_sinks() -> Vec<SinkConfig> {
+    vec![SinkConfig::Desktop]
+}
+
+/// Sends `notice` through every sink, returning one message per failure.
+pub fn dispatch(sinks: &[SinkConfig], notice: &Notice) -> Vec<String> {
+    sinks
+        .iter()
+        .map(SinkConfig::build)
+        .filter_map(|sink| {
+            sink.send(notice)
+                .err()
+                .map(|e| format!("{}: {}", sink.name(), e))
+        })
+        .collect()
+}
+
+fn http_agent() -> ureq::Agent {
+    ureq::AgentBuilder::new().timeout(HTTP_TIMEOUT).build()
+}
+
+/// Just the host (and port) of `url`. Sink names end up in the notification
+/// log, and paths and queries often carry tokens: ntfy topics, webhook
+/// secrets.
+fn host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    authority.rsplit('@').next().unwrap_or(authority)
+}
+
+/// What went wrong, without the URL ureq puts in its own message.
+fn http_error(error: ureq::Error) -> String {
+    match error {
+        ureq::Error::Status(code, _) => format!("status code {code}"),
+        ureq::Error::Transport(transport) => match transport.message() {
+            Some(message) => format!("{}: {message}", transport.kind()),
+            None => transport.kind().to_string(),
+        },
+    }
+}
+
+pub struct DesktopSink;
+
+impl NotificationSink for DesktopSink {
+    fn name(&self) -> String {
+        "desktop".to_string()
+    }
+
+    fn send(&self, notice: &Notice) -> Result<(), String> {
+        let mut notification = Notification::new();
+        notification.summary(notice.title).body(notice.message).timeout(0);
+        // urgency() is a Linux/D-Bus-only builder method — macOS's
+        // notification backend doesn't expose it.
+        #[cfg(not(target_os = "macos"))]
+        notification.urgency(notify_rust::Urgency::Normal);
+        notification.show().map(|_| ()).map_err(|e| e.to_string())
+    }
+}
+
+pub struct WebhookSink {
+    pub url: String,
+}
+
+impl NotificationSink for WebhookSink {
+    fn name(&self) -> String {
+        format!("webhook {}", host(&self.url))
+    }
+
+    fn send(&self, notice: &Notice) -> Result<(), String> {
+        let payload = serde_json::json!({
+            "title": notice.title,
+            "message": notice.message,
+            "trigger": notice.trigger.as_str(),
+            "done": notice.done,
+            "total": notice.total,
+            "percent": notice.percent(),
+        });
+        http_agent()
+            .post(&self.url)
+            .send_json(payload)
+            .map(|_| ())
+            .map_err(http_error)
+    }
+}
+
+pub struct NtfySink {
+    pub url: String,
+    pub token: Option<String>,
+}
+
+impl NotificationSink for NtfySink {
+    fn name(&self) -> String {
+        format!("ntfy {}", host(&self.url))
+    }
+
+    fn send(&self, notice: &Notice) -> Result<(), String> {
+        // The title goes in the query string: headers can't carry the
+        // non-ASCII characters digest titles use.
+        let mut request = http_agent()
+            .post(&self.url)
+            .query("title", notice.title)
+            .set("Tags", notice.trigger.as_str());
+        if let Some(token) = &self.token {
+            request = request.set("Authorization", &format!("Bearer {token}"));
+        }
+        request
+            .send_string(notice.message)
+            .map(|_| ())
+            .map_err(http_error)
+    }
+}
+
+pub struct GotifySink {
+    pub url: String,
+    pub token: String,
+}
+
+impl NotificationSink for GotifySink {
+    fn name(&self) -> String {
+        format!("gotify {}", host(&self.url))
+    }
+
+    fn send(&self, notice: &Notice) -> Result<(), String> {
+        let endpoint = format!("{}/message", self.url.trim_end_matches('/'));
+        http_agent()
+            .post(&endpoint)
+            .set("X-Gotify-Key", &self.token)
+            .send_json(serde_json::json!({
+                "title": notice.title,
+                "message": notice.message,
+                "priority": 5,
+            }))
+            .map(|_| ())
+            .map_err(http_error)
+    }
+}
+
+pub struct CommandSink {
+    pub command: String,
+}
+
+impl NotificationSink for CommandSink {
+    /// Only the program: arguments can hold tokens too.
+    fn name(&self) -> String {
+        let program = self.command.split_whitespace().next().unwrap_or_default();
+        format!("command `{program}`")
+    }
+
+    fn send(&self, notice: &Notice) -> Result<(), String> {
+        #[cfg(windows)]
+        let mut shell = {
+            let mut c = Command::new("cmd");
+            c.arg("/C");
+            c
+        };
+        #[cfg(not(windows))]
+        let mut shell = {
+            let mut c = Command::new("sh");
+            c.arg("-c");
+            c
+        };
+        let status = shell
+            .arg(&self.command)
+            .env("FLOW_STATE_TITLE", notice.title)
+            .env("FLOW_STATE_MESSAGE", notice.message)
+            .env("FLOW_STATE_TRIGGER", notice.trigger.as_str())
+            .env("FLOW_STATE_DONE", notice.done.to_string())
+            .env("FLOW_STATE_TOTAL", notice.total.to_string())
+            .env("FLOW_STATE_PERCENT", format!("{:.0}", notice.percent()))
+            .status()
+            .map_err(|e| e.to_string())?;
+        if status.success() {
+            Ok(())
+        } else {
+            Err(format!("exited with {status}"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    /// A received request: lowercased header lines and the body.
+    struct Request {
+        head: String,
+        body: String,
+    }
+
+    /// Answers one request on a free local port with `status`, handing back
+    /// what it received.
+    fn serve_once(status: &'static str) -> (String, JoinHandle<Request>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+            let mut head = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                head.push_str(&line.to_lowercase());
+            }
+            let length = head
+                .lines()
+                .find_map(|l| l.strip_prefix("content-length:"))
+                .map_or(0, |n| n.trim().parse().unwrap());
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let response =
+                format!("HTTP/1.1 {status}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n");
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+            Request {
+                head,
+                body: String::from_utf8(body).unwrap(),
+            }
+        });
+        (url, handle)
+    }
+
+    fn notice() -> Notice<'static> {
+        Notice {
+            title: "flow_state reminder",
+            message: "Nearly there",
+            trigger: NotificationTrigger::High,
+            done: 3,
+            total: 4,
+        }
+    }
+
+    #[test]
+    fn webhook_posts_json_payload() {
+        let (url, server) = serve_once("200 OK");
+        let errors = dispatch(
+            &[SinkConfig::Webhook {
+                url: format!("{url}/hook"),
+            }],
+            &notice(),
+        );
+        let request = server.join().unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+        assert!(request.head.starts_with("post /hook "));
+        let payload: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "title": "flow_state reminder",
+                "message": "Nearly there",
+                "trigger": "high",
+                "done": 3,
+                "total": 4,
+                "percent": 75.0,
+            })
+        );
+    }
+
+    #[test]
+    fn ntfy_sends_message_as_body_with_headers() {
+        let (url, server) = serve_once("200 OK");
+        let sink = SinkConfig::Ntfy {
+            url: format!("{url}/habits"),
+            token: Some("secret".to_string()),
+        };
+        let notice = Notice {
+            title: "Weekly reflection · Oct 12",
+            ..notice()
+        };
+        let errors = dispatch(&[sink], &notice);
+        let request = server.join().unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+        assert!(request
+            .head
+            .starts_with("post /habits?title=weekly+reflection+%c2%b7+oct+12 "));
+        assert!(request.head.contains("tags: high\r\n"));
+        assert!(request.head.contains("authorization: bearer secret\r\n"));
+        assert_eq!(request.body, "Nearly there");
+    }
+
+    #[test]
+    fn gotify_posts_to_message_endpoint() {
+        let (url, server) = serve_once("200 OK");
+        let sink = SinkConfig::Gotify {
+            url: format!("{url}/"),
+            token: "app-token".to_string(),
+        };
+        let errors = dispatch(&[sink], &notice());
+        let request = server.join().unwrap();
+        assert!(errors.is_empty(), "{errors:?}");
+        assert!(request.head.starts_with("post /message "));
+        assert!(request.head.contains("x-gotify-key: app-token\r\n"));
+        let payload: serde_json::Value = serde_json::from_str(&request.body).unwrap();
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "title": "flow_state reminder",
+                "message": "Nearly there",
+                "priority": 5,
+            })
+        );
+    }
+
+    #[test]
+    fn failures_are_reported_per_sink() {
+        let (url, server) = serve_once("500 Internal Server Error");
+        let refused = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+        let errors = dispatch(
+            &[
+                SinkConfig::Webhook {
+                    url: format!("{url}/hooks/secret-token"),
+                },
+                SinkConfig::Ntfy {
+                    url: format!("{refused}/private-topic"),
+                    token: None,
+                },
+            ],
+            &notice(),
+        );
+        server.join().unwrap();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0],
+            format!("webhook {}: status code 500", host(&url))
+        );
+        assert!(errors[1].starts_with(&format!("ntfy {}: ", host(&refused))));
+        for error in &errors {
+            assert!(!error.contains("secret-token") && !error.contains("private-topic"));
+        }
+    }
+
+    #[test]
+    fn names_show_only_the_host() {
+        assert_eq!(host("https://ntfy.sh/my-private-topic?auth=abc"), "ntfy.sh");
+        assert_eq!(
+            host("http://user:pw@example.com:8080#x"),
+            "example.com:8080"
+        );
+        let sink = SinkConfig::Gotify {
+            url: "https://gotify.example.com/".to_string(),
+            token: "AbCdEf123".to_string(),
+        };
+        assert_eq!(sink.build().name(), "gotify gotify.example.com");
+        let sink = SinkConfig::Command {
+            command: "curl -H 'token: abc' https://example.com".to_string(),
+        };
+        assert_eq!(sink.build().name(), "command `curl`");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_gets_the_notice_in_env_vars() {
+        let out = std::env::temp_dir().join(format!("flow_state-sink-{}", std::process::id()));
+        let command = format!(
+            "printf '%s|%s|%s|%s|%s|%s' \"$FLOW_STATE_TITLE\" \"$FLOW_STATE_MESSAGE\" \
+             \"$FLOW_STATE_TRIGGER\" \"$FLOW_STATE_DONE\" \"$FLOW_STATE_TOTAL\" \
+             \"$FLOW_STATE_PERCENT\" > '{}'",
+            out.display()
+        );
+        let errors = dispatch(&[SinkConfig::Command { command }], &notice());
+        let written = std::fs::read_to_string(&out).unwrap();
+        let _ = std::fs::remove_file(&out);
+        assert!(errors.is_empty(), "{errors:?}");
+        assert_eq!(written, "flow_state reminder|Nearly there|high|3|4|75");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_failure_reports_the_exit_status() {
+        let errors = dispatch(
+            &[SinkConfig::Command {
+                command: "exit 3".to_string(),
+            }],
+            &notice(),
+        );
+        assert_eq!(errors, ["command `exit`: exited with exit status: 3"]);
+    }
+}
@@ -1,18 +1,30 @@
 use std::{
     collections::HashSet,
-    fs::{create_dir_all, read_to_string, write},
+    fs::{self, create_dir_all, read_to_string, write},
     io,
+    path::PathBuf,
+    time::SystemTime,
 };
 
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    app::AppError,
-    habit::{Habit, HabitType},
+    app::{AppError, SortMode},
+    digest::{DigestSettings, DigestState},
+    habit::{Habit, HabitType, TimeOfDay},
+    keymap::{Keymap, KeymapFile},
+    notifications::NotificationRecord,
+    sinks::{self, SinkConfig},
+    theme::{Theme, ThemeFile},
 };
 
+/// Oldest entries are dropped past this, so the log can't grow forever.
+const MAX_LOG_ENTRIES: usize = 500;
+
+/// Every field is optional; anything left out falls back to `Default`.
 #[derive(Serialize, Deserialize, Clone)]
+#[serde(default, deny_unknown_fields)]
 pub struct NotificationSettings {
     pub enable: bool,
     pub hour: usize,
@@ -21,17 +33,90 @@ pub struct NotificationSettings {
     pub high_threshold: usize,
     /// Hours past midnight that still count as "yesterday" (e.g. 2 lets a
     /// night owl's day run until 2am). 0 = day resets at local midnight.
-    #[serde(default)]
     pub day_cutoff_hour: u32,
+    /// Where notifications are delivered. Defaults to a desktop popup.
+    pub sinks: Vec<SinkConfig>,
+    pub digest: DigestSettings,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        NotificationSettings {
+            enable: false,
+            hour: 20,
+            minute: 0,
+            low_threshold: 20,
+            high_threshold: 80,
+            day_cutoff_hour: 0,
+            sinks: sinks::default_sinks(),
+            digest: DigestSettings::default(),
+        }
+    }
+}
+
+impl NotificationSettings {
+    /// Values that parse but can't work, described so the user can fix them.
+    pub fn validate(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+        if self.hour > 23 {
+            errors.push(format!("hour = {} is out of range, use 0–23", self.hour));
+        }
+        if self.minute > 59 {
+            errors.push(format!("minute = {} is out of range, use 0–59", self.minute));
+        }
+        if self.low_threshold > 100 {
+            errors.push(format!(
+                "low_threshold = {} is a percentage, use 0–100",
+                self.low_threshold
+            ));
+        }
+        if self.high_threshold > 100 {
+            errors.push(format!(
+                "high_threshold = {} is a percentage, use 0–100",
+                self.high_threshold
+            ));
+        }
+        if self.low_threshold >= self.high_threshold {
+            errors.push(format!(
+                "low_threshold ({}) must be below high_threshold ({})",
+                self.low_threshold, self.high_threshold
+            ));
+        }
+        if self.day_cutoff_hour > 23 {
+            errors.push(format!(
+                "day_cutoff_hour = {} is out of range, use 0–23",
+                self.day_cutoff_hour
+            ));
+        }
+        for (i, sink) in self.sinks.iter().enumerate() {
+            if let Err(e) = sink.validate() {
+                errors.push(format!("sinks[{}]: {}", i + 1, e));
+            }
+        }
+        errors
+    }
 }
 
+#[derive(Serialize, Deserialize, Clone, Default)]
+struct NotificationLog {
+    #[serde(default)]
+    entries: Vec<NotificationRecord>,
+}
+
+/// Everything in `habits.toml`. The lists are kept in the user's own order.
 #[derive(Serialize, Deserialize, Clone)]
-struct HabitsData {
-    build_habits: Vec<Habit>,
-    avoid_habits: Vec<Habit>,
+pub struct HabitsData {
+    pub build_habits: Vec<Habit>,
+    pub avoid_habits: Vec<Habit>,
+    #[serde(default)]
+    pub sort: SortMode,
 }
 
-pub fn save_habits(build_habits: &[Habit], avoid_habits: &[Habit]) -> Result<(), AppError> {
+pub fn save_habits(
+    build_habits: &[Habit],
+    avoid_habits: &[Habit],
+    sort: SortMode,
+) -> Result<(), AppError> {
     let config_dir = match dirs::config_dir() {
         Some(path) => Ok(path.join("flow_state")),
         None => Err(io::Error::new(
@@ -42,16 +127,26 @@ pub fn save_habits(build_habits: &[Habit], avoid_habits: &[Habit]) -> Result<(),
 
     create_dir_all(&config_dir)?;
 
-    let habits_data = HabitsData {
+    let mut habits_data = HabitsData {
         build_habits: build_habits.to_vec(),
         avoid_habits: avoid_habits.to_vec(),
+        sort,
     };
+    // Overlapping holidays saved by older versions are merged on the way out.
+    for habit in habits_data.build_habits.iter_mut().chain(&mut habits_data.avoid_habits) {
+        habit.normalize_holidays();
+    }
     let toml_string = toml::to_string(&habits_data)?;
     write(config_dir.join("habits.toml"), toml_string)?;
     Ok(())
 }
 
 pub fn load_habits() -> Result<(Vec<Habit>, Vec<Habit>), AppError> {
+    let data = load_habits_data()?;
+    Ok((data.build_habits, data.avoid_habits))
+}
+
+pub fn load_habits_data() -> Result<HabitsData, AppError> {
     let config_dir = match dirs::config_dir(){
         Some(path)=>Ok(path.join("flow_state")) ,
         None => Err(io::Error::new(io::ErrorKind::NotFound, "config directory not found"))
@@ -62,10 +157,14 @@ pub fn load_habits() -> Result<(Vec<Habit>, Vec<Habit>), AppError> {
 
     if habits_file.exists() {
         let content = read_to_string(habits_file)?;
-        let habits_data: HabitsData = toml::from_str(&content)?;
-        Ok((habits_data.build_habits, habits_data.avoid_habits))
+        Ok(toml::from_str(&content)?)
     } else {
-        Ok(populate_dummy_data())
+        let (build_habits, avoid_habits) = populate_dummy_data();
+        Ok(HabitsData {
+            build_habits,
+            avoid_habits,
+            sort: SortMode::default(),
+        })
     }
 }
 
@@ -75,15 +174,25 @@ fn populate_dummy_data() -> (Vec<Habit>, Vec<Habit>) {
             name: "Morning run".to_string(),
             habit_type: HabitType::Build,
             days_completed: HashSet::new(),
-            created: NaiveDate::from_ymd_opt(2025, 06, 12).unwrap(),
+            created: NaiveDate::from_ymd_opt(2025, 6, 12).unwrap(),
             holidays: Vec::new(),
+            slips: Vec::new(),
+            tags: vec!["health".to_string()],
+            paused: None,
+            archived: None,
+            time_of_day: Some(TimeOfDay::Morning),
         },
         Habit {
             name: "Read 10 pages".to_string(),
             habit_type: HabitType::Build,
             days_completed: HashSet::new(),
-            created: NaiveDate::from_ymd_opt(2025, 06, 12).unwrap(),
+            created: NaiveDate::from_ymd_opt(2025, 6, 12).unwrap(),
             holidays: Vec::new(),
+            slips: Vec::new(),
+            tags: vec!["learning".to_string()],
+            paused: None,
+            archived: None,
+            time_of_day: Some(TimeOfDay::Evening),
         },
     ];
     let avoid_habits = vec![
@@ -91,44 +200,303 @@ fn populate_dummy_data() -> (Vec<Habit>, Vec<Habit>) {
             name: "Social media scrolling".to_string(),
             habit_type: HabitType::Avoid,
             days_completed: HashSet::new(),
-            created: NaiveDate::from_ymd_opt(2025, 06, 12).unwrap(),
+            created: NaiveDate::from_ymd_opt(2025, 6, 12).unwrap(),
             holidays: Vec::new(),
+            slips: Vec::new(),
+            tags: vec!["focus".to_string()],
+            paused: None,
+            archived: None,
+            time_of_day: None,
         },
         Habit {
             name: "Late-night snacking".to_string(),
             habit_type: HabitType::Avoid,
             days_completed: HashSet::new(),
-            created: NaiveDate::from_ymd_opt(2025, 06, 12).unwrap(),
+            created: NaiveDate::from_ymd_opt(2025, 6, 12).unwrap(),
             holidays: Vec::new(),
+            slips: Vec::new(),
+            tags: vec!["health".to_string()],
+            paused: None,
+            archived: None,
+            time_of_day: Some(TimeOfDay::Night),
         },
     ];
     (build_habits, avoid_habits)
 }
 
+pub fn notification_settings_path() -> Result<PathBuf, AppError> {
+    match dirs::config_dir() {
+        Some(path) => Ok(path.join("flow_state").join("notification.toml")),
+        None => Err(io::Error::new(io::ErrorKind::NotFound, "config directory not found").into()),
+    }
+}
+
+/// Loads and validates `notification.toml`. A missing file gives the
+/// defaults; a malformed or invalid one is an `AppError::Config`.
 pub fn load_notification_settings() -> Result<NotificationSettings, AppError> {
+    let notification_file = notification_settings_path()?;
+
+    if notification_file.exists() {
+        let content = read_to_string(&notification_file)?;
+        let notification_data: NotificationSettings = toml::from_str(&content)
+            .map_err(|e| AppError::Config(format!("{}: {}", notification_file.display(), e)))?;
+        let errors = notification_data.validate();
+        if !errors.is_empty() {
+            return Err(AppError::Config(format!(
+                "{}:\n  {}",
+                notification_file.display(),
+                errors.join("\n  ")
+            )));
+        }
+        Ok(notification_data)
+    } else {
+        Ok(NotificationSettings::default())
+    }
+}
+
+/// Loads `theme.toml`, falling back to the dark preset when it's missing.
+/// Colors are downgraded to what the terminal supports either way.
+pub fn load_theme() -> Result<Theme, AppError> {
     let config_dir = match dirs::config_dir(){
         Some(path)=>Ok(path.join("flow_state")) ,
         None => Err(io::Error::new(io::ErrorKind::NotFound, "config directory not found"))
     }?;
 
-    let notification_file = config_dir.join("notification.toml");
+    let theme_file = config_dir.join("theme.toml");
 
-    if notification_file.exists() {
-        let content = read_to_string(notification_file)?;
-        let notification_data: NotificationSettings = toml::from_str(&content)?;
-        Ok(notification_data)
+    let file = if theme_file.exists() {
+        let content = read_to_string(&theme_file)?;
+        toml::from_str(&content)
+            .map_err(|e| AppError::Config(format!("{}: {}", theme_file.display(), e)))?
+    } else {
+        ThemeFile::default()
+    };
+    Theme::from_file(&file)
+        .map_err(|e| AppError::Config(format!("{}:\n  {}", theme_file.display(), e)))
+}
+
+/// Loads `keymap.toml`, falling back to the default bindings when it's missing.
+pub fn load_keymap() -> Result<Keymap, AppError> {
+    let config_dir = match dirs::config_dir(){
+        Some(path)=>Ok(path.join("flow_state")) ,
+        None => Err(io::Error::new(io::ErrorKind::NotFound, "config directory not found"))
+    }?;
+
+    let keymap_file = config_dir.join("keymap.toml");
+
+    let file = if keymap_file.exists() {
+        let content = read_to_string(&keymap_file)?;
+        toml::from_str(&content)
+            .map_err(|e| AppError::Config(format!("{}: {}", keymap_file.display(), e)))?
+    } else {
+        KeymapFile::default()
+    };
+    Keymap::from_file(&file)
+        .map_err(|e| AppError::Config(format!("{}:\n  {}", keymap_file.display(), e)))
+}
+
+/// Notices when `notification.toml` is created, edited or removed, so
+/// settings can be reloaded without a restart.
+pub struct ConfigWatcher {
+    modified: Option<SystemTime>,
+}
+
+impl ConfigWatcher {
+    pub fn new() -> Self {
+        ConfigWatcher {
+            modified: Self::modified(),
+        }
+    }
+
+    fn modified() -> Option<SystemTime> {
+        let path = notification_settings_path().ok()?;
+        fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    pub fn changed(&mut self) -> bool {
+        let modified = Self::modified();
+        if modified != self.modified {
+            self.modified = modified;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+pub fn load_notification_log() -> Result<Vec<NotificationRecord>, AppError> {
+    let config_dir = match dirs::config_dir(){
+        Some(path)=>Ok(path.join("flow_state")) ,
+        None => Err(io::Error::new(io::ErrorKind::NotFound, "config directory not found"))
+    }?;
+
+    let log_file = config_dir.join("notification_log.toml");
+
+    if log_file.exists() {
+        let content = read_to_string(log_file)?;
+        let log: NotificationLog = toml::from_str(&content)?;
+        Ok(log.entries)
     } else {
-        Ok(default_notification_settings())
+        Ok(Vec::new())
     }
 }
 
-fn default_notification_settings() -> NotificationSettings {
-    NotificationSettings {
-        enable: false,
-        hour: 0,
-        minute: 0,
-        low_threshold: 20,
-        high_threshold: 80,
-        day_cutoff_hour: 0,
+pub fn save_notification_log(records: &[NotificationRecord]) -> Result<(), AppError> {
+    let config_dir = match dirs::config_dir() {
+        Some(path) => Ok(path.join("flow_state")),
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "config directory not found",
+        )),
+    }?;
+
+    create_dir_all(&config_dir)?;
+
+    let skip = records.len().saturating_sub(MAX_LOG_ENTRIES);
+    let log = NotificationLog {
+        entries: records[skip..].to_vec(),
+    };
+    let toml_string = toml::to_string(&log)?;
+    write(config_dir.join("notification_log.toml"), toml_string)?;
+    Ok(())
+}
+
+pub fn append_notification_record(record: NotificationRecord) -> Result<(), AppError> {
+    let mut records = load_notification_log()?;
+    records.push(record);
+    save_notification_log(&records)
+}
+
+pub fn load_digest_state() -> Result<DigestState, AppError> {
+    let config_dir = match dirs::config_dir(){
+        Some(path)=>Ok(path.join("flow_state")) ,
+        None => Err(io::Error::new(io::ErrorKind::NotFound, "config directory not found"))
+    }?;
+
+    let state_file = config_dir.join("digest_state.toml");
+
+    if state_file.exists() {
+        let content = read_to_string(state_file)?;
+        Ok(toml::from_str(&content)?)
+    } else {
+        Ok(DigestState::default())
+    }
+}
+
+pub fn save_digest_state(state: &DigestState) -> Result<(), AppError> {
+    let config_dir = match dirs::config_dir() {
+        Some(path) => Ok(path.join("flow_state")),
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "config directory not found",
+        )),
+    }?;
+
+    create_dir_all(&config_dir)?;
+    write(config_dir.join("digest_state.toml"), toml::to_string(state)?)?;
+    Ok(())
+}
+
+pub fn save_digest_markdown(file_name: &str, markdown: &str) -> Result<(), AppError> {
+    let digest_dir = match dirs::config_dir() {
+        Some(path) => Ok(path.join("flow_state").join("digests")),
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "config directory not found",
+        )),
+    }?;
+
+    create_dir_all(&digest_dir)?;
+    write(digest_dir.join(file_name), markdown)?;
+    Ok(())
+}
+
+/// Writes an export under `.config/flow_state/exports/` and returns its path.
+pub fn save_export(file_name: &str, contents: &str) -> Result<PathBuf, AppError> {
+    let export_dir = match dirs::config_dir() {
+        Some(path) => Ok(path.join("flow_state").join("exports")),
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "config directory not found",
+        )),
+    }?;
+
+    create_dir_all(&export_dir)?;
+    let path = export_dir.join(file_name);
+    write(&path, contents)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sinks::SinkConfig;
+
+    #[test]
+    fn defaults_are_valid() {
+        assert!(NotificationSettings::default().validate().is_empty());
+    }
+
+    #[test]
+    fn out_of_range_values_are_each_reported() {
+        let settings = NotificationSettings {
+            hour: 24,
+            minute: 60,
+            day_cutoff_hour: 24,
+            ..NotificationSettings::default()
+        };
+        assert_eq!(
+            settings.validate(),
+            [
+                "hour = 24 is out of range, use 0–23",
+                "minute = 60 is out of range, use 0–59",
+                "day_cutoff_hour = 24 is out of range, use 0–23",
+            ]
+        );
+    }
+
+    #[test]
+    fn thresholds_must_be_percentages_in_order() {
+        let settings = NotificationSettings {
+            low_threshold: 50,
+            high_threshold: 50,
+            ..NotificationSettings::default()
+        };
+        assert_eq!(
+            settings.validate(),
+            ["low_threshold (50) must be below high_threshold (50)"]
+        );
+
+        let settings = NotificationSettings {
+            low_threshold: 20,
+            high_threshold: 120,
+            ..NotificationSettings::default()
+        };
+        assert_eq!(
+            settings.validate(),
+            ["high_threshold = 120 is a percentage, use 0–100"]
+        );
+    }
+
+    #[test]
+    fn bad_sinks_are_numbered_from_one() {
+        let settings = NotificationSettings {
+            sinks: vec![
+                SinkConfig::Desktop,
+                SinkConfig::Webhook { url: "example.com/hook".to_string() },
+                SinkConfig::Gotify {
+                    url: "https://gotify.example.com".to_string(),
+                    token: " ".to_string(),
+                },
+            ],
+            ..NotificationSettings::default()
+        };
+        assert_eq!(
+            settings.validate(),
+            [
+                "sinks[2]: url \"example.com/hook\" must start with http:// or https://",
+                "sinks[3]: gotify needs a non-empty token",
+            ]
+        );
     }
 }
@@ -0,0 +1,266 @@
+use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::text::Span;
+
+/// What a key or paste did to a field.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Edit {
+    /// Not an editing key; the form may handle it.
+    Ignored,
+    /// Only the cursor moved.
+    Moved,
+    Changed,
+    /// Text was cut off at the field's maximum length.
+    TooLong,
+}
+
+/// A single-line input with a cursor, word-wise editing and a length
+/// limit, shared by the forms.
+#[derive(Default, Clone)]
+pub struct TextField {
+    text: String,
+    /// In chars, from 0 to the text's length.
+    cursor: usize,
+    max_len: usize,
+}
+
+impl TextField {
+    pub fn new(max_len: usize) -> Self {
+        TextField {
+            max_len,
+            ..TextField::default()
+        }
+    }
+
+    /// Starts out holding `text`, with the cursor at its end.
+    pub fn with_text(text: &str, max_len: usize) -> Self {
+        let mut field = TextField::new(max_len);
+        field.insert_str(text);
+        field
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    fn byte_at(&self, char_index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(char_index)
+            .map_or(self.text.len(), |(i, _)| i)
+    }
+
+    /// Inserts at the cursor, up to the length limit. Line breaks and tabs
+    /// become spaces, since fields are one line.
+    pub fn insert_str(&mut self, text: &str) -> Edit {
+        let room = self.max_len.saturating_sub(self.len());
+        let incoming: Vec<char> = text
+            .chars()
+            .filter(|c| *c != '\r')
+            .map(|c| if c.is_whitespace() { ' ' } else { c })
+            .filter(|c| !c.is_control())
+            .collect();
+        let kept: String = incoming.iter().take(room).collect();
+        let at = self.byte_at(self.cursor);
+        self.text.insert_str(at, &kept);
+        self.cursor += kept.chars().count();
+        if kept.chars().count() < incoming.len() {
+            Edit::TooLong
+        } else {
+            Edit::Changed
+        }
+    }
+
+    /// Removes the chars between `from` and `to` (char indices) and leaves
+    /// the cursor at `from`.
+    fn remove(&mut self, from: usize, to: usize) -> Edit {
+        if from == to {
+            return Edit::Moved;
+        }
+        let range = self.byte_at(from)..self.byte_at(to);
+        self.text.replace_range(range, "");
+        self.cursor = from;
+        Edit::Changed
+    }
+
+    /// Start of the word before the cursor, skipping spaces first.
+    fn word_start(&self) -> usize {
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut i = self.cursor;
+        while i > 0 && !chars[i - 1].is_alphanumeric() {
+            i -= 1;
+        }
+        while i > 0 && chars[i - 1].is_alphanumeric() {
+            i -= 1;
+        }
+        i
+    }
+
+    /// End of the word after the cursor, skipping spaces first.
+    fn word_end(&self) -> usize {
+        let chars: Vec<char> = self.text.chars().collect();
+        let mut i = self.cursor;
+        while i < chars.len() && !chars[i].is_alphanumeric() {
+            i += 1;
+        }
+        while i < chars.len() && chars[i].is_alphanumeric() {
+            i += 1;
+        }
+        i
+    }
+
+    fn move_to(&mut self, cursor: usize) -> Edit {
+        self.cursor = cursor;
+        Edit::Moved
+    }
+
+    /// Readline-style editing: arrows, Home/End (or Ctrl+A/E), Backspace
+    /// and Delete, with Ctrl (or Alt) working a word at a time, and
+    /// Ctrl+W/U/K to cut a word, to the start, or to the end.
+    pub fn handle_key(&mut self, key: &KeyEvent) -> Edit {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let word = key.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        match key.code {
+            KeyCode::Char('a') if ctrl => self.move_to(0),
+            KeyCode::Char('e') if ctrl => self.move_to(self.len()),
+            KeyCode::Char('w') if ctrl => self.remove(self.word_start(), self.cursor),
+            KeyCode::Char('u') if ctrl => self.remove(0, self.cursor),
+            KeyCode::Char('k') if ctrl => self.remove(self.cursor, self.len()),
+            KeyCode::Char(_) if word => Edit::Ignored,
+            KeyCode::Char(c) => self.insert_str(&c.to_string()),
+            KeyCode::Backspace if word => self.remove(self.word_start(), self.cursor),
+            KeyCode::Backspace => self.remove(self.cursor.saturating_sub(1), self.cursor),
+            KeyCode::Delete if word => self.remove(self.cursor, self.word_end()),
+            KeyCode::Delete => self.remove(self.cursor, (self.cursor + 1).min(self.len())),
+            KeyCode::Left if word => self.move_to(self.word_start()),
+            KeyCode::Left => self.move_to(self.cursor.saturating_sub(1)),
+            KeyCode::Right if word => self.move_to(self.word_end()),
+            KeyCode::Right => self.move_to((self.cursor + 1).min(self.len())),
+            KeyCode::Home => self.move_to(0),
+            KeyCode::End => self.move_to(self.len()),
+            _ => Edit::Ignored,
+        }
+    }
+
+    /// The part of the text that fits in `width` columns with the cursor in
+    /// view, and the cursor's column within it.
+    pub fn view(&self, width: usize) -> (String, u16) {
+        let chars: Vec<char> = self.text.chars().collect();
+        let col = |from: usize, to: usize| {
+            Span::raw(chars[from..to].iter().collect::<String>()).width()
+        };
+        // Scroll just far enough that the cursor, and a space for it, fit.
+        let mut start = 0;
+        while start < self.cursor && col(start, self.cursor) >= width.max(1) {
+            start += 1;
+        }
+        let mut shown = String::new();
+        let mut used = 0;
+        for c in &chars[start..] {
+            let w = Span::raw(c.to_string()).width();
+            if used + w > width {
+                break;
+            }
+            shown.push(*c);
+            used += w;
+        }
+        (shown, col(start, self.cursor) as u16)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn ctrl(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    #[test]
+    fn edits_multibyte_text_by_char() {
+        let mut field = TextField::with_text("café", 20);
+        field.handle_key(&key(KeyCode::Left));
+        assert_eq!(field.handle_key(&key(KeyCode::Char('ü'))), Edit::Changed);
+        assert_eq!(field.text(), "cafüé");
+        assert_eq!(field.handle_key(&key(KeyCode::Delete)), Edit::Changed);
+        assert_eq!(field.text(), "cafü");
+        field.handle_key(&key(KeyCode::Backspace));
+        assert_eq!(field.text(), "caf");
+        field.insert_str("🌊 ok");
+        assert_eq!(field.text(), "caf🌊 ok");
+        field.handle_key(&key(KeyCode::Home));
+        field.handle_key(&key(KeyCode::Delete));
+        assert_eq!(field.text(), "af🌊 ok");
+    }
+
+    #[test]
+    fn ctrl_w_cuts_the_word_before_the_cursor() {
+        let mut field = TextField::with_text("read ten pages  ", 40);
+        field.handle_key(&ctrl('w'));
+        assert_eq!(field.text(), "read ten ");
+        field.handle_key(&ctrl('w'));
+        assert_eq!(field.text(), "read ");
+        field.handle_key(&ctrl('w'));
+        assert_eq!(field.text(), "");
+        assert_eq!(field.handle_key(&ctrl('w')), Edit::Moved);
+    }
+
+    #[test]
+    fn ctrl_u_and_k_cut_around_the_cursor() {
+        let mut field = TextField::with_text("morning run", 40);
+        for _ in 0..4 {
+            field.handle_key(&key(KeyCode::Left));
+        }
+        field.handle_key(&ctrl('k'));
+        assert_eq!(field.text(), "morning");
+        field.handle_key(&key(KeyCode::Left));
+        field.handle_key(&ctrl('u'));
+        assert_eq!(field.text(), "g");
+        field.insert_str("o");
+        assert_eq!(field.text(), "og");
+    }
+
+    #[test]
+    fn paste_is_cut_at_max_len() {
+        let mut field = TextField::with_text("ab", 5);
+        assert_eq!(field.insert_str("cdéfg"), Edit::TooLong);
+        assert_eq!(field.text(), "abcdé");
+        assert_eq!(field.handle_key(&key(KeyCode::Char('x'))), Edit::TooLong);
+        assert_eq!(field.text(), "abcdé");
+
+        let mut field = TextField::new(10);
+        assert_eq!(field.insert_str("one\r\ntwo\tthree"), Edit::TooLong);
+        assert_eq!(field.text(), "one two th");
+    }
+
+    #[test]
+    fn view_scrolls_to_keep_the_cursor_visible() {
+        let mut field = TextField::with_text("abcdefghij", 20);
+        assert_eq!(field.view(20), ("abcdefghij".to_string(), 10));
+        assert_eq!(field.view(5), ("ghij".to_string(), 4));
+        field.handle_key(&key(KeyCode::Home));
+        assert_eq!(field.view(5), ("abcde".to_string(), 0));
+        field.handle_key(&key(KeyCode::End));
+        field.handle_key(&key(KeyCode::Left));
+        assert_eq!(field.view(5), ("fghij".to_string(), 4));
+    }
+
+    #[test]
+    fn view_counts_wide_chars_as_two_columns() {
+        let field = TextField::with_text("日本語テキスト", 20);
+        let (shown, cursor) = field.view(6);
+        assert_eq!(shown, "スト");
+        assert_eq!(cursor, 4);
+    }
+}
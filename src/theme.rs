@@ -0,0 +1,459 @@
+use std::env;
+use std::str::FromStr;
+
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+use crate::glyphs::{Glyphs, ASCII, UNICODE};
+use crate::habit::HabitPattern;
+
+/// Named color roles used across the UI, so no screen hard-codes a color.
+#[derive(Clone)]
+pub struct Theme {
+    /// Emphasized body text.
+    pub text: Color,
+    /// Hints and de-emphasized text.
+    pub muted: Color,
+    /// Panel titles, section headings and footers.
+    pub accent: Color,
+    /// Key names, labels and secondary highlights.
+    pub info: Color,
+    /// Border of the focused form field.
+    pub focus: Color,
+    pub error: Color,
+    pub warning: Color,
+    pub success: Color,
+    pub border: Color,
+    /// Background of the active tab and selected list rows.
+    pub selection: Color,
+    /// Text drawn on top of `selection`, `build` or `avoid` backgrounds.
+    pub highlight_fg: Color,
+    /// Selected-row background in the Build column.
+    pub build: Color,
+    /// Selected-row background in the Avoid column.
+    pub avoid: Color,
+    /// Chaotic, Struggling, Developing, Established, Mastered.
+    pub tiers: [Color; 5],
+    /// No check-ins, then four buckets of increasing completion.
+    pub heatmap: [Color; 5],
+    /// Add shapes alongside color (shaded heatmap cells, tier symbols,
+    /// a selection marker) so nothing is told apart by hue alone.
+    pub cues: bool,
+    /// Symbols to draw with; ASCII for limited terminals.
+    pub glyphs: &'static Glyphs,
+    /// Replace the panels with one linear, top-to-bottom text view.
+    pub screen_reader: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum Preset {
+    #[default]
+    Dark,
+    Light,
+    Solarized,
+    HighContrast,
+    /// Blue/orange palette for red-green (green-weak) color blindness.
+    Deuteranopia,
+    /// Blue/orange palette for red-green (red-weak) color blindness.
+    Protanopia,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorDepth {
+    /// Detect from `COLORTERM` and `TERM`.
+    #[default]
+    Auto,
+    #[serde(rename = "truecolor")]
+    TrueColor,
+    #[serde(rename = "256")]
+    Ansi256,
+    #[serde(rename = "16")]
+    Ansi16,
+}
+
+/// `theme.toml`: a preset plus optional per-role overrides. Colors are
+/// names ("light-green"), hex ("#26a641") or 256-color indexes ("42").
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct ThemeFile {
+    pub preset: Preset,
+    pub color_depth: ColorDepth,
+    /// Non-color cues; defaults to on for the accessibility presets.
+    pub cues: Option<bool>,
+    /// Draw with plain ASCII instead of emoji and box glyphs.
+    pub ascii: bool,
+    /// Linear text layout for screen readers; implies `ascii`.
+    pub screen_reader: bool,
+    pub colors: ColorOverrides,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColorOverrides {
+    pub text: Option<String>,
+    pub muted: Option<String>,
+    pub accent: Option<String>,
+    pub info: Option<String>,
+    pub focus: Option<String>,
+    pub error: Option<String>,
+    pub warning: Option<String>,
+    pub success: Option<String>,
+    pub border: Option<String>,
+    pub selection: Option<String>,
+    pub highlight_fg: Option<String>,
+    pub build: Option<String>,
+    pub avoid: Option<String>,
+    pub tiers: Option<Vec<String>>,
+    pub heatmap: Option<Vec<String>>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::preset(Preset::Dark)
+    }
+}
+
+impl Theme {
+    /// Tier name, prefixed with its symbol when cues are on.
+    pub fn tier_label(&self, pattern: HabitPattern) -> String {
+        if self.cues {
+            format!("{} {}", self.glyphs.tier(pattern), pattern)
+        } else {
+            pattern.to_string()
+        }
+    }
+
+    pub fn preset(preset: Preset) -> Self {
+        match preset {
+            Preset::Dark => Theme {
+                text: Color::White,
+                muted: Color::Gray,
+                accent: Color::LightYellow,
+                info: Color::Cyan,
+                focus: Color::Yellow,
+                error: Color::Red,
+                warning: Color::Yellow,
+                success: Color::Green,
+                border: Color::Reset,
+                selection: Color::Gray,
+                highlight_fg: Color::Black,
+                build: Color::Green,
+                avoid: Color::Red,
+                tiers: [
+                    Color::Red,
+                    Color::Yellow,
+                    Color::Cyan,
+                    Color::LightGreen,
+                    Color::Green,
+                ],
+                heatmap: [
+                    Color::DarkGray,
+                    Color::Rgb(14, 68, 41),
+                    Color::Rgb(0, 109, 50),
+                    Color::Rgb(38, 166, 65),
+                    Color::Rgb(57, 211, 83),
+                ],
+                cues: false,
+                glyphs: &UNICODE,
+                screen_reader: false,
+            },
+            Preset::Light => Theme {
+                text: Color::Black,
+                muted: Color::DarkGray,
+                accent: Color::Rgb(138, 90, 0),
+                info: Color::Blue,
+                focus: Color::Magenta,
+                error: Color::Rgb(178, 34, 34),
+                warning: Color::Rgb(160, 100, 0),
+                success: Color::Rgb(0, 120, 50),
+                border: Color::DarkGray,
+                selection: Color::Rgb(200, 200, 200),
+                highlight_fg: Color::Black,
+                build: Color::Rgb(140, 210, 150),
+                avoid: Color::Rgb(240, 160, 160),
+                tiers: [
+                    Color::Rgb(178, 34, 34),
+                    Color::Rgb(190, 120, 0),
+                    Color::Rgb(0, 120, 160),
+                    Color::Rgb(60, 150, 60),
+                    Color::Rgb(0, 110, 40),
+                ],
+                heatmap: [
+                    Color::Rgb(235, 237, 240),
+                    Color::Rgb(155, 233, 168),
+                    Color::Rgb(64, 196, 99),
+                    Color::Rgb(48, 161, 78),
+                    Color::Rgb(33, 110, 57),
+                ],
+                cues: false,
+                glyphs: &UNICODE,
+                screen_reader: false,
+            },
+            Preset::Solarized => Theme {
+                text: Color::Rgb(238, 232, 213),
+                muted: Color::Rgb(131, 148, 150),
+                accent: Color::Rgb(181, 137, 0),
+                info: Color::Rgb(42, 161, 152),
+                focus: Color::Rgb(203, 75, 22),
+                error: Color::Rgb(220, 50, 47),
+                warning: Color::Rgb(181, 137, 0),
+                success: Color::Rgb(133, 153, 0),
+                border: Color::Rgb(88, 110, 117),
+                selection: Color::Rgb(147, 161, 161),
+                highlight_fg: Color::Rgb(0, 43, 54),
+                build: Color::Rgb(133, 153, 0),
+                avoid: Color::Rgb(220, 50, 47),
+                tiers: [
+                    Color::Rgb(220, 50, 47),
+                    Color::Rgb(181, 137, 0),
+                    Color::Rgb(42, 161, 152),
+                    Color::Rgb(38, 139, 210),
+                    Color::Rgb(133, 153, 0),
+                ],
+                heatmap: [
+                    Color::Rgb(7, 54, 66),
+                    Color::Rgb(48, 78, 30),
+                    Color::Rgb(84, 105, 10),
+                    Color::Rgb(112, 132, 0),
+                    Color::Rgb(153, 178, 0),
+                ],
+                cues: false,
+                glyphs: &UNICODE,
+                screen_reader: false,
+            },
+            Preset::HighContrast => Theme {
+                text: Color::White,
+                muted: Color::White,
+                accent: Color::LightYellow,
+                info: Color::LightCyan,
+                focus: Color::LightYellow,
+                error: Color::LightRed,
+                warning: Color::LightYellow,
+                success: Color::LightGreen,
+                border: Color::White,
+                selection: Color::White,
+                highlight_fg: Color::Black,
+                build: Color::LightGreen,
+                avoid: Color::LightRed,
+                tiers: [
+                    Color::LightRed,
+                    Color::LightYellow,
+                    Color::LightCyan,
+                    Color::LightBlue,
+                    Color::LightGreen,
+                ],
+                heatmap: [
+                    Color::Black,
+                    Color::DarkGray,
+                    Color::Gray,
+                    Color::LightGreen,
+                    Color::White,
+                ],
+                cues: true,
+                glyphs: &UNICODE,
+                screen_reader: false,
+            },
+            Preset::Deuteranopia | Preset::Protanopia => {
+                // Okabe–Ito hues for text roles and viridis for the heatmap:
+                // both stay distinct under red-green color blindness and
+                // also differ in lightness.
+                let (avoid, chaotic) = match preset {
+                    Preset::Protanopia => (Color::Rgb(230, 159, 0), Color::Rgb(230, 159, 0)),
+                    _ => (Color::Rgb(213, 94, 0), Color::Rgb(213, 94, 0)),
+                };
+                Theme {
+                    text: Color::White,
+                    muted: Color::Gray,
+                    accent: Color::Rgb(240, 228, 66),
+                    info: Color::Rgb(86, 180, 233),
+                    focus: Color::Rgb(240, 228, 66),
+                    error: avoid,
+                    warning: Color::Rgb(240, 228, 66),
+                    success: Color::Rgb(0, 114, 178),
+                    border: Color::Reset,
+                    selection: Color::Gray,
+                    highlight_fg: Color::Black,
+                    build: Color::Rgb(86, 180, 233),
+                    avoid,
+                    tiers: [
+                        chaotic,
+                        Color::Rgb(240, 228, 66),
+                        Color::Rgb(204, 121, 167),
+                        Color::Rgb(86, 180, 233),
+                        Color::Rgb(0, 114, 178),
+                    ],
+                    heatmap: [
+                        Color::Rgb(40, 40, 40),
+                        Color::Rgb(59, 82, 139),
+                        Color::Rgb(33, 145, 140),
+                        Color::Rgb(94, 201, 98),
+                        Color::Rgb(253, 231, 37),
+                    ],
+                    cues: true,
+                    glyphs: &UNICODE,
+                    screen_reader: false,
+                }
+            }
+        }
+    }
+
+    /// Builds the theme described by `file`, or explains the first color
+    /// that couldn't be parsed.
+    pub fn from_file(file: &ThemeFile) -> Result<Self, String> {
+        let mut theme = Theme::preset(file.preset);
+        let c = &file.colors;
+        let roles: [(&str, &Option<String>, &mut Color); 13] = [
+            ("text", &c.text, &mut theme.text),
+            ("muted", &c.muted, &mut theme.muted),
+            ("accent", &c.accent, &mut theme.accent),
+            ("info", &c.info, &mut theme.info),
+            ("focus", &c.focus, &mut theme.focus),
+            ("error", &c.error, &mut theme.error),
+            ("warning", &c.warning, &mut theme.warning),
+            ("success", &c.success, &mut theme.success),
+            ("border", &c.border, &mut theme.border),
+            ("selection", &c.selection, &mut theme.selection),
+            ("highlight_fg", &c.highlight_fg, &mut theme.highlight_fg),
+            ("build", &c.build, &mut theme.build),
+            ("avoid", &c.avoid, &mut theme.avoid),
+        ];
+        for (role, value, slot) in roles {
+            if let Some(value) = value {
+                *slot = parse_color(role, value)?;
+            }
+        }
+        if let Some(tiers) = &c.tiers {
+            theme.tiers = parse_ramp("tiers", tiers)?;
+        }
+        if let Some(heatmap) = &c.heatmap {
+            theme.heatmap = parse_ramp("heatmap", heatmap)?;
+        }
+        if let Some(cues) = file.cues {
+            theme.cues = cues;
+        }
+        if file.ascii || file.screen_reader {
+            theme.use_ascii(file.screen_reader);
+        }
+        Ok(theme.with_depth(file.color_depth.resolve()))
+    }
+
+    /// Switches to ASCII glyphs, and optionally the linear screen-reader view.
+    pub fn use_ascii(&mut self, screen_reader: bool) {
+        self.glyphs = &ASCII;
+        self.screen_reader |= screen_reader;
+    }
+
+    /// Downgrades every color the terminal can't show.
+    pub fn with_depth(self, depth: ColorDepth) -> Self {
+        let adapt = |c: Color| adapt_color(c, depth);
+        Theme {
+            text: adapt(self.text),
+            muted: adapt(self.muted),
+            accent: adapt(self.accent),
+            info: adapt(self.info),
+            focus: adapt(self.focus),
+            error: adapt(self.error),
+            warning: adapt(self.warning),
+            success: adapt(self.success),
+            border: adapt(self.border),
+            selection: adapt(self.selection),
+            highlight_fg: adapt(self.highlight_fg),
+            build: adapt(self.build),
+            avoid: adapt(self.avoid),
+            tiers: self.tiers.map(adapt),
+            heatmap: self.heatmap.map(adapt),
+            cues: self.cues,
+            glyphs: self.glyphs,
+            screen_reader: self.screen_reader,
+        }
+    }
+}
+
+fn parse_color(role: &str, value: &str) -> Result<Color, String> {
+    Color::from_str(value.trim()).map_err(|_| format!("{role}: \"{value}\" is not a color"))
+}
+
+fn parse_ramp(role: &str, values: &[String]) -> Result<[Color; 5], String> {
+    if values.len() != 5 {
+        return Err(format!("{role}: expected 5 colors, found {}", values.len()));
+    }
+    let mut ramp = [Color::Reset; 5];
+    for (slot, value) in ramp.iter_mut().zip(values) {
+        *slot = parse_color(role, value)?;
+    }
+    Ok(ramp)
+}
+
+impl ColorDepth {
+    fn resolve(self) -> ColorDepth {
+        if self != ColorDepth::Auto {
+            return self;
+        }
+        let colorterm = env::var("COLORTERM").unwrap_or_default();
+        if colorterm == "truecolor" || colorterm == "24bit" {
+            return ColorDepth::TrueColor;
+        }
+        let term = env::var("TERM").unwrap_or_default();
+        if term.contains("256color") {
+            ColorDepth::Ansi256
+        } else if term.is_empty() && cfg!(windows) {
+            ColorDepth::TrueColor
+        } else {
+            ColorDepth::Ansi16
+        }
+    }
+}
+
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (205, 0, 0)),
+    (Color::Green, (0, 205, 0)),
+    (Color::Yellow, (205, 205, 0)),
+    (Color::Blue, (0, 0, 238)),
+    (Color::Magenta, (205, 0, 205)),
+    (Color::Cyan, (0, 205, 205)),
+    (Color::Gray, (229, 229, 229)),
+    (Color::DarkGray, (127, 127, 127)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (92, 92, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+fn rgb_to_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    // Nearest of the 6×6×6 cube and the 24-step gray ramp.
+    let level = |v: u8| if v < 48 { 0 } else if v < 115 { 1 } else { (v - 35) / 40 };
+    let steps = [0u8, 95, 135, 175, 215, 255];
+    let (cr, cg, cb) = (level(r), level(g), level(b));
+    let cube = (steps[cr as usize], steps[cg as usize], steps[cb as usize]);
+    let avg = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_index = if avg > 238 { 23 } else { (avg.saturating_sub(3) / 10) as u8 };
+    let gray = 8 + gray_index * 10;
+    if distance((r, g, b), (gray, gray, gray)) < distance((r, g, b), cube) {
+        232 + gray_index
+    } else {
+        16 + 36 * cr + 6 * cg + cb
+    }
+}
+
+fn adapt_color(color: Color, depth: ColorDepth) -> Color {
+    match (color, depth) {
+        (Color::Rgb(r, g, b), ColorDepth::Ansi256) => Color::Indexed(rgb_to_ansi256(r, g, b)),
+        (Color::Rgb(r, g, b), ColorDepth::Ansi16) => ANSI16
+            .iter()
+            .min_by_key(|(_, rgb)| distance(*rgb, (r, g, b)))
+            .map(|(c, _)| *c)
+            .unwrap_or(color),
+        _ => color,
+    }
+}
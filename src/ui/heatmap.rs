@@ -2,19 +2,20 @@ use std::rc::Rc;
 
 use chrono::{Datelike, NaiveDate};
 use ratatui::{
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Stylize},
-    text::Line,
-    widgets::{Block, BorderType, Borders, List, ListItem, Padding, Paragraph},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
+    style::{Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Padding, Paragraph},
     Frame,
 };
 
 use crate::app::App;
+use crate::keymap::{Action, KeyMode};
+use crate::theme::Theme;
+
+use super::helpers::render_scrollbar;
 
-const WEEK_COLS: usize = 53;
 const DAYS_PER_WEEK: usize = 7;
-const MONTH_GAPS: usize = 11;
-const TOTAL_DRAW_COLS: usize = WEEK_COLS + MONTH_GAPS;
 // Fixed cell footprint so the grid reads the same at any terminal size instead
 // of stretching to fill whatever space is available.
 const MAX_CELL_W: u16 = 2;
@@ -22,13 +23,16 @@ const MAX_CELL_W: u16 = 2;
 // instead of fusing into a solid vertical bar.
 const ROW_H: u16 = 2;
 
-fn cell_w_for(width: u16) -> u16 {
-    (width / TOTAL_DRAW_COLS as u16).clamp(1, MAX_CELL_W)
+/// A day's place on the grid: weekday row, drawn column, and the date.
+type Cell = (usize, usize, NaiveDate);
+
+fn cell_w_for(width: u16, cols: usize) -> u16 {
+    (width / cols as u16).clamp(1, MAX_CELL_W)
 }
 
 /// Shrinks `available` down to the grid's fixed content size and centers it.
-fn fit_and_center(available: Rect, cell_w: u16) -> Rect {
-    let width = ((TOTAL_DRAW_COLS as u16) * cell_w).min(available.width);
+fn fit_and_center(available: Rect, cols: usize, cell_w: u16) -> Rect {
+    let width = ((cols as u16) * cell_w).min(available.width);
     let height = ((DAYS_PER_WEEK as u16) * ROW_H).min(available.height);
     let x = available.x + (available.width.saturating_sub(width)) / 2;
     let y = available.y + (available.height.saturating_sub(height)) / 2;
@@ -49,8 +53,24 @@ pub fn render_heatmap_page(chunks: Rc<[Rect]>, frame: &mut Frame, app: &App) {
 
     render_heatmap_body(body_chunks[0], frame, app);
 
-    let years_list = render_year_list(&app.years, app.counter.year_counter);
-    frame.render_widget(years_list, body_chunks[1]);
+    let selected = if app.heatmap_rolling {
+        app.years.len()
+    } else {
+        app.counter.year_counter
+    };
+    let years_list = render_year_list(&app.years, selected, &app.theme);
+    let mut states = app.list_states.borrow_mut();
+    states.years.select(Some(selected));
+    frame.render_stateful_widget(years_list, body_chunks[1], &mut states.years);
+    render_scrollbar(
+        frame,
+        body_chunks[1],
+        app.years.len() + 1,
+        states.years.offset(),
+        &app.theme,
+    );
+    drop(states);
+    app.hitboxes.borrow_mut().years = body_chunks[1].inner(Margin::new(1, 1));
 
     let footer_chunks = Layout::default()
         .direction(Direction::Horizontal)
@@ -60,57 +80,91 @@ pub fn render_heatmap_page(chunks: Rc<[Rect]>, frame: &mut Frame, app: &App) {
             Constraint::Percentage(25),
         ])
         .split(chunks[1]);
-    let footer = Paragraph::new("← → Year  ↑ ↓ Navigate  Tab Switch")
-        .block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded),
-        )
-        .centered();
+    let g = app.theme.glyphs;
+    let date = app.heatmap_cursor;
+    let (done, total) = app.heatmap_counts(date);
+    let status = format!("{} · {done}/{total} habits", date.format("%a, %b %-d %Y"));
+    let footer = Paragraph::new(vec![
+        Line::from(g.plain(&status)).bold(),
+        Line::from(g.plain(&format!(
+            "{} / {} year · {} open day · {} filter",
+            app.keys_for(KeyMode::Heatmap, Action::PrevYear),
+            app.keys_for(KeyMode::Heatmap, Action::NextYear),
+            app.keys_for(KeyMode::Heatmap, Action::OpenDay),
+            app.keys_for(KeyMode::Heatmap, Action::NextFilter)
+        )))
+        .fg(app.theme.muted),
+    ])
+    .block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(g.border),
+    )
+    .centered();
     frame.render_widget(footer, footer_chunks[1]);
-}
 
-fn compute_month_start_cols(year: i32) -> [usize; 12] {
-    let jan1 = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
-    let start_weekday = jan1.weekday().num_days_from_monday() as usize;
-    let days_in_year = if jan1.leap_year() { 366 } else { 365 };
+    let filter = Paragraph::new(vec![
+        Line::from("Showing").fg(app.theme.muted),
+        Line::from(app.heatmap_filter_label()),
+    ])
+    .centered();
+    frame.render_widget(filter, center_vertical(footer_chunks[0], 2));
+    frame.render_widget(render_legend(app), center_vertical(footer_chunks[2], 2));
+}
 
-    let mut starts = [0usize; 12];
-    let mut day_of_year = 0usize;
-    let mut current_month = 1u32;
-    let mut draw_col = 0usize;
+/// One swatch per bucket of the heatmap ramp, labeled with the share of
+/// habits done that it stands for.
+fn render_legend(app: &App) -> Paragraph<'static> {
+    let g = app.theme.glyphs;
+    let mut swatches = vec![Span::raw("Less ")];
+    for (bucket, color) in app.theme.heatmap.iter().enumerate() {
+        swatches.push(if app.theme.cues {
+            Span::styled(g.shades[bucket].to_string().repeat(3), Style::new().fg(*color))
+        } else {
+            Span::styled("   ", Style::new().bg(*color))
+        });
+        swatches.push(Span::raw(" "));
+    }
+    swatches.push(Span::raw("More"));
+    Paragraph::new(vec![
+        Line::from(swatches),
+        Line::from("     0%  <25 <50 <75 75+     ").fg(app.theme.muted),
+    ])
+    .centered()
+}
 
-    for col in 0..WEEK_COLS {
-        let mut gap_added = false;
-        for row in 0..DAYS_PER_WEEK {
-            if col == 0 && row < start_weekday {
-                continue;
-            }
-            if day_of_year >= days_in_year {
-                break;
-            }
-            let date = jan1 + chrono::Duration::days(day_of_year as i64);
-            if date.month() != current_month && !gap_added {
-                gap_added = true;
-                draw_col += 1;
-                current_month = date.month();
-                starts[current_month as usize - 1] = draw_col;
-            }
-            day_of_year += 1;
+/// Lays out every day from `start` to `end` in week columns, with a blank
+/// column wherever a new month begins so months read as separate blocks.
+fn layout_days(start: NaiveDate, end: NaiveDate) -> Vec<Cell> {
+    let mut cells = Vec::new();
+    let mut draw_col = 0;
+    let mut month = start.month();
+    for date in start.iter_days().take_while(|d| *d <= end) {
+        let row = date.weekday().num_days_from_monday() as usize;
+        if row == 0 && date != start {
+            draw_col += 1;
         }
-        draw_col += 1;
+        if date.month() != month {
+            draw_col += 1;
+            month = date.month();
+        }
+        cells.push((row, draw_col, date));
     }
-    starts
+    cells
 }
 
-fn render_year_list(years: &[String], selected_index: usize) -> List<'_> {
+/// The years, then the last 365 days, which `selected_index` picks when it
+/// runs one past the years.
+fn render_year_list<'a>(years: &'a [String], selected_index: usize, theme: &Theme) -> List<'a> {
     let items: Vec<ListItem> = years
         .iter()
+        .map(String::as_str)
+        .chain(std::iter::once("Last 365"))
         .enumerate()
         .map(|(idx, year)| {
-            let text = Line::from(format!("{}", year)).alignment(Alignment::Center);
+            let text = Line::from(year).alignment(Alignment::Center);
             if idx == selected_index {
-                ListItem::new(text).bg(Color::Gray).fg(Color::Black)
+                ListItem::new(text).bg(theme.selection).fg(theme.highlight_fg)
             } else {
                 ListItem::new(text)
             }
@@ -119,34 +173,40 @@ fn render_year_list(years: &[String], selected_index: usize) -> List<'_> {
     List::new(items).block(
         Block::default()
             .borders(Borders::ALL)
-            .border_type(BorderType::Rounded),
+            .border_set(theme.glyphs.border),
     )
 }
 
-fn render_month_label(chunk: Rect, frame: &mut Frame, month_start_cols: [usize; 12], cell_w: u16) {
-    let months = [
-        "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT", "NOV", "DEC",
-    ];
+fn render_month_label(chunk: Rect, frame: &mut Frame, cells: &[Cell], cell_w: u16, theme: &Theme) {
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_type(BorderType::Rounded);
+        .border_set(theme.glyphs.border);
     let inner = block.inner(chunk);
-    let area = fit_and_center(inner, cell_w);
+    let cols = cells.last().map_or(1, |c| c.1 + 1);
+    let area = fit_and_center(inner, cols, cell_w);
 
-    for (i, &label) in months.iter().enumerate() {
-        let x_offset = month_start_cols[i] as u16 * cell_w;
+    // Each month is labeled from the column its first shown day falls in.
+    let first = cells.first().map(|c| c.2);
+    let starts: Vec<(usize, NaiveDate)> = cells
+        .iter()
+        .filter(|(_, _, date)| date.day() == 1 || Some(*date) == first)
+        .map(|(_, col, date)| (*col, *date))
+        .collect();
+    for (i, (col, date)) in starts.iter().enumerate() {
+        let x_offset = *col as u16 * cell_w;
         if x_offset >= area.width {
             continue;
         }
-        let next_offset = if i + 1 < 12 {
-            month_start_cols[i + 1] as u16 * cell_w
-        } else {
-            area.width
-        };
+        let next_offset = starts
+            .get(i + 1)
+            .map_or(area.width, |(next, _)| *next as u16 * cell_w);
         let w = next_offset
             .saturating_sub(x_offset)
             .min(area.width - x_offset);
-        if w == 0 {
+        let label = date.format("%b").to_string().to_uppercase();
+        // A sliver of a month at the edge of the range goes unlabeled
+        // rather than showing a clipped name.
+        if (w as usize) < label.len() {
             continue;
         }
         let label_area = Rect::new(area.x + x_offset, area.y, w, area.height);
@@ -155,10 +215,10 @@ fn render_month_label(chunk: Rect, frame: &mut Frame, month_start_cols: [usize;
     frame.render_widget(block, chunk);
 }
 
-fn render_day_label(chunk: Rect, frame: &mut Frame) {
+fn render_day_label(chunk: Rect, frame: &mut Frame, theme: &Theme) {
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
+        .border_set(theme.glyphs.border)
         .padding(Padding::new(0, 0, 1, 0));
     let inner = block.inner(chunk);
     let area = center_vertical(inner, DAYS_PER_WEEK as u16 * ROW_H);
@@ -183,75 +243,56 @@ fn cell_rect(chunk: Rect, row: usize, col: usize, cell_w: u16) -> Rect {
     )
 }
 
-fn render_grid(chunk: Rect, frame: &mut Frame, app: &App, cell_w: u16) {
-    let selected_year: i32 = app
-        .years
-        .get(app.counter.year_counter)
-        .and_then(|y| y.parse().ok())
-        .unwrap_or(2026);
-
-    let jan1 = NaiveDate::from_ymd_opt(selected_year, 1, 1).unwrap();
-    let start_weekday = jan1.weekday().num_days_from_monday() as usize;
-    let days_in_year = if jan1.leap_year() { 366 } else { 365 };
-
-    let mut day_of_year: usize = 0;
-    let mut current_month = jan1.month();
-    let mut draw_col: usize = 0;
-
-    for col in 0..WEEK_COLS {
-        let mut month_gap_added = false;
-        for row in 0..DAYS_PER_WEEK {
-            if col == 0 && row < start_weekday {
-                continue;
-            }
-            if day_of_year >= days_in_year {
-                return;
-            }
-
-            let date = jan1 + chrono::Duration::days(day_of_year as i64);
-            if date.month() != current_month && !month_gap_added {
-                month_gap_added = true;
-                draw_col += 1;
-                current_month = date.month();
-            }
-
-            let color = rate_to_color(app.completion_rate_for_date(date));
-            let area = cell_rect(chunk, row, draw_col, cell_w);
-            // Leave a 1-col/1-row gap around each cell so both weeks and
-            // weekdays stay visually distinct instead of fusing into bars.
-            let fill_w = if cell_w > 1 { cell_w - 1 } else { 1 };
-            let fill_h = if area.height > 1 { area.height - 1 } else { 1 };
-            let fill_area = Rect::new(area.x, area.y, fill_w, fill_h);
+fn render_grid(chunk: Rect, frame: &mut Frame, app: &App, cells: &[Cell], cell_w: u16) {
+    for &(row, draw_col, date) in cells {
+        let (done, total) = app.heatmap_counts(date);
+        let rate = if total == 0 { 0.0 } else { done as f32 / total as f32 };
+        let bucket = rate_to_bucket(rate);
+        let color = app.theme.heatmap[bucket];
+        let area = cell_rect(chunk, row, draw_col, cell_w);
+        // Leave a 1-col/1-row gap around each cell so both weeks and
+        // weekdays stay visually distinct instead of fusing into bars.
+        let fill_w = if cell_w > 1 { cell_w - 1 } else { 1 };
+        let fill_h = if area.height > 1 { area.height - 1 } else { 1 };
+        let fill_area = Rect::new(area.x, area.y, fill_w, fill_h);
+        app.hitboxes.borrow_mut().heatmap_cells.push((fill_area, date));
+        if date == app.heatmap_cursor {
+            // The cursor keeps the cell's color and adds a marker on top.
+            let marker = app.theme.glyphs.selected.to_string();
+            frame.render_widget(
+                Paragraph::new(marker).bg(color).fg(app.theme.highlight_fg).bold(),
+                fill_area,
+            );
+        } else if app.theme.cues {
+            // Shade density carries the bucket even without color.
+            let shade = app.theme.glyphs.shades[bucket].to_string().repeat(fill_w as usize);
+            let rows = vec![Line::from(shade); fill_h as usize];
+            frame.render_widget(Paragraph::new(rows).fg(color), fill_area);
+        } else {
             frame.render_widget(Block::default().bg(color), fill_area);
-            day_of_year += 1;
         }
-        draw_col += 1;
     }
 }
 
-// Same 5-step Gray/Yellow/Cyan/LightGreen/Green language as the Stats page's
-// pattern tiers, so the same color means the same thing on both screens.
-fn rate_to_color(rate: f32) -> Color {
+// Five buckets indexing the theme's heatmap ramp: empty, then quarters.
+pub(super) fn rate_to_bucket(rate: f32) -> usize {
     if rate <= 0.0 {
-        Color::DarkGray
+        0
     } else if rate < 0.25 {
-        Color::Rgb(14, 68, 41)
+        1
     } else if rate < 0.50 {
-        Color::Rgb(0, 109, 50)
+        2
     } else if rate < 0.75 {
-        Color::Rgb(38, 166, 65)
+        3
     } else {
-        Color::Rgb(57, 211, 83)
+        4
     }
 }
 
 fn render_heatmap_body(chunk: Rect, frame: &mut Frame, app: &App) {
-    let selected_year: i32 = app
-        .years
-        .get(app.counter.year_counter)
-        .and_then(|y| y.parse().ok())
-        .unwrap_or(2026);
-    let month_start_cols = compute_month_start_cols(selected_year);
+    let (start, end) = app.heatmap_range();
+    let cells = layout_days(start, end);
+    let cols = cells.last().map_or(1, |c| c.1 + 1);
 
     let body_chunks = Layout::default()
         .direction(Direction::Vertical)
@@ -266,17 +307,17 @@ fn render_heatmap_body(chunk: Rect, frame: &mut Frame, app: &App) {
         .direction(Direction::Horizontal)
         .constraints([Constraint::Length(5), Constraint::Min(1)])
         .split(body_chunks[1]);
-    render_day_label(day_chunks[0], frame);
+    render_day_label(day_chunks[0], frame, &app.theme);
 
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
+        .border_set(app.theme.glyphs.border)
         .padding(Padding::new(1, 1, 1, 0));
     let block_inner = block.inner(day_chunks[1]);
-    let cell_w = cell_w_for(block_inner.width);
-    let grid_area = fit_and_center(block_inner, cell_w);
+    let cell_w = cell_w_for(block_inner.width, cols);
+    let grid_area = fit_and_center(block_inner, cols, cell_w);
 
-    render_month_label(label_chunks[1], frame, month_start_cols, cell_w);
-    render_grid(grid_area, frame, app, cell_w);
+    render_month_label(label_chunks[1], frame, &cells, cell_w, &app.theme);
+    render_grid(grid_area, frame, app, &cells, cell_w);
     frame.render_widget(block, day_chunks[1]);
 }
@@ -1,23 +1,117 @@
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Margin, Rect},
+    widgets::{Scrollbar, ScrollbarOrientation, ScrollbarState},
+    Frame,
 };
 
-pub fn centered_rect(area: Rect, width_pct: u16, height_pct: u16) -> Rect {
-    let popup_layout = Layout::default()
-        .direction(Direction::Vertical)
-        .constraints([
-            Constraint::Percentage((100 - height_pct) / 2),
-            Constraint::Percentage(height_pct),
-            Constraint::Percentage((100 - height_pct) / 2),
-        ])
-        .split(area);
-
-    Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([
-            Constraint::Percentage((100 - width_pct) / 2),
-            Constraint::Percentage(width_pct),
-            Constraint::Percentage((100 - width_pct) / 2),
-        ])
-        .split(popup_layout[1])[1]
+use crate::theme::Theme;
+
+/// Narrower than this, the Today tab shows one habit list at a time and
+/// the tabs use short labels.
+pub const COMPACT_WIDTH: u16 = 80;
+/// Shorter than this, the title box shrinks to a single line.
+pub const FULL_TITLE_HEIGHT: u16 = 30;
+/// Shorter than this, the title line is dropped too, unless it has a
+/// status or tag filter to show.
+pub const TITLE_HEIGHT: u16 = 20;
+/// Shorter than this, the tabs drop their borders.
+pub const BORDERED_TABS_HEIGHT: u16 = 22;
+/// Below this size only a "terminal too small" message is drawn.
+pub const MIN_WIDTH: u16 = 40;
+pub const MIN_HEIGHT: u16 = 15;
+
+pub fn is_compact(width: u16) -> bool {
+    width < COMPACT_WIDTH
+}
+
+pub fn fits(area: Rect) -> bool {
+    area.width >= MIN_WIDTH && area.height >= MIN_HEIGHT
+}
+
+/// Rows the title takes in a pane `height` tall: the box, one line, or
+/// none. `has_subtitle` keeps the line for a status, tag or config problem.
+pub fn title_height(height: u16, has_subtitle: bool) -> u16 {
+    match height {
+        h if h >= FULL_TITLE_HEIGHT => 4,
+        h if h >= TITLE_HEIGHT || has_subtitle => 1,
+        _ => 0,
+    }
+}
+
+pub fn tabs_height(height: u16) -> u16 {
+    if height < BORDERED_TABS_HEIGHT {
+        1
+    } else {
+        3
+    }
+}
+
+/// A popup in the middle of `area`, `width_pct` × `height_pct` of it but
+/// at least `min_width` × `min_height` cells as far as `area` allows, so
+/// forms keep their fields in short panes.
+pub fn centered_rect(
+    area: Rect,
+    width_pct: u16,
+    height_pct: u16,
+    min_width: u16,
+    min_height: u16,
+) -> Rect {
+    let scale = |len: u16, pct: u16| (len as u32 * pct as u32 / 100) as u16;
+    let width = scale(area.width, width_pct).max(min_width).min(area.width);
+    let height = scale(area.height, height_pct).max(min_height).min(area.height);
+    Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    )
+}
+
+/// A scrollbar over the right border of the bordered `area`, shown only
+/// when `len` lines don't fit its inside.
+pub fn render_scrollbar(frame: &mut Frame, area: Rect, len: usize, offset: usize, theme: &Theme) {
+    let track = area.inner(Margin::new(0, 1));
+    if len <= track.height as usize {
+        return;
+    }
+    let mut state = ScrollbarState::new(len.saturating_sub(track.height as usize))
+        .position(offset)
+        .viewport_content_length(track.height as usize);
+    let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+        .symbols(theme.glyphs.scrollbar.clone())
+        .style(theme.border);
+    frame.render_stateful_widget(scrollbar, track, &mut state);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compact_below_eighty_columns() {
+        assert!(is_compact(COMPACT_WIDTH - 1));
+        assert!(!is_compact(COMPACT_WIDTH));
+    }
+
+    #[test]
+    fn forty_by_fifteen_is_the_smallest_drawn() {
+        assert!(fits(Rect::new(0, 0, 40, 15)));
+        assert!(!fits(Rect::new(0, 0, 39, 15)));
+        assert!(!fits(Rect::new(0, 0, 40, 14)));
+    }
+
+    #[test]
+    fn title_shrinks_then_goes_unless_it_has_news() {
+        assert_eq!(title_height(FULL_TITLE_HEIGHT, false), 4);
+        assert_eq!(title_height(FULL_TITLE_HEIGHT - 1, false), 1);
+        assert_eq!(title_height(TITLE_HEIGHT, false), 1);
+        assert_eq!(title_height(TITLE_HEIGHT - 1, false), 0);
+        assert_eq!(title_height(MIN_HEIGHT, true), 1);
+    }
+
+    #[test]
+    fn tabs_lose_borders_in_short_panes() {
+        assert_eq!(tabs_height(BORDERED_TABS_HEIGHT), 3);
+        assert_eq!(tabs_height(BORDERED_TABS_HEIGHT - 1), 1);
+    }
 }
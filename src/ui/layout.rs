@@ -1,31 +1,82 @@
 use crate::app::{App, CurrentScreen};
+use crate::keymap::{Action, KeyMode};
+use crate::theme::Theme;
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style, Stylize},
     text::Line,
-    widgets::{Block, BorderType, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
     Frame,
 };
 
+use super::helpers::is_compact;
 use super::stats::render_stats_page;
+use super::month::render_month_page;
 use super::today::render_today_page;
+use super::week::render_week_page;
 use super::heatmap::render_heatmap_page;
 
-pub fn render_title(chunk: Rect, frame: &mut Frame) {
-    let title_items = vec![
-        ListItem::new(
-            Line::styled(
-                "🌊 Flow State 🌊",
-                Style::new().fg(Color::White).add_modifier(Modifier::BOLD),
-            )
-            .centered(),
+pub fn render_title(chunk: Rect, frame: &mut Frame, app: &App) {
+    let theme = &app.theme;
+    let name = Line::styled(
+        theme.glyphs.plain("🌊 Flow State 🌊"),
+        Style::new().fg(theme.text).add_modifier(Modifier::BOLD),
+    );
+    // A broken config stays in view until it's fixed.
+    let subtitle = match (&app.status, app.config_problem(), &app.tag_filter) {
+        (Some(status), _, _) => Some(Line::from(status.clone()).fg(theme.info)),
+        (None, Some(problem), _) => Some(
+            Line::from(theme.glyphs.plain(&format!(
+                "⚠ {problem} · run flow_state config check"
+            )))
+            .fg(theme.error),
+        ),
+        (None, None, Some(tag)) => Some(
+            Line::from(theme.glyphs.plain(&format!(
+                "Showing #{tag} · {} next tag",
+                app.keys_for(KeyMode::Global, Action::NextTag)
+            )))
+            .fg(theme.accent),
         ),
-        ListItem::new(Line::from("Minimalist Habit Tracker").centered()),
+        (None, None, None) => None,
+    };
+    // The wall clock, so a pane left open all day shows the time.
+    let clock = Line::from(app.clock.format("%H:%M").to_string()).fg(theme.muted);
+    // Centered when it fits, else from the start so it leads with the file
+    // name or command it is about.
+    let fit = |line: Line<'static>, room: u16| {
+        if line.width() > room as usize {
+            line.left_aligned()
+        } else {
+            line.centered()
+        }
+    };
+    // Too short for the box: the status or tag, else the name, on one line.
+    if chunk.height < 4 {
+        let line = fit(subtitle.unwrap_or(name), chunk.width.saturating_sub(12));
+        let text_area = if line.alignment == Some(Alignment::Left) {
+            // Stop short of the clock.
+            Rect { width: chunk.width.saturating_sub(6), ..chunk }
+        } else {
+            chunk
+        };
+        frame.render_widget(line, text_area);
+        frame.render_widget(clock.right_aligned(), chunk);
+        return;
+    }
+    let subtitle = subtitle.unwrap_or_else(|| Line::from("Minimalist Habit Tracker"));
+    let title_items = vec![
+        ListItem::new(name.centered()),
+        ListItem::new(fit(subtitle, chunk.width.saturating_sub(2))),
     ];
+    let date = Line::from(app.clock.format("%a %b %-d").to_string()).fg(theme.muted);
     let title = List::new(title_items).block(
         Block::default()
             .borders(Borders::ALL)
-            .border_type(BorderType::Rounded),
+            .border_set(theme.glyphs.border)
+            .border_style(theme.border)
+            .title_top(date.left_aligned())
+            .title_top(clock.right_aligned()),
     );
     frame.render_widget(title, chunk);
 }
@@ -33,43 +84,52 @@ pub fn render_title(chunk: Rect, frame: &mut Frame) {
 pub fn render_tab(chunk: Rect, frame: &mut Frame, app: &App) {
     let tab_chunks = Layout::default()
         .direction(Direction::Horizontal)
-        .constraints([Constraint::Min(1), Constraint::Min(1), Constraint::Min(1)])
+        .constraints([Constraint::Min(1); 5])
         .split(chunk);
 
-    let day_name = app.current_day.as_str();
+    let day_name = if is_compact(chunk.width) {
+        app.current_date.format("%b %-d").to_string()
+    } else {
+        app.theme.glyphs.plain(&app.current_date_label())
+    };
+    let heatmap = if is_compact(chunk.width) { "Heat" } else { "Heatmap" };
+    let tabs = [
+        (day_name.as_str(), CurrentScreen::Today),
+        ("Week", CurrentScreen::Week),
+        ("Month", CurrentScreen::Month),
+        ("Stats", CurrentScreen::Stats),
+        (heatmap, CurrentScreen::Heatmap),
+    ];
 
+    // Borderless tabs in short panes, one row high.
+    let borders = if chunk.height < 3 { Borders::NONE } else { Borders::ALL };
     let outer_block = Block::new()
-        .borders(Borders::ALL)
-        .border_type(BorderType::Rounded);
-
-    let today_tab = render_tab_item(day_name, matches!(app.current_screen, CurrentScreen::Today));
-    let today_inner = outer_block.inner(tab_chunks[0]);
-    frame.render_widget(outer_block.clone(), tab_chunks[0]);
-    frame.render_widget(today_tab, today_inner);
+        .borders(borders)
+        .border_set(app.theme.glyphs.border)
+        .border_style(app.theme.border);
 
-    let stats_tab = render_tab_item("Stats", matches!(app.current_screen, CurrentScreen::Stats));
-    let stats_inner = outer_block.inner(tab_chunks[1]);
-    frame.render_widget(outer_block.clone(), tab_chunks[1]);
-    frame.render_widget(stats_tab, stats_inner);
+    app.hitboxes.borrow_mut().tabs = tabs
+        .iter()
+        .zip(tab_chunks.iter())
+        .map(|((_, screen), area)| (*area, *screen))
+        .collect();
 
-    let heatmap_tab = render_tab_item(
-        "Heatmap",
-        matches!(app.current_screen, CurrentScreen::Heatmap),
-    );
-    let heatmap_inner = outer_block.inner(tab_chunks[2]);
-    frame.render_widget(outer_block.clone(), tab_chunks[2]);
-    frame.render_widget(heatmap_tab, heatmap_inner);
+    for ((label, screen), area) in tabs.into_iter().zip(tab_chunks.iter()) {
+        let tab = render_tab_item(label, app.current_screen == screen, &app.theme);
+        frame.render_widget(outer_block.clone(), *area);
+        frame.render_widget(tab, outer_block.inner(*area));
+    }
 }
 
-fn render_tab_item(label: &str, is_active: bool) -> Paragraph<'_> {
+fn render_tab_item<'a>(label: &'a str, is_active: bool, theme: &Theme) -> Paragraph<'a> {
     let content = Paragraph::new(Line::from(label).fg(if is_active {
-        Color::Black
+        theme.highlight_fg
     } else {
         Color::default()
     }))
     .centered();
     content.block(Block::default().style(Style::new().bg(if is_active {
-        Color::Gray
+        theme.selection
     } else {
         Color::default()
     })))
@@ -82,6 +142,8 @@ pub fn render_body(chunk: Rect, frame: &mut Frame, app: &App) {
         .split(chunk);
     match app.current_screen {
         CurrentScreen::Today => render_today_page(body_chunks, frame, app),
+        CurrentScreen::Week => render_week_page(body_chunks, frame, app),
+        CurrentScreen::Month => render_month_page(body_chunks, frame, app),
         CurrentScreen::Stats => render_stats_page(body_chunks, frame, app),
         CurrentScreen::Heatmap => render_heatmap_page(body_chunks, frame, app),
     }
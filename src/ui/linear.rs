@@ -0,0 +1,309 @@
+use chrono::{Datelike, Duration, NaiveDate};
+use ratatui::{
+    layout::{Position, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::Paragraph,
+    Frame,
+};
+
+use crate::app::{App, CurrentScreen, ListRow, SortMode};
+use crate::habit::{
+    find_best_habit, find_worst_habit, group_pattern, today_with_cutoff, Encouragement, Habit,
+    HabitPattern, HabitType,
+};
+use crate::keymap::{Action, KeyMode};
+
+const TIERS: [HabitPattern; 5] = [
+    HabitPattern::Chaotic,
+    HabitPattern::Struggling,
+    HabitPattern::Developing,
+    HabitPattern::Established,
+    HabitPattern::Mastered,
+];
+
+/// Screen-reader layout: plain lines top to bottom, no panels or columns.
+/// The second line always describes the selection and holds the cursor,
+/// so a reader following the cursor announces it after every keypress.
+pub fn render_linear(area: Rect, frame: &mut Frame, app: &App) {
+    let (heading, announcement, body) = match app.current_screen {
+        CurrentScreen::Today => today_lines(app),
+        CurrentScreen::Week => week_lines(app),
+        CurrentScreen::Month => month_lines(app),
+        CurrentScreen::Stats => stats_lines(app),
+        CurrentScreen::Heatmap => heatmap_lines(app),
+    };
+    let heading = match &app.tag_filter {
+        Some(tag) => format!("{heading} - tagged #{tag}"),
+        None => heading,
+    };
+    let heading = match &app.status {
+        Some(status) => format!("{status}. {heading}"),
+        None => heading,
+    };
+    let heading = match app.config_problem() {
+        Some(problem) => format!("{problem}. Run flow_state config check. {heading}"),
+        None => heading,
+    };
+
+    let mut lines = vec![
+        Line::from(heading).fg(app.theme.accent),
+        Line::from(announcement.clone()).fg(app.theme.text),
+        Line::from(""),
+    ];
+    lines.extend(body.into_iter().map(Line::from));
+    lines.push(Line::from(""));
+    let keys = format!(
+        "Keys: {} next screen, {} help, {} notifications, {} quit",
+        app.keys_for(KeyMode::Global, Action::NextScreen),
+        app.keys_for(KeyMode::Global, Action::Help),
+        app.keys_for(KeyMode::Global, Action::Inbox),
+        app.keys_for(KeyMode::Global, Action::Quit)
+    );
+    lines.push(Line::from(keys).fg(app.theme.muted));
+    frame.render_widget(Paragraph::new(lines), area);
+
+    if area.height > 1 {
+        let x = (announcement.len() as u16).min(area.width.saturating_sub(1));
+        frame.set_cursor_position(Position::new(area.x + x, area.y + 1));
+    }
+}
+
+fn habit_state(habit: &Habit, date: NaiveDate, app: &App) -> String {
+    let mut state = match habit.habit_type {
+        HabitType::Build if habit.completed_on(date, app.day_cutoff_hour) => "done".to_string(),
+        HabitType::Build => "not done".to_string(),
+        HabitType::Avoid => match habit.slip_count_on(date) {
+            0 => "no slips".to_string(),
+            1 => "1 slip".to_string(),
+            n => format!("{n} slips"),
+        },
+    };
+    if habit.paused.is_some_and(|since| since <= date) {
+        state.push_str(", paused");
+    } else if habit.is_on_holiday(date) {
+        state.push_str(", on holiday");
+    }
+    format!("{state}, {}", habit.check_pattern(app.day_cutoff_hour))
+}
+
+fn today_lines(app: &App) -> (String, String, Vec<String>) {
+    let date = app.current_date;
+    let heading = match app.sort_mode {
+        SortMode::Manual => format!("Flow State - {}", date.format("%A %B %-d %Y")),
+        mode => format!(
+            "Flow State - {}, sorted by {}",
+            date.format("%A %B %-d %Y"),
+            mode.label()
+        ),
+    };
+
+    let (habit_type, habits, index, kind) = if app.counter.switch {
+        (HabitType::Avoid, &app.avoid_habits, app.counter.avoid_counter, "Avoid")
+    } else {
+        (HabitType::Build, &app.build_habits, app.counter.build_counter, "Build")
+    };
+    let listed: Vec<usize> = app
+        .list_rows(habit_type)
+        .into_iter()
+        .filter_map(|row| match row {
+            ListRow::Habit(i) => Some(i),
+            ListRow::Group(..) => None,
+        })
+        .collect();
+    let announcement = match listed.iter().position(|i| *i == index) {
+        Some(pos) => format!(
+            "Selected: {kind} habit {} of {}, {}: {}.",
+            pos + 1,
+            listed.len(),
+            habits[index].name,
+            habit_state(&habits[index], date, app)
+        ),
+        None => format!(
+            "No {} habits shown. Press {} to add one.",
+            kind.to_lowercase(),
+            app.keys_for(KeyMode::Today, Action::Add)
+        ),
+    };
+
+    let total = app.habits_on(date).count();
+    let day = match (app.today() - date).num_days() {
+        0 => "today".to_string(),
+        1 => "yesterday".to_string(),
+        _ => format!("on {}", date.format("%B %-d")),
+    };
+    let mut body = vec![format!(
+        "{} of {total} habits done {day}.",
+        app.count_completed_on(date)
+    )];
+    let sections = [
+        ("Build habits", HabitType::Build, &app.build_habits, !app.counter.switch, app.counter.build_counter),
+        ("Avoid habits", HabitType::Avoid, &app.avoid_habits, app.counter.switch, app.counter.avoid_counter),
+    ];
+    for (title, habit_type, habits, active, selected) in sections {
+        body.push(String::new());
+        body.push(format!("{title}:"));
+        let rows = app.list_rows(habit_type);
+        if rows.is_empty() {
+            body.push("  none".to_string());
+        }
+        let mut number = 0;
+        for row in rows {
+            match row {
+                ListRow::Group(name, count) => {
+                    let folded = if app.is_collapsed(&name) { ", folded" } else { "" };
+                    let habits = if count == 1 { "habit" } else { "habits" };
+                    body.push(format!("  {name}, {count} {habits}{folded}:"));
+                }
+                ListRow::Habit(i) => {
+                    number += 1;
+                    let habit = &habits[i];
+                    let marker = if active && i == selected { ">" } else { " " };
+                    body.push(format!(
+                        "{marker} {number}. {}: {}",
+                        habit.name,
+                        habit_state(habit, date, app)
+                    ));
+                }
+            }
+        }
+    }
+    (heading, announcement, body)
+}
+
+fn week_lines(app: &App) -> (String, String, Vec<String>) {
+    let dates = app.week_dates();
+    let heading = format!("Flow State - Week of {}", dates[0].format("%A %B %-d %Y"));
+    let date = app.current_date;
+    let habits: Vec<&Habit> = app.all_habits().collect();
+    let shown = app.selection_shown();
+    let announcement = match habits.get(app.selected_row()).filter(|_| shown) {
+        Some(habit) => format!(
+            "Selected: {}, {}: {}.",
+            habit.name,
+            date.format("%A %B %-d"),
+            habit_state(habit, date, app)
+        ),
+        None => "No habits yet.".to_string(),
+    };
+
+    let mut body = Vec::new();
+    for habit in app.row_order(true).into_iter().map(|row| habits[row]) {
+        let days: Vec<String> = dates
+            .iter()
+            .filter(|d| **d <= app.today())
+            .map(|d| {
+                let state = if habit.completed_on(*d, app.day_cutoff_hour) {
+                    "done"
+                } else if habit.is_on_holiday(*d) {
+                    "holiday"
+                } else {
+                    "missed"
+                };
+                format!("{} {state}", d.format("%a"))
+            })
+            .collect();
+        let (done, due) = habit.tally(dates[0], dates[6].min(app.today()));
+        body.push(format!("{}: {done} of {due}. {}.", habit.name, days.join(", ")));
+    }
+    (heading, announcement, body)
+}
+
+fn month_lines(app: &App) -> (String, String, Vec<String>) {
+    let date = app.current_date;
+    let heading = format!("Flow State - {}", date.format("%B %Y"));
+    let announcement = format!(
+        "Selected: {}, {} of {} habits done. {} shows the day.",
+        date.format("%A %B %-d"),
+        app.count_completed_on(date),
+        app.habits_on(date).count(),
+        app.keys_for(KeyMode::Month, Action::OpenDay)
+    );
+
+    let first = date.with_day(1).unwrap();
+    let body = (0..31)
+        .map(|i| first + Duration::days(i))
+        .take_while(|d| d.month() == first.month() && *d <= app.today())
+        .map(|d| {
+            let total = app.habits_on(d).count();
+            format!("{}: {} of {total}", d.format("%a %-d"), app.count_completed_on(d))
+        })
+        .collect();
+    (heading, announcement, body)
+}
+
+fn stats_lines(app: &App) -> (String, String, Vec<String>) {
+    let habits: Vec<Habit> = app.shown_habits().cloned().collect();
+    let cutoff = app.day_cutoff_hour;
+    let heading = "Flow State - Stats".to_string();
+    if habits.is_empty() {
+        return (heading, "No habits tracked yet.".to_string(), Vec::new());
+    }
+
+    let mut body = vec!["Pattern health:".to_string()];
+    for pattern in TIERS {
+        let count = habits.iter().filter(|h| h.check_pattern(cutoff) == pattern).count();
+        let pct = (count as f32 / habits.len() as f32 * 100.0).round();
+        body.push(format!("  {pattern}: {count} ({pct}%)"));
+    }
+    body.push(String::new());
+    if let Some(newest) = habits.iter().max_by_key(|h| h.created) {
+        body.push(format!("Newest habit: {}", newest.name));
+    }
+    if let Some(best) = find_best_habit(&habits, cutoff) {
+        body.push(format!("Thriving: {}", best.name));
+    }
+    if let Some(worst) = find_worst_habit(&habits, cutoff) {
+        body.push(format!("Growing edge: {}", worst.name));
+    }
+    let tags = app.tags();
+    if !tags.is_empty() {
+        body.push(String::new());
+        body.push("By tag:".to_string());
+    }
+    for tag in tags {
+        let tagged: Vec<&Habit> = app.all_habits().filter(|h| h.has_tag(&tag)).collect();
+        let pattern = group_pattern(&tagged, today_with_cutoff(cutoff));
+        body.push(format!("  #{tag}: {} habits, {pattern}", tagged.len()));
+    }
+
+    let message = Encouragement::for_habits(&habits, today_with_cutoff(cutoff)).message();
+    (heading, app.theme.glyphs.plain(message), body)
+}
+
+fn heatmap_lines(app: &App) -> (String, String, Vec<String>) {
+    let heading = "Flow State - Heatmap".to_string();
+    let (start, end) = app.heatmap_range();
+    let range = if app.heatmap_rolling {
+        "the last 365 days".to_string()
+    } else {
+        format!("year {}", start.year())
+    };
+    let (done, total) = app.heatmap_counts(app.heatmap_cursor);
+    let announcement = format!(
+        "{}: {done} of {total} habits. Showing {}, {range}.",
+        app.heatmap_cursor.format("%A %B %-d"),
+        app.heatmap_filter_label()
+    );
+
+    let today = today_with_cutoff(app.day_cutoff_hour);
+    let mut body = vec!["Average share of habits done per day:".to_string()];
+    let mut first = start;
+    while first <= end.min(today) {
+        let days: Vec<NaiveDate> = first
+            .iter_days()
+            .take_while(|d| d.month() == first.month() && *d <= end.min(today))
+            .collect();
+        let average = days
+            .iter()
+            .map(|d| match app.heatmap_counts(*d) {
+                (_, 0) => 0.0,
+                (done, total) => done as f32 / total as f32,
+            })
+            .sum::<f32>()
+            / days.len() as f32;
+        body.push(format!("  {}: {:.0}%", first.format("%B %Y"), average * 100.0));
+        first = *days.last().unwrap() + Duration::days(1);
+    }
+    (heading, announcement, body)
+}
@@ -1,42 +1,99 @@
 mod helpers;
 mod heatmap;
 mod layout;
+mod linear;
+mod month;
 mod popups;
 mod stats;
 mod today;
+mod week;
 
-use crate::app::App;
+use crate::app::{App, Hitboxes};
 use layout::{render_body, render_tab, render_title};
-use popups::{confirm_float, habit_form_float, help_popup, holiday_form_float};
+use linear::render_linear;
+use popups::{
+    archive_popup, command_palette, confirm_float, day_detail_popup, digest_popup, habit_form_float, goto_form_float, help_popup, holiday_form_float, holidays_popup, inbox_popup,
+    search_bar, slip_form_float,
+};
+use helpers::{fits, tabs_height, title_height, MIN_HEIGHT, MIN_WIDTH};
 use ratatui::{
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
+    style::Stylize,
+    text::Line,
+    widgets::{Paragraph, Wrap},
     Frame,
 };
 
 use crate::app::ScreenMode;
 
 pub fn ui(frame: &mut Frame, app: &App) {
+    *app.hitboxes.borrow_mut() = Hitboxes::default();
+    let area = frame.area();
+    if !app.theme.screen_reader && !fits(area) {
+        app.hitboxes.borrow_mut().too_small = true;
+        render_too_small(frame, app);
+        return;
+    }
+    if app.theme.screen_reader {
+        render_linear(frame.area(), frame, app);
+    } else {
+        render_panels(frame, app);
+    }
+
+    match app.screen_mode {
+        ScreenMode::Adding => habit_form_float(frame, area, app, "Add habit"),
+        ScreenMode::Editing => habit_form_float(frame, area, app, "Edit habit"),
+        ScreenMode::Deleting => confirm_float(frame, area, app, "Confirm delete"),
+        ScreenMode::Reset => confirm_float(frame, area, app, "Confirm reset"),
+        ScreenMode::Holidays => holidays_popup(frame, area, app),
+        ScreenMode::Holiday => holiday_form_float(frame, area, app),
+        ScreenMode::Slip => slip_form_float(frame, area, app),
+        ScreenMode::GoTo => goto_form_float(frame, area, app),
+        ScreenMode::Help => help_popup(frame, area, app),
+        ScreenMode::Inbox => inbox_popup(frame, area, app),
+        ScreenMode::Digest => digest_popup(frame, area, app),
+        ScreenMode::DayDetail => day_detail_popup(frame, area, app),
+        ScreenMode::Archive => archive_popup(frame, area, app),
+        ScreenMode::Search => search_bar(frame, area, app),
+        ScreenMode::Command => command_palette(frame, area, app),
+        ScreenMode::Normal => {}
+    }
+}
+
+fn render_panels(frame: &mut Frame, app: &App) {
+    // Short panes trade the title box and tab borders for list rows.
+    let height = frame.area().height;
+    let has_subtitle =
+        app.status.is_some() || app.tag_filter.is_some() || app.config_problem().is_some();
+    let title = title_height(height, has_subtitle);
+    let tabs = tabs_height(height);
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Length(4),
-            Constraint::Length(3),
+            Constraint::Length(title),
+            Constraint::Length(tabs),
             Constraint::Min(1),
         ])
         .split(frame.area());
 
-    render_title(chunks[0], frame);
+    if title > 0 {
+        render_title(chunks[0], frame, app);
+    }
     render_tab(chunks[1], frame, app);
     render_body(chunks[2], frame, app);
+}
 
+/// Drawn instead of the panels when they can't fit, with the size needed.
+fn render_too_small(frame: &mut Frame, app: &App) {
     let area = frame.area();
-    match app.screen_mode {
-        ScreenMode::Adding => habit_form_float(frame, area, app, "Add habit"),
-        ScreenMode::Editing => habit_form_float(frame, area, app, "Edit habit"),
-        ScreenMode::Deleting => confirm_float(frame, area, app, "Confirm delete"),
-        ScreenMode::Reset => confirm_float(frame, area, app, "Confirm reset"),
-        ScreenMode::Holiday => holiday_form_float(frame, area, app),
-        ScreenMode::Help => help_popup(frame, area),
-        ScreenMode::Normal => {}
-    }
+    let lines = vec![
+        Line::from("Terminal too small").bold(),
+        Line::from(format!("{}x{}, needs {MIN_WIDTH}x{MIN_HEIGHT}", area.width, area.height))
+            .fg(app.theme.muted),
+    ];
+    let top = area.height.saturating_sub(lines.len() as u16) / 2;
+    frame.render_widget(
+        Paragraph::new(lines).centered().wrap(Wrap { trim: true }),
+        Rect::new(area.x, area.y + top, area.width, area.height - top),
+    );
 }
@@ -0,0 +1,185 @@
+use std::rc::Rc;
+
+use chrono::{Datelike, Duration, Months, NaiveDate};
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::app::App;
+use crate::habit::week_start;
+use crate::keymap::{Action, KeyMode};
+
+use super::heatmap::rate_to_bucket;
+
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const MAX_CELL_H: u16 = 4;
+
+pub fn render_month_page(chunks: Rc<[Rect]>, frame: &mut Frame, app: &App) {
+    let first = app.current_date.with_day(1).unwrap();
+    let title = app
+        .theme
+        .glyphs
+        .plain(&format!("📅 {}", first.format("%B %Y")));
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(app.theme.glyphs.border)
+        .border_style(app.theme.border)
+        .title(title);
+    let inner = block.inner(chunks[0]);
+    frame.render_widget(block, chunks[0]);
+    render_calendar(inner, frame, app, first);
+
+    render_footer(chunks[1], frame, app, first);
+}
+
+fn render_calendar(area: Rect, frame: &mut Frame, app: &App, first: NaiveDate) {
+    if area.height < 2 || area.width < 7 {
+        return;
+    }
+    let last = first + Months::new(1) - Duration::days(1);
+    let start = week_start(first);
+    let weeks = ((last - start).num_days() / 7 + 1) as u16;
+    let cell_w = area.width / 7;
+    let cell_h = ((area.height - 1) / weeks).clamp(1, MAX_CELL_H);
+    // Center the grid vertically when the cells hit their maximum height.
+    let grid_h = 1 + cell_h * weeks;
+    let top = area.y + (area.height.saturating_sub(grid_h)) / 2;
+    let left = area.x + (area.width - cell_w * 7) / 2;
+
+    for (col, name) in WEEKDAYS.iter().enumerate() {
+        frame.render_widget(
+            Paragraph::new(*name).bold(),
+            Rect::new(left + col as u16 * cell_w, top, cell_w, 1),
+        );
+    }
+
+    let today = app.today();
+    let g = app.theme.glyphs;
+    let mut hitboxes = app.hitboxes.borrow_mut();
+    for day in 0..(weeks as i64 * 7) {
+        let date = start + Duration::days(day);
+        if date.month() != first.month() {
+            continue;
+        }
+        let (row, col) = ((day / 7) as u16, (day % 7) as u16);
+        // A one-row gap between weeks once there's room for it.
+        let height = if cell_h > 2 { cell_h - 1 } else { cell_h };
+        let cell = Rect::new(
+            left + col * cell_w,
+            top + 1 + row * cell_h,
+            cell_w.saturating_sub(1).max(1),
+            height,
+        );
+        hitboxes.month_cells.push((cell, date));
+
+        let selected = date == app.current_date;
+        let marker = if selected && app.theme.cues {
+            format!("{} ", g.selected)
+        } else {
+            String::new()
+        };
+        let day_style = if date == today {
+            Style::new().fg(app.theme.accent).add_modifier(Modifier::BOLD)
+        } else if date > today {
+            Style::new().fg(app.theme.muted)
+        } else {
+            Style::new()
+        };
+        let day_label = Span::styled(format!("{marker}{}", date.day()), day_style);
+
+        let count = if date > today {
+            Vec::new()
+        } else {
+            let bucket = rate_to_bucket(app.completion_rate_for_date(date));
+            let color = app.theme.heatmap[bucket];
+            let swatch = if app.theme.cues {
+                Span::styled(g.shades[bucket].to_string().repeat(3), Style::new().fg(color))
+            } else {
+                Span::styled("   ", Style::new().bg(color))
+            };
+            vec![
+                Span::raw(format!(
+                    "{}/{} ",
+                    app.count_completed_on(date),
+                    app.habits_on(date).count()
+                )),
+                swatch,
+            ]
+        };
+
+        let lines = if cell.height >= 2 {
+            vec![Line::from(day_label), Line::from(count)]
+        } else {
+            let mut spans = vec![day_label, Span::raw(" ")];
+            spans.extend(count);
+            vec![Line::from(spans)]
+        };
+        let paragraph = if selected {
+            Paragraph::new(lines)
+                .style(Style::new().bg(app.theme.selection).fg(app.theme.highlight_fg))
+        } else {
+            Paragraph::new(lines)
+        };
+        frame.render_widget(paragraph, cell);
+    }
+}
+
+fn render_footer(area: Rect, frame: &mut Frame, app: &App, first: NaiveDate) {
+    let footer_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(20),
+            Constraint::Percentage(60),
+            Constraint::Percentage(20),
+        ])
+        .split(area);
+
+    let days: Vec<NaiveDate> = (0..31)
+        .map(|i| first + Duration::days(i))
+        .take_while(|d| d.month() == first.month() && *d <= app.today())
+        .collect();
+    let average = if days.is_empty() {
+        0.0
+    } else {
+        days.iter()
+            .map(|d| app.completion_rate_for_date(*d))
+            .sum::<f32>()
+            / days.len() as f32
+    };
+
+    let g = app.theme.glyphs;
+    let lines = vec![
+        ListItem::new(
+            Line::from(format!(
+                "{}: {:.0}% of habits done on an average day",
+                first.format("%B"),
+                average * 100.0
+            ))
+            .centered(),
+        ),
+        ListItem::new(
+            Line::from(g.plain(&format!(
+                "{} / {} month · {} open day · {} today",
+                app.keys_for(KeyMode::Month, Action::PrevMonth),
+                app.keys_for(KeyMode::Month, Action::NextMonth),
+                app.keys_for(KeyMode::Month, Action::OpenDay),
+                app.keys_for(KeyMode::Month, Action::GoToToday)
+            )))
+            .fg(app.theme.muted)
+            .centered(),
+        ),
+    ];
+    frame.render_widget(
+        List::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(g.border)
+                .border_style(app.theme.border),
+        ),
+        footer_chunks[1],
+    );
+}
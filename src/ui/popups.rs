@@ -1,21 +1,51 @@
-use crate::app::App;
-use crate::habit::HabitType;
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::app::{App, HabitField, HolidayField, SlipField};
+use crate::keymap::{Action, KeyMode};
+use crate::theme::Theme;
+use crate::habit::{today_with_cutoff, week_start, Habit, HabitType};
+use crate::notifications::{summarize, NotificationTrigger};
+use crate::palette;
+use crate::text_field::TextField;
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Position, Rect},
     style::{Color, Modifier, Style, Stylize},
     text::{Line, Span},
-    widgets::{Block, BorderType, Borders, Clear, List, ListItem, Padding, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Padding, Paragraph, Wrap},
     Frame,
 };
 
 use super::helpers::centered_rect;
 
-pub fn help_popup(frame: &mut Frame, area: Rect) {
-    let popup_area = centered_rect(area, 62, 78);
+pub fn help_popup(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+
+    // Generated from the active keymap so remapped keys show up here.
+    let g = theme.glyphs;
+    let mut sections: Vec<Vec<ListItem>> = Vec::new();
+    for (mode, entries) in app.keymap.sections() {
+        let mut items = vec![ListItem::new(Line::from(Span::styled(
+            mode.title(),
+            Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+        )))];
+        for (action, keys) in entries {
+            let keys = keys.iter().map(|k| k.label(g)).collect::<Vec<_>>().join(", ");
+            items.push(ListItem::new(Line::from(vec![
+                Span::styled(format!("  {keys:<16}"), Style::default().fg(theme.info)),
+                Span::raw(g.plain(action.description(mode))),
+            ])));
+        }
+        sections.push(items);
+    }
+
+    // Too long for one column: split into two at a section boundary.
+    let total: usize = sections.iter().map(Vec::len).sum();
+    let two_columns = total as u16 + 5 > area.height * 78 / 100;
+    let popup_area = centered_rect(area, if two_columns { 90 } else { 62 }, 78, 40, 15);
     let popup_block = Block::default()
         .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
-        .title("⌨  Keymaps")
+        .border_set(theme.glyphs.border)
+        .title(theme.glyphs.plain("⌨  Keymaps"))
         .padding(Padding::proportional(1));
     let inner_area = popup_block.inner(popup_area);
 
@@ -24,80 +54,491 @@ pub fn help_popup(frame: &mut Frame, area: Rect) {
         .constraints([Constraint::Min(1), Constraint::Length(1)])
         .split(inner_area);
 
-    let sections: [(&str, &[(&str, &str)]); 5] = [
-        (
-            "Global",
-            &[
-                ("q / Esc", "Quit (Esc closes a popup first)"),
-                ("Tab", "Switch view: Today → Stats → Heatmap"),
-                ("?", "Toggle this help"),
-            ],
-        ),
-        (
-            "Today",
-            &[
-                ("j/k, ↓/↑", "Move selection"),
-                ("h/l, ←/→", "Switch Build / Avoid column"),
-                ("Enter, Space", "Toggle habit for current day"),
-                ("y", "Edit yesterday instead of today"),
-                ("a", "Add habit"),
-                ("e", "Edit selected habit"),
-                ("d", "Delete selected habit"),
-                ("r", "Reset selected habit"),
-                ("H", "Mark holiday range for selected habit"),
-            ],
-        ),
-        (
-            "Add / Edit habit",
-            &[("Tab", "Switch Build / Avoid"), ("Enter", "Save"), ("Esc", "Cancel")],
-        ),
-        (
-            "Holiday form",
-            &[("Tab", "Switch Start / End field"), ("Enter", "Save"), ("Esc", "Cancel")],
-        ),
-        (
-            "Delete / Reset confirm",
-            &[("y", "Confirm"), ("n, Esc", "Cancel")],
-        ),
-    ];
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(popup_block, popup_area);
 
-    let mut items: Vec<ListItem> = Vec::new();
-    for (title, keys) in sections {
-        items.push(ListItem::new(Line::from(Span::styled(
-            title,
-            Style::default().fg(Color::LightYellow).add_modifier(Modifier::BOLD),
-        ))));
-        for (key, action) in keys {
-            items.push(ListItem::new(Line::from(vec![
-                Span::styled(format!("  {key:<14}"), Style::default().fg(Color::Cyan)),
-                Span::raw(*action),
-            ])));
+    if two_columns {
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(main_chunks[0]);
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+        for section in sections {
+            if left.is_empty() || left.len() + section.len() <= total.div_ceil(2) {
+                left.extend(section);
+            } else {
+                right.extend(section);
+            }
         }
+        render_help_column(frame, columns[0], left, app);
+        render_help_column(frame, columns[1], right, app);
+    } else {
+        render_help_column(frame, main_chunks[0], sections.concat(), app);
+    }
+    let mut footer = format!(
+        "{} or {} to close",
+        app.keys_for(KeyMode::Global, Action::Help),
+        app.keys_for(KeyMode::Global, Action::Back)
+    );
+    if app.hitboxes.borrow().help_hidden > 0 {
+        footer = format!(
+            "{} / {} scroll · {footer}",
+            app.keys_for(KeyMode::Popup, Action::ScrollUp),
+            app.keys_for(KeyMode::Popup, Action::ScrollDown)
+        );
     }
+    let footer = Paragraph::new(g.plain(&footer)).centered().fg(theme.accent);
+    frame.render_widget(footer, main_chunks[1]);
+}
+
+/// Draws one column of help from the current scroll position, noting how
+/// much is left below so scrolling can't run past the end.
+fn render_help_column(frame: &mut Frame, area: Rect, items: Vec<ListItem>, app: &App) {
+    let hidden = items.len().saturating_sub(area.height as usize);
+    let mut hitboxes = app.hitboxes.borrow_mut();
+    hitboxes.help_hidden = hitboxes.help_hidden.max(hidden);
+    let items: Vec<ListItem> = items.into_iter().skip(app.help_scroll.min(hidden)).collect();
+    frame.render_widget(List::new(items), area);
+}
+
+pub fn inbox_popup(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let popup_area = centered_rect(area, 70, 70, 40, 12);
+    let popup_block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(theme.glyphs.border)
+        .title(theme.glyphs.plain("📬 Notifications"))
+        .padding(Padding::proportional(1));
+    let inner_area = popup_block.inner(popup_area);
+
+    let main_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .split(inner_area);
+
+    let this_week = week_start(today_with_cutoff(app.day_cutoff_hour));
+    let summary = summarize(&app.notification_log, this_week).describe("this week");
+    let header = Paragraph::new(summary).fg(theme.accent).centered();
+
+    let g = theme.glyphs;
+    let items: Vec<ListItem> = if app.notification_log.is_empty() {
+        vec![ListItem::new(Line::from("No notifications sent yet").centered())]
+    } else {
+        app.notification_log
+            .iter()
+            .rev()
+            .map(|record| {
+                let trigger_color = match record.trigger {
+                    NotificationTrigger::Low => theme.warning,
+                    NotificationTrigger::High => theme.success,
+                    NotificationTrigger::Digest => theme.info,
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(
+                        format!("{}  ", record.sent_at.format("%a %Y-%m-%d %H:%M")),
+                        Style::default().fg(theme.info),
+                    ),
+                    Span::styled(
+                        format!("{:<5}", record.trigger.as_str()),
+                        Style::default().fg(trigger_color),
+                    ),
+                    Span::raw(format!("{}/{}  ", record.done, record.total)),
+                    Span::raw(if record.acted_on {
+                        format!("{} acted on  ", g.done)
+                    } else {
+                        format!("{} ignored   ", g.missed)
+                    }),
+                    Span::raw(g.plain(&record.text)),
+                    Span::styled(
+                        if record.errors.is_empty() {
+                            String::new()
+                        } else {
+                            g.plain("  ⚠ delivery failed")
+                        },
+                        Style::default().fg(theme.error),
+                    ),
+                ]))
+            })
+            .collect()
+    };
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(popup_block, popup_area);
+    frame.render_widget(header, main_chunks[0]);
+    frame.render_widget(List::new(items), main_chunks[1]);
+
+    let footer = Paragraph::new(format!(
+        "{} or {} to close",
+        app.keys_for(KeyMode::Global, Action::Inbox),
+        app.keys_for(KeyMode::Global, Action::Back)
+    ))
+    .centered()
+    .fg(theme.accent);
+    frame.render_widget(footer, main_chunks[2]);
+}
+
+pub fn digest_popup(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let Some(digest) = &app.digest else {
+        return;
+    };
+    let popup_area = centered_rect(area, 60, 70, 40, 14);
+    let popup_block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(theme.glyphs.border)
+        .title(theme.glyphs.plain(&format!("🪞 {}", digest.title())))
+        .padding(Padding::proportional(1));
+    let inner_area = popup_block.inner(popup_area);
+
+    let main_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner_area);
+
+    let items: Vec<ListItem> = digest
+        .lines()
+        .into_iter()
+        .map(|line| theme.glyphs.plain(&line))
+        .map(|line| {
+            if let Some(heading) = line.strip_prefix("## ") {
+                ListItem::new(Line::from(Span::styled(
+                    heading.to_string(),
+                    Style::default().fg(theme.accent).add_modifier(Modifier::BOLD),
+                )))
+            } else if let Some(quote) = line.strip_prefix("> ") {
+                ListItem::new(Line::from(quote.to_string()).fg(theme.info).centered())
+            } else {
+                ListItem::new(Line::from(line))
+            }
+        })
+        .collect();
 
     frame.render_widget(Clear, popup_area);
     frame.render_widget(popup_block, popup_area);
     frame.render_widget(List::new(items), main_chunks[0]);
 
-    let footer = Paragraph::new("? or Esc to close").centered().fg(Color::LightYellow);
+    let footer = Paragraph::new(format!(
+        "{} or {} to close",
+        app.keys_for(KeyMode::Popup, Action::Close),
+        app.keys_for(KeyMode::Global, Action::Back)
+    ))
+    .centered()
+    .fg(theme.accent);
+    frame.render_widget(footer, main_chunks[1]);
+}
+
+pub fn day_detail_popup(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let date = app.detail_date;
+    let popup_area = centered_rect(area, 50, 60, 44, 12);
+    let popup_block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(theme.glyphs.border)
+        .title(theme.glyphs.plain(&format!("📅 {}", date.format("%A, %B %-d %Y"))))
+        .padding(Padding::proportional(1));
+    let inner_area = popup_block.inner(popup_area);
+
+    let main_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(2),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .split(inner_area);
+
+    let total = app.habits_on(date).count();
+    let header = Paragraph::new(format!("{}/{} habits", app.count_completed_on(date), total))
+        .fg(theme.accent)
+        .centered();
+
+    let g = theme.glyphs;
+    let selected_row = app.selected_row();
+    let habits: Vec<&Habit> = app.all_habits().collect();
+    let items: Vec<ListItem> = app
+        .detail_rows()
+        .into_iter()
+        .map(|row| {
+            let habit = habits[row];
+            let (mark, color) = if habit.created > date {
+                (" ", theme.muted)
+            } else if habit.completed_on(date, app.day_cutoff_hour) {
+                (g.done, theme.success)
+            } else {
+                (g.missed, theme.error)
+            };
+            let mut note = String::new();
+            if habit.created > date {
+                note.push_str("  not tracked yet");
+            }
+            if habit.is_on_holiday(date) {
+                note.push_str(&format!("  {}", g.holiday));
+            }
+            match habit.slip_count_on(date) {
+                0 => {}
+                1 => note.push_str(&g.plain("  · 1 slip")),
+                n => note.push_str(&g.plain(&format!("  · {n} slips"))),
+            }
+            let selected = row == selected_row;
+            let marker = match (theme.cues, selected) {
+                (false, _) => "",
+                (true, true) => g.selected,
+                (true, false) => " ",
+            };
+            let line = Line::from(vec![
+                Span::raw(if marker.is_empty() { String::new() } else { format!("{marker} ") }),
+                Span::styled(format!("{mark} "), Style::default().fg(color)),
+                Span::raw(habit.name.clone()),
+                Span::styled(note, Style::default().fg(theme.muted)),
+            ]);
+            if selected {
+                let bg = match habit.habit_type {
+                    HabitType::Build => theme.build,
+                    HabitType::Avoid => theme.avoid,
+                };
+                ListItem::new(line).bg(bg).fg(theme.highlight_fg)
+            } else {
+                ListItem::new(line)
+            }
+        })
+        .collect();
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(popup_block, popup_area);
+    frame.render_widget(header, main_chunks[0]);
+    frame.render_widget(List::new(items), main_chunks[1]);
+    {
+        let mut hitboxes = app.hitboxes.borrow_mut();
+        hitboxes.popup = popup_area;
+        hitboxes.detail_list = main_chunks[1];
+    }
+
+    let footer = Paragraph::new(g.plain(&format!(
+        "{} toggle · {} undo slip · {} close",
+        app.keys_for(KeyMode::Day, Action::Activate),
+        app.keys_for(KeyMode::Day, Action::UndoSlip),
+        app.keys_for(KeyMode::Global, Action::Back)
+    )))
+    .centered()
+    .fg(theme.accent);
+    frame.render_widget(footer, main_chunks[2]);
+}
+
+pub fn archive_popup(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let g = theme.glyphs;
+    let popup_area = centered_rect(area, 50, 60, 44, 12);
+    let popup_block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .title(g.plain("🗄 Archived habits"))
+        .padding(Padding::proportional(1));
+    let inner_area = popup_block.inner(popup_area);
+
+    let main_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner_area);
+
+    let habits: Vec<&Habit> = app.all_habits().collect();
+    let rows = app.archived_rows();
+    let items: Vec<ListItem> = rows
+        .iter()
+        .enumerate()
+        .map(|(line, row)| {
+            let habit = habits[*row];
+            let archived = habit.archived.unwrap_or(habit.created);
+            // The tier it retired with, not one dragged down since.
+            let pattern = habit.pattern_on(archived.pred_opt().unwrap_or(archived));
+            let selected = line == app.archive_selected;
+            let marker = match (theme.cues, selected) {
+                (false, _) => "",
+                (true, true) => g.selected,
+                (true, false) => " ",
+            };
+            let kind = match habit.habit_type {
+                HabitType::Build => "build",
+                HabitType::Avoid => "avoid",
+            };
+            let text = Line::from(vec![
+                Span::raw(if marker.is_empty() { String::new() } else { format!("{marker} ") }),
+                Span::raw(habit.name.clone()),
+                Span::styled(
+                    g.plain(&format!(
+                        "  · {kind} · {} · since {}",
+                        theme.tier_label(pattern),
+                        archived.format("%b %-d %Y")
+                    )),
+                    Style::default().fg(theme.muted),
+                ),
+            ]);
+            if selected {
+                ListItem::new(text).bg(theme.selection).fg(theme.highlight_fg)
+            } else {
+                ListItem::new(text)
+            }
+        })
+        .collect();
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(popup_block, popup_area);
+    if items.is_empty() {
+        let empty = Paragraph::new(format!(
+            "Nothing archived. {} on a habit retires it and keeps its history.",
+            app.keys_for(KeyMode::Today, Action::Archive)
+        ))
+        .fg(theme.muted)
+        .centered()
+        .wrap(Wrap { trim: true });
+        frame.render_widget(empty, main_chunks[0]);
+    } else {
+        frame.render_widget(List::new(items), main_chunks[0]);
+    }
+    {
+        let mut hitboxes = app.hitboxes.borrow_mut();
+        hitboxes.popup = popup_area;
+        hitboxes.archive_list = main_chunks[0];
+    }
+
+    let footer = Paragraph::new(g.plain(&format!(
+        "{} restore · {} close",
+        app.keys_for(KeyMode::Archive, Action::Restore),
+        app.keys_for(KeyMode::Global, Action::Back)
+    )))
+    .centered()
+    .fg(theme.accent);
     frame.render_widget(footer, main_chunks[1]);
 }
 
+pub fn holidays_popup(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let g = theme.glyphs;
+    let popup_area = centered_rect(area, 50, 60, 44, 12);
+    let popup_block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .title(g.plain(&format!("🌴 Holidays — {}", app.current_habit.name)))
+        .padding(Padding::proportional(1));
+    let inner_area = popup_block.inner(popup_area);
+
+    let main_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1), Constraint::Length(1)])
+        .split(inner_area);
+
+    let today = app.today();
+    let items: Vec<ListItem> = app
+        .current_habit
+        .holidays
+        .iter()
+        .enumerate()
+        .map(|(line, holiday)| {
+            let selected = line == app.holiday_selected;
+            let marker = match (theme.cues, selected) {
+                (false, _) => "",
+                (true, true) => g.selected,
+                (true, false) => " ",
+            };
+            let days = (holiday.end - holiday.start).num_days() + 1;
+            let when = if holiday.end < today {
+                ""
+            } else if holiday.start <= today {
+                " · now"
+            } else {
+                " · upcoming"
+            };
+            let mut spans = vec![
+                Span::raw(if marker.is_empty() { String::new() } else { format!("{marker} ") }),
+                Span::raw(format!(
+                    "{} – {}",
+                    holiday.start.format("%b %-d %Y"),
+                    holiday.end.format("%b %-d %Y")
+                )),
+                Span::styled(
+                    format!("  · {days} day{}{when}", if days == 1 { "" } else { "s" }),
+                    Style::default().fg(theme.muted),
+                ),
+            ];
+            if let Some(reason) = &holiday.reason {
+                spans.push(Span::raw(format!("  {reason}")));
+            }
+            let item = ListItem::new(Line::from(spans));
+            if selected {
+                item.bg(theme.selection).fg(theme.highlight_fg)
+            } else if holiday.end < today {
+                item.fg(theme.muted)
+            } else {
+                item
+            }
+        })
+        .collect();
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(popup_block, popup_area);
+    if items.is_empty() {
+        let empty = Paragraph::new(format!(
+            "No holidays yet. {} adds one; missed days in it won't count against the habit.",
+            app.keys_for(KeyMode::Holidays, Action::Add)
+        ))
+        .fg(theme.muted)
+        .centered()
+        .wrap(Wrap { trim: true });
+        frame.render_widget(empty, main_chunks[0]);
+    } else {
+        frame.render_widget(List::new(items), main_chunks[0]);
+    }
+    {
+        let mut hitboxes = app.hitboxes.borrow_mut();
+        hitboxes.popup = popup_area;
+        hitboxes.holiday_list = main_chunks[0];
+    }
+
+    if let Some(since) = app.current_habit.paused {
+        let paused = Paragraph::new(format!(
+            "Paused since {}, which counts as a holiday too",
+            since.format("%b %-d %Y")
+        ))
+        .fg(theme.info)
+        .centered();
+        frame.render_widget(paused, main_chunks[1]);
+    }
+
+    let footer = Paragraph::new(g.plain(&format!(
+        "{} add · {} edit · {} delete · {} close",
+        app.keys_for(KeyMode::Holidays, Action::Add),
+        app.keys_for(KeyMode::Holidays, Action::Edit),
+        app.keys_for(KeyMode::Holidays, Action::Delete),
+        app.keys_for(KeyMode::Global, Action::Back)
+    )))
+    .centered()
+    .fg(theme.accent);
+    frame.render_widget(footer, main_chunks[2]);
+}
+
 pub fn habit_form_float(frame: &mut Frame, area: Rect, app: &App, title: &str) {
-    let popup_area = centered_rect(area, 50, 40);
+    let theme = &app.theme;
+    let popup_area = centered_rect(area, 50, 45, 40, 17);
     let popup_block = Block::default()
         .borders(Borders::ALL)
         .title(title)
-        .border_type(BorderType::Rounded)
+        .border_set(theme.glyphs.border)
         .padding(Padding::proportional(1));
     let inner_area = popup_block.inner(popup_area);
 
     let main_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
-            Constraint::Percentage(40),
-            Constraint::Percentage(10),
-            Constraint::Percentage(40),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(0),
+            Constraint::Length(1),
         ])
         .split(inner_area);
 
@@ -106,21 +547,37 @@ pub fn habit_form_float(frame: &mut Frame, area: Rect, app: &App, title: &str) {
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(main_chunks[2]);
 
-    let name_input = Paragraph::new(app.current_habit.name.as_str()).block(
+    let input = &app.habit_input;
+    let field_block = |title: &'static str, focused: bool| {
         Block::default()
             .borders(Borders::ALL)
-            .title("Name:")
-            .border_type(BorderType::Rounded),
-    );
-
-    let button_block = Block::new()
-        .borders(Borders::ALL)
-        .border_type(BorderType::Rounded);
-    let (build_tab, avoid_tab) = habit_type_tabs(&app.current_habit.habit_type);
+            .border_set(theme.glyphs.border)
+            .title(title)
+            .fg(if focused { theme.focus } else { Color::default() })
+    };
+    let time = app.current_habit.time_of_day.map_or("Any time", |t| t.as_str());
+    let time_input = Paragraph::new(time)
+        .block(field_block("When:", input.focus == HabitField::TimeOfDay));
+    let button_block = field_block("", input.focus == HabitField::Type);
+    let (build_tab, avoid_tab) = habit_type_tabs(&app.current_habit.habit_type, theme);
 
     frame.render_widget(Clear, popup_area);
     frame.render_widget(popup_block, popup_area);
-    frame.render_widget(name_input, main_chunks[0]);
+    text_input(
+        frame,
+        main_chunks[0],
+        &input.name,
+        field_block("Name:", input.focus == HabitField::Name),
+        input.focus == HabitField::Name,
+    );
+    text_input(
+        frame,
+        main_chunks[1],
+        &input.tags,
+        field_block("Tags (optional, e.g. health, morning):", input.focus == HabitField::Tags),
+        input.focus == HabitField::Tags,
+    );
+    frame.render_widget(time_input, main_chunks[3]);
 
     let inner_build_button = button_block.inner(button_chunks[0]);
     frame.render_widget(button_block.clone(), button_chunks[0]);
@@ -130,19 +587,27 @@ pub fn habit_form_float(frame: &mut Frame, area: Rect, app: &App, title: &str) {
     frame.render_widget(button_block.clone(), button_chunks[1]);
     frame.render_widget(avoid_tab, inner_avoid_button);
 
-    let x_offset = app.current_habit.name.len() as u16;
-    let input_area = main_chunks[0];
-    let position = Position::new(input_area.x + x_offset + 1, input_area.y + 1);
-    frame.set_cursor_position(position);
+    let hint = match input.focus {
+        HabitField::Type => format!("space switch type · {}", form_hint(app, "next field")),
+        HabitField::TimeOfDay => format!("space change time · {}", form_hint(app, "next field")),
+        _ => form_hint(app, "next field"),
+    };
+    let hint = match &input.error {
+        Some(error) => Paragraph::new(error.as_str()).fg(theme.error),
+        None => Paragraph::new(theme.glyphs.plain(&hint)),
+    };
+    frame.render_widget(hint.centered(), main_chunks[5]);
 }
 
 pub fn holiday_form_float(frame: &mut Frame, area: Rect, app: &App) {
-    let popup_area = centered_rect(area, 50, 45);
-    let title = format!("🌴 Holiday — {}", app.current_habit.name);
+    let theme = &app.theme;
+    let g = theme.glyphs;
+    let popup_area = centered_rect(area, 60, 60, 60, 20);
+    let title = g.plain(&format!("🌴 Holiday — {}", app.current_habit.name));
     let popup_block = Block::default()
         .borders(Borders::ALL)
         .title(title)
-        .border_type(BorderType::Rounded)
+        .border_set(g.border)
         .padding(Padding::proportional(1));
     let inner_area = popup_block.inner(popup_area);
 
@@ -151,56 +616,404 @@ pub fn holiday_form_float(frame: &mut Frame, area: Rect, app: &App) {
         .constraints([
             Constraint::Length(3),
             Constraint::Length(3),
-            Constraint::Min(1),
+            Constraint::Min(8),
+            Constraint::Length(1),
             Constraint::Length(1),
         ])
         .split(inner_area);
+    let field_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(main_chunks[0]);
+    let body_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Length(30), Constraint::Min(0)])
+        .split(main_chunks[2]);
 
-    let field_block = |title: &'static str, focused: bool| {
+    let input = &app.holiday_input;
+    let (start, end) = app.holiday_dates();
+    // What each field reads as, so `+7d` or `fri` can be checked before saving.
+    let field_block = |title: &'static str, field: &TextField, date: Option<NaiveDate>, focused| {
+        let resolved = match date {
+            _ if field.text().trim().is_empty() => Span::raw("same day"),
+            Some(date) => Span::raw(date.format("%a %b %-d %Y").to_string()),
+            None => Span::raw("?").fg(theme.error),
+        };
         Block::default()
             .borders(Borders::ALL)
-            .border_type(BorderType::Rounded)
+            .border_set(g.border)
             .title(title)
-            .fg(if focused { Color::Yellow } else { Color::default() })
+            .title_top(Line::from(resolved).right_aligned())
+            .fg(if focused { theme.focus } else { Color::default() })
+    };
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(popup_block, popup_area);
+    text_input(
+        frame,
+        field_chunks[0],
+        &input.start,
+        field_block("Start:", &input.start, start, input.focus == HolidayField::Start),
+        input.focus == HolidayField::Start,
+    );
+    text_input(
+        frame,
+        field_chunks[1],
+        &input.end,
+        field_block("End:", &input.end, end, input.focus == HolidayField::End),
+        input.focus == HolidayField::End,
+    );
+    let reason_focused = input.focus == HolidayField::Reason;
+    text_input(
+        frame,
+        main_chunks[1],
+        &input.reason,
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(g.border)
+            .title("Reason (optional):")
+            .fg(if reason_focused { theme.focus } else { Color::default() }),
+        reason_focused,
+    );
+    let range = start.zip(end).filter(|(start, end)| start <= end);
+    date_picker(frame, body_chunks[0], app, range, app.picked_date());
+
+    // The habit's other holidays, with any the new one runs into marked.
+    let others: Vec<_> = app
+        .current_habit
+        .holidays
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| Some(*i) != input.editing)
+        .map(|(_, holiday)| holiday)
+        .collect();
+    let overlaps: Vec<_> = others
+        .iter()
+        .filter(|h| range.is_some_and(|(start, end)| h.overlaps(start, end)))
+        .collect();
+    let mut lines = vec![Line::from("Other holidays").fg(theme.accent).bold()];
+    for holiday in &others {
+        let text = format!(
+            "{} – {}",
+            holiday.start.format("%b %-d %Y"),
+            holiday.end.format("%b %-d %Y")
+        );
+        if overlaps.contains(&holiday) {
+            lines.push(Line::from(g.plain(&format!("{text} ⚠"))).fg(theme.warning));
+        } else {
+            lines.push(Line::from(text));
+        }
+    }
+    if let Some(since) = app.current_habit.paused {
+        lines.push(Line::from(format!("Paused since {}", since.format("%b %-d %Y"))));
+    }
+    if lines.len() == 1 {
+        lines.push(Line::from("None yet").fg(theme.muted));
+    }
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().padding(Padding::horizontal(2))),
+        body_chunks[1],
+    );
+
+    let status = if let Some(error) = &input.error {
+        Paragraph::new(error.as_str()).fg(theme.error)
+    } else if start.is_some() && end.is_some() && range.is_none() {
+        Paragraph::new("The holiday ends before it starts").fg(theme.warning)
+    } else if let Some(first) = overlaps.first() {
+        Paragraph::new(format!(
+            "Overlaps {} – {}; saving merges them",
+            first.start.format("%b %-d"),
+            first.end.format("%b %-d")
+        ))
+        .fg(theme.warning)
+    } else {
+        Paragraph::new("Missed days in this range won't count against your pattern")
+            .fg(theme.accent)
     };
+    frame.render_widget(status.centered(), main_chunks[3]);
 
-    let start_input = Paragraph::new(app.holiday_input.start.as_str())
-        .block(field_block("Start (YYYY-MM-DD):", !app.holiday_input.focus_end));
-    let end_input = Paragraph::new(app.holiday_input.end.as_str())
-        .block(field_block("End (YYYY-MM-DD):", app.holiday_input.focus_end));
+    let hint = format!(
+        "{} {} day · {} {} week · {} {} month · {}",
+        app.keys_for(KeyMode::Picker, Action::PrevDay),
+        app.keys_for(KeyMode::Picker, Action::NextDay),
+        app.keys_for(KeyMode::Picker, Action::PrevWeek),
+        app.keys_for(KeyMode::Picker, Action::NextWeek),
+        app.keys_for(KeyMode::Picker, Action::PrevMonth),
+        app.keys_for(KeyMode::Picker, Action::NextMonth),
+        form_hint(app, "switch field")
+    );
+    frame.render_widget(Paragraph::new(g.plain(&hint)).centered(), main_chunks[4]);
+}
 
-    let hint = Paragraph::new("Missed days in this range won't count against your pattern")
-        .fg(Color::LightYellow)
-        .centered();
+/// A month calendar around `picked`, with the `range` being entered
+/// highlighted and the habit's existing holidays underlined.
+fn date_picker(
+    frame: &mut Frame,
+    area: Rect,
+    app: &App,
+    range: Option<(NaiveDate, NaiveDate)>,
+    picked: NaiveDate,
+) {
+    let theme = &app.theme;
+    let first = picked.with_day(1).unwrap();
+    let today = app.today();
+    let mut lines = vec![
+        Line::from(first.format("%B %Y").to_string()).bold().centered(),
+        Line::from("Mo  Tu  We  Th  Fr  Sa  Su").fg(theme.muted),
+    ];
+    let mut date = week_start(first);
+    while date.month() == first.month() || date < first {
+        let mut spans = Vec::new();
+        for _ in 0..7 {
+            if date.month() != first.month() {
+                spans.push(Span::raw("    "));
+            } else {
+                let mut style = Style::default();
+                if app.current_habit.is_on_holiday(date) {
+                    style = style.fg(theme.info).add_modifier(Modifier::UNDERLINED);
+                }
+                if date == today {
+                    style = style.fg(theme.accent).add_modifier(Modifier::BOLD);
+                }
+                if range.is_some_and(|(start, end)| date >= start && date <= end) {
+                    style = style.bg(theme.selection).fg(theme.highlight_fg);
+                }
+                if date == picked {
+                    style = style.add_modifier(Modifier::REVERSED | Modifier::BOLD);
+                }
+                spans.push(Span::styled(format!("{:>2}", date.day()), style));
+                spans.push(Span::raw("  "));
+            }
+            date += Duration::days(1);
+        }
+        lines.push(Line::from(spans));
+    }
+    frame.render_widget(Paragraph::new(lines), area);
+}
+
+/// Draws a text field in `block`, scrolled so the cursor stays in view,
+/// and puts the terminal cursor there when it has focus.
+fn text_input(frame: &mut Frame, area: Rect, field: &TextField, block: Block, focused: bool) {
+    let inner = block.inner(area);
+    let (shown, cursor) = field.view(inner.width.saturating_sub(1) as usize);
+    frame.render_widget(Paragraph::new(shown).block(block), area);
+    if focused {
+        frame.set_cursor_position(Position::new(inner.x + cursor, inner.y));
+    }
+}
+
+pub fn goto_form_float(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let popup_area = centered_rect(area, 40, 30, 36, 9);
+    let popup_block = Block::default()
+        .borders(Borders::ALL)
+        .title("Go to date")
+        .border_set(theme.glyphs.border)
+        .padding(Padding::proportional(1));
+    let inner_area = popup_block.inner(popup_area);
+
+    let main_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Length(3), Constraint::Min(0), Constraint::Length(1)])
+        .split(inner_area);
+
+    let date_block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(theme.glyphs.border)
+        .title("Date (YYYY-MM-DD, or e.g. -7d):")
+        .fg(theme.focus);
 
     frame.render_widget(Clear, popup_area);
     frame.render_widget(popup_block, popup_area);
-    frame.render_widget(start_input, main_chunks[0]);
-    frame.render_widget(end_input, main_chunks[1]);
-    frame.render_widget(hint, main_chunks[2]);
+    text_input(frame, main_chunks[0], &app.goto_input.date, date_block, true);
 
-    if let Some(error) = &app.holiday_input.error {
-        let error_msg = Paragraph::new(error.as_str()).fg(Color::Red).centered();
-        frame.render_widget(error_msg, main_chunks[3]);
-    } else {
-        let footer_hint = Paragraph::new("Tab switch field · Enter save").centered();
-        frame.render_widget(footer_hint, main_chunks[3]);
+    let footer = match &app.goto_input.error {
+        Some(error) => Paragraph::new(error.as_str()).fg(theme.error),
+        None => Paragraph::new(theme.glyphs.plain(&format!(
+            "{} go · {} cancel",
+            app.keys_for(KeyMode::Form, Action::Save),
+            app.keys_for(KeyMode::Form, Action::Cancel)
+        ))),
+    };
+    frame.render_widget(footer.centered(), main_chunks[2]);
+}
+
+/// A one-line input pinned to the bottom of the screen, as wide as the
+/// middle of the footer. Returns the inside of its border.
+fn bottom_bar(frame: &mut Frame, area: Rect, app: &App, title: String) -> Rect {
+    let width = (area.width * 3 / 5).max(area.width.min(40));
+    let bar = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.bottom().saturating_sub(3),
+        width,
+        3.min(area.height),
+    );
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(app.theme.glyphs.border)
+        .border_style(app.theme.focus)
+        .title(title);
+    let inner = block.inner(bar);
+    frame.render_widget(Clear, bar);
+    frame.render_widget(block, bar);
+    inner
+}
+
+/// A bar's prompt char and field, with `rest` after the text when it fits.
+/// Puts the terminal cursor in the field.
+fn bar_input(frame: &mut Frame, inner: Rect, prompt: char, field: &TextField, rest: Option<Span>) {
+    let (shown, cursor) = field.view(inner.width.saturating_sub(2) as usize);
+    let used = 1 + Span::raw(shown.as_str()).width() as u16;
+    let mut spans = vec![Span::raw(format!("{prompt}{shown}"))];
+    spans.extend(rest.filter(|rest| used + rest.width() as u16 <= inner.width));
+    frame.render_widget(Paragraph::new(Line::from(spans)), inner);
+    frame.set_cursor_position(Position::new(inner.x + 1 + cursor, inner.y));
+}
+
+pub fn search_bar(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let search = &app.search_input;
+    let status = match (search.query.text().trim().is_empty(), search.matches.len()) {
+        (true, _) => "type to search".to_string(),
+        (false, 0) => "no match".to_string(),
+        (false, count) => format!(
+            "{}/{count} · {} next",
+            search.current + 1,
+            app.keys_for(KeyMode::Form, Action::NextField)
+        ),
+    };
+    let inner = bottom_bar(frame, area, app, theme.glyphs.plain(&format!("🔍 Search habits · {status}")));
+
+    // The match is named here too, for screens that don't list habits.
+    let found = search
+        .matches
+        .get(search.current)
+        .and_then(|row| app.all_habits().nth(*row))
+        .map(|habit| {
+            Span::styled(
+                theme.glyphs.plain(&format!("  → {}", habit.name)),
+                Style::default().fg(theme.muted),
+            )
+        });
+    bar_input(frame, inner, '/', &search.query, found);
+}
+
+pub fn command_palette(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let input = &app.command_input;
+    let title = match &input.error {
+        Some(_) => "Command".to_string(),
+        None => format!(
+            "Command · {} complete · {} run",
+            app.keys_for(KeyMode::Form, Action::NextField),
+            app.keys_for(KeyMode::Form, Action::Save)
+        ),
+    };
+    let inner = bottom_bar(frame, area, app, theme.glyphs.plain(&title));
+
+    // Matching commands stack up above the bar, so every command is a
+    // keystroke away from being found.
+    let suggestions = palette::suggestions(input.text.text());
+    let height = (suggestions.len() as u16 + 2).min(area.height.saturating_sub(3));
+    if !suggestions.is_empty() && height > 2 {
+        let list_area = Rect::new(inner.x - 1, inner.y - 1 - height, inner.width + 2, height);
+        let items: Vec<ListItem> = suggestions
+            .iter()
+            .map(|(name, args, about)| {
+                ListItem::new(Line::from(vec![
+                    Span::styled(format!(" {:<38}", format!("{name} {args}")), Style::default().fg(theme.info)),
+                    Span::styled(format!("  {about}"), Style::default().fg(theme.muted)),
+                ]))
+            })
+            .collect();
+        frame.render_widget(Clear, list_area);
+        frame.render_widget(
+            List::new(items).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_set(theme.glyphs.border),
+            ),
+            list_area,
+        );
     }
 
-    let (field_area, text_len) = if app.holiday_input.focus_end {
-        (main_chunks[1], app.holiday_input.end.len())
-    } else {
-        (main_chunks[0], app.holiday_input.start.len())
+    let error = input
+        .error
+        .as_ref()
+        .map(|error| Span::styled(format!("  {error}"), Style::default().fg(theme.error)));
+    bar_input(frame, inner, ':', &input.text, error);
+}
+
+pub fn slip_form_float(frame: &mut Frame, area: Rect, app: &App) {
+    let theme = &app.theme;
+    let popup_area = centered_rect(area, 50, 55, 40, 16);
+    let date = app.acting_date();
+    let title = theme.glyphs.plain(&format!(
+        "🚫 Slip — {} · {}",
+        app.current_habit.name,
+        date.format("%a %b %-d")
+    ));
+    let popup_block = Block::default()
+        .borders(Borders::ALL)
+        .title(title)
+        .border_set(theme.glyphs.border)
+        .padding(Padding::proportional(1));
+    let inner_area = popup_block.inner(popup_area);
+
+    let main_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .split(inner_area);
+
+    let input = &app.slip_input;
+    let field_block = |title: &'static str, focused: bool| {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_set(theme.glyphs.border)
+            .title(title)
+            .fg(if focused { theme.focus } else { Color::default() })
     };
-    let position = Position::new(field_area.x + text_len as u16 + 1, field_area.y + 1);
-    frame.set_cursor_position(position);
+
+    let fields = [
+        (SlipField::Count, "How many times:", &input.count),
+        (SlipField::Time, "Time (HH:MM, optional):", &input.time),
+        (SlipField::Trigger, "Trigger (optional, e.g. stress):", &input.trigger),
+    ];
+
+    frame.render_widget(Clear, popup_area);
+    frame.render_widget(popup_block, popup_area);
+    for (i, (field, label, value)) in fields.into_iter().enumerate() {
+        let focused = input.focus == field;
+        text_input(frame, main_chunks[i], value, field_block(label, focused), focused);
+    }
+
+    let hint = Paragraph::new("Slips are data, not failures")
+        .fg(theme.accent)
+        .centered();
+    frame.render_widget(hint, main_chunks[3]);
+
+    if let Some(error) = &input.error {
+        let error_msg = Paragraph::new(error.as_str()).fg(theme.error).centered();
+        frame.render_widget(error_msg, main_chunks[4]);
+    } else {
+        let footer_hint =
+            Paragraph::new(theme.glyphs.plain(&form_hint(app, "next field"))).centered();
+        frame.render_widget(footer_hint, main_chunks[4]);
+    }
 }
 
 pub fn confirm_float(frame: &mut Frame, area: Rect, app: &App, message: &str) {
-    let popup_area = centered_rect(area, 35, 35);
+    let theme = &app.theme;
+    let popup_area = centered_rect(area, 35, 35, 30, 9);
     let popup_block = Block::default()
         .borders(Borders::ALL)
-        .border_type(BorderType::Rounded).padding(Padding::proportional(1));
+        .border_set(theme.glyphs.border).padding(Padding::proportional(1));
     let inner_area = popup_block.inner(popup_area);
 
     let main_chunks = Layout::default()
@@ -213,7 +1026,7 @@ pub fn confirm_float(frame: &mut Frame, area: Rect, app: &App, message: &str) {
         .split(inner_area);
 
     let msg = Paragraph::new(message)
-        .fg(Color::Red)
+        .fg(theme.error)
         .centered()
         .style(Style::default().bold());
     let habit_title = Paragraph::new(if app.counter.switch {
@@ -221,11 +1034,15 @@ pub fn confirm_float(frame: &mut Frame, area: Rect, app: &App, message: &str) {
     } else {
         app.build_habits[app.counter.build_counter].name.as_str()
     })
-    .fg(Color::White)
+    .fg(theme.text)
     .centered();
-    let choices = Paragraph::new("y/n")
+    let choices = Paragraph::new(format!(
+        "{} / {}",
+        app.keys_for(KeyMode::Confirm, Action::Confirm),
+        app.keys_for(KeyMode::Confirm, Action::Cancel)
+    ))
         .style(Style::default().bold())
-        .fg(Color::White)
+        .fg(theme.text)
         .centered();
 
     frame.render_widget(Clear, popup_area);
@@ -235,15 +1052,23 @@ pub fn confirm_float(frame: &mut Frame, area: Rect, app: &App, message: &str) {
     frame.render_widget(choices, main_chunks[2]);
 }
 
-fn habit_type_tabs(habit_type: &HabitType) -> (Paragraph<'static>, Paragraph<'static>) {
+fn form_hint(app: &App, next_field: &str) -> String {
+    format!(
+        "{} {next_field} · {} save",
+        app.keys_for(KeyMode::Form, Action::NextField),
+        app.keys_for(KeyMode::Form, Action::Save)
+    )
+}
+
+fn habit_type_tabs(habit_type: &HabitType, theme: &Theme) -> (Paragraph<'static>, Paragraph<'static>) {
     let (build_tab, avoid_tab) = match habit_type {
         HabitType::Build => (
-            Paragraph::new(Line::from("Build Habit").fg(Color::Black)),
+            Paragraph::new(Line::from("Build Habit").fg(theme.highlight_fg)),
             Paragraph::new("Avoid Habit"),
         ),
         HabitType::Avoid => (
             Paragraph::new("Build Habit"),
-            Paragraph::new(Line::from("Avoid Habit").fg(Color::Black)),
+            Paragraph::new(Line::from("Avoid Habit").fg(theme.highlight_fg)),
         ),
     };
 
@@ -251,7 +1076,7 @@ fn habit_type_tabs(habit_type: &HabitType) -> (Paragraph<'static>, Paragraph<'st
         build_tab
             .block(
                 Block::default().bg(if matches!(habit_type, HabitType::Build) {
-                    Color::Gray
+                    theme.selection
                 } else {
                     Color::default()
                 }),
@@ -260,7 +1085,7 @@ fn habit_type_tabs(habit_type: &HabitType) -> (Paragraph<'static>, Paragraph<'st
         avoid_tab
             .block(
                 Block::default().bg(if matches!(habit_type, HabitType::Avoid) {
-                    Color::Gray
+                    theme.selection
                 } else {
                     Color::default()
                 }),
@@ -1,13 +1,17 @@
 use std::rc::Rc;
 
 use crate::app::App;
-use crate::habit::{find_best_habit, find_worst_habit, Habit, HabitPattern};
+use crate::theme::Theme;
+use crate::habit::{
+    find_best_habit, find_worst_habit, group_pattern, slip_times, slip_triggers,
+    today_with_cutoff, Encouragement, Habit, HabitPattern,
+};
 use ratatui::style::Stylize;
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
-    style::{Color, Style},
+    style::Style,
     text::Line,
-    widgets::{Block, BorderType, Borders, Gauge, Paragraph},
+    widgets::{Block, Borders, Gauge, Paragraph},
     Frame,
 };
 
@@ -17,37 +21,69 @@ pub fn render_stats_page(body_chunks: Rc<[Rect]>, frame: &mut Frame, app: &App)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
         .split(body_chunks[0]);
 
-    let all_habits: Vec<Habit> = app
-        .build_habits
+    let all_habits: Vec<Habit> = app.shown_habits().cloned().collect();
+    let avoid_habits: Vec<Habit> = app
+        .avoid_habits
         .iter()
-        .chain(app.avoid_habits.iter())
+        .filter(|h| app.shows(h))
         .cloned()
         .collect();
 
-    render_pattern_health(stat_chunks[0], frame, &all_habits, app.day_cutoff_hour);
-    render_habit_spotlight(stat_chunks[1], frame, &all_habits, app.day_cutoff_hour);
-    render_encouragement(body_chunks[1], frame, &all_habits, app.day_cutoff_hour);
+    let theme = &app.theme;
+    let cutoff_hour = app.day_cutoff_hour;
+    let tags = app.tags();
+    if tags.is_empty() {
+        render_pattern_health(stat_chunks[0], frame, &all_habits, cutoff_hour, theme);
+    } else {
+        let left_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Min(7),
+                Constraint::Length(tags.len().min(MAX_TAG_ROWS) as u16 + 2),
+            ])
+            .split(stat_chunks[0]);
+        render_pattern_health(left_chunks[0], frame, &all_habits, cutoff_hour, theme);
+        render_tag_health(left_chunks[1], frame, app, &tags);
+    }
+    if avoid_habits.is_empty() {
+        render_habit_spotlight(stat_chunks[1], frame, &all_habits, cutoff_hour, theme);
+    } else {
+        let right_chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(5), Constraint::Min(1)])
+            .split(stat_chunks[1]);
+        render_habit_spotlight(right_chunks[0], frame, &all_habits, cutoff_hour, theme);
+        render_slip_patterns(right_chunks[1], frame, &avoid_habits, theme);
+    }
+    render_encouragement(body_chunks[1], frame, &all_habits, cutoff_hour, theme);
 }
 
-const TIERS: [(HabitPattern, &str, Color); 5] = [
-    (HabitPattern::Chaotic, "Chaotic", Color::Red),
-    (HabitPattern::Struggling, "Struggling", Color::Yellow),
-    (HabitPattern::Developing, "Developing", Color::Cyan),
-    (HabitPattern::Established, "Established", Color::LightGreen),
-    (HabitPattern::Mastered, "Mastered", Color::Green),
+/// In the same order as `Theme::tiers`.
+const TIERS: [HabitPattern; 5] = [
+    HabitPattern::Chaotic,
+    HabitPattern::Struggling,
+    HabitPattern::Developing,
+    HabitPattern::Established,
+    HabitPattern::Mastered,
 ];
 
-fn render_pattern_health(area: Rect, frame: &mut Frame, habits: &[Habit], cutoff_hour: u32) {
+fn render_pattern_health(
+    area: Rect,
+    frame: &mut Frame,
+    habits: &[Habit],
+    cutoff_hour: u32,
+    theme: &Theme,
+) {
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
-        .title("🌊 Pattern Health")
-        .fg(Color::LightYellow);
+        .border_set(theme.glyphs.border)
+        .title(theme.glyphs.plain("🌊 Pattern Health"))
+        .fg(theme.accent);
 
     if habits.is_empty() {
         let empty = Paragraph::new("Add a habit to start seeing your patterns")
             .centered()
-            .fg(Color::LightYellow)
+            .fg(theme.accent)
             .block(block);
         frame.render_widget(empty, area);
         return;
@@ -62,28 +98,86 @@ fn render_pattern_health(area: Rect, frame: &mut Frame, habits: &[Habit], cutoff
         .split(inner);
 
     let total = habits.len() as f32;
-    for (i, (pattern, label, color)) in TIERS.iter().enumerate() {
+    for (i, pattern) in TIERS.iter().enumerate() {
         let count = habits.iter().filter(|h| h.check_pattern(cutoff_hour) == *pattern).count();
         let pct = (count as f32 / total * 100.0).round() as u16;
-        let gauge = Gauge::default()
-            .gauge_style(Style::default().fg(*color))
-            .percent(pct)
-            .label(format!("{label} · {count} ({pct}%)"));
-        frame.render_widget(gauge, rows[i]);
+        let label = theme.glyphs.plain(&format!(
+            "{} · {count} ({pct}%)",
+            theme.tier_label(*pattern)
+        ));
+        if theme.glyphs.ascii {
+            // Gauge fills with block glyphs, so draw the bar as text instead.
+            let width = rows[i].width.saturating_sub(label.len() as u16 + 2);
+            let filled = (width as f32 * pct as f32 / 100.0).round() as usize;
+            let bar = format!("{label}  {}", "#".repeat(filled));
+            frame.render_widget(Paragraph::new(bar).fg(theme.tiers[i]), rows[i]);
+        } else {
+            let gauge = Gauge::default()
+                .gauge_style(Style::default().fg(theme.tiers[i]))
+                .percent(pct)
+                .label(label);
+            frame.render_widget(gauge, rows[i]);
+        }
     }
 }
 
-fn render_habit_spotlight(area: Rect, frame: &mut Frame, habits: &[Habit], cutoff_hour: u32) {
+/// Tags listed before the panel stops growing.
+const MAX_TAG_ROWS: usize = 8;
+
+/// One line per tag: how many habits carry it and their pooled tier.
+fn render_tag_health(area: Rect, frame: &mut Frame, app: &App, tags: &[String]) {
+    let theme = &app.theme;
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(theme.glyphs.border)
+        .title(theme.glyphs.plain("🏷 By Tag"))
+        .fg(theme.accent);
+    let as_of = today_with_cutoff(app.day_cutoff_hour);
+    let lines: Vec<Line> = tags
+        .iter()
+        .map(|tag| {
+            let habits: Vec<&Habit> = app
+                .all_habits()
+                .filter(|h| h.archived.is_none() && h.has_tag(tag))
+                .collect();
+            let pattern = group_pattern(&habits, as_of);
+            let count = match habits.len() {
+                1 => "1 habit".to_string(),
+                n => format!("{n} habits"),
+            };
+            let line = Line::from(theme.glyphs.plain(&format!(
+                "#{tag} · {count} · {}",
+                theme.tier_label(pattern)
+            )))
+            .fg(theme.tiers[pattern as usize]);
+            let selected = app.tag_filter.as_ref().is_some_and(|t| t.eq_ignore_ascii_case(tag));
+            if selected {
+                line.bold()
+            } else {
+                line
+            }
+        })
+        .collect();
+    frame.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+fn render_habit_spotlight(
+    area: Rect,
+    frame: &mut Frame,
+    habits: &[Habit],
+    cutoff_hour: u32,
+    theme: &Theme,
+) {
     let block = Block::default()
         .borders(Borders::ALL)
-        .border_type(BorderType::Rounded)
-        .title("✨ Habit Spotlight")
-        .fg(Color::LightYellow);
+        .border_set(theme.glyphs.border)
+        .title(theme.glyphs.plain("✨ Habit Spotlight"))
+        .fg(theme.accent);
 
     if habits.is_empty() {
         let empty = Paragraph::new("No habits tracked yet")
             .centered()
-            .fg(Color::LightYellow)
+            .fg(theme.accent)
             .block(block);
         frame.render_widget(empty, area);
         return;
@@ -96,39 +190,89 @@ fn render_habit_spotlight(area: Rect, frame: &mut Frame, habits: &[Habit], cutof
     let thriving = find_best_habit(habits, cutoff_hour).unwrap();
     let growing_edge = find_worst_habit(habits, cutoff_hour).unwrap();
 
+    let plain = |text: String| theme.glyphs.plain(&text);
     let lines = [
-        Line::from(format!("🌊  New: {}", newest.name)).fg(Color::White),
-        Line::from(format!("🌟  Thriving: {}", thriving.name)).fg(Color::LightGreen),
-        Line::from(format!("🌱  Growing edge: {}", growing_edge.name)).fg(Color::Cyan),
+        Line::from(plain(format!("🌊  New: {}", newest.name))).fg(theme.text),
+        Line::from(plain(format!("🌟  Thriving: {}", thriving.name))).fg(theme.tiers[3]),
+        Line::from(plain(format!("🌱  Growing edge: {}", growing_edge.name))).fg(theme.tiers[2]),
     ];
 
     frame.render_widget(Paragraph::new(lines.to_vec()), inner);
 }
 
-fn render_encouragement(area: Rect, frame: &mut Frame, habits: &[Habit], cutoff_hour: u32) {
+fn render_slip_patterns(area: Rect, frame: &mut Frame, avoid_habits: &[Habit], theme: &Theme) {
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(theme.glyphs.border)
+        .title(theme.glyphs.plain("🚫 Slip Patterns"))
+        .fg(theme.accent);
+
+    let triggers = slip_triggers(avoid_habits);
+    let times = slip_times(avoid_habits);
+    if triggers.is_empty() && times.iter().all(|(_, n)| *n == 0) {
+        let empty = Paragraph::new("No slips with a time or trigger logged yet")
+            .centered()
+            .fg(theme.accent)
+            .block(block);
+        frame.render_widget(empty, area);
+        return;
+    }
+
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+        .split(inner);
+
+    let mut trigger_lines = vec![Line::from("Top triggers").fg(theme.info)];
+    trigger_lines.extend(
+        triggers
+            .iter()
+            .take(5)
+            .map(|(trigger, n)| Line::from(theme.glyphs.plain(&format!("{trigger} · {n}"))).fg(theme.text)),
+    );
+    if triggers.is_empty() {
+        trigger_lines.push(Line::from("none noted").fg(theme.muted));
+    }
+
+    let mut time_lines = vec![Line::from("Time of day").fg(theme.info)];
+    time_lines.extend(
+        times
+            .iter()
+            .map(|(period, n)| Line::from(theme.glyphs.plain(&format!("{} · {n}", period.as_str()))).fg(theme.text)),
+    );
+
+    frame.render_widget(Paragraph::new(trigger_lines), columns[0]);
+    frame.render_widget(Paragraph::new(time_lines), columns[1]);
+}
+
+fn render_encouragement(
+    area: Rect,
+    frame: &mut Frame,
+    habits: &[Habit],
+    cutoff_hour: u32,
+    theme: &Theme,
+) {
     let (message, color) = if habits.is_empty() {
-        ("Add a habit whenever you're ready — no rush", Color::LightYellow)
+        ("Add a habit whenever you're ready — no rush", theme.accent)
     } else {
-        let strong = habits
-            .iter()
-            .filter(|h| matches!(h.check_pattern(cutoff_hour), HabitPattern::Established | HabitPattern::Mastered))
-            .count();
-        let ratio = strong as f32 / habits.len() as f32;
-        if ratio >= 0.6 {
-            ("Strong patterns forming — keep flowing 🌊", Color::Green)
-        } else if ratio >= 0.3 {
-            ("Progress isn't a straight line — you're doing fine", Color::Cyan)
-        } else {
-            ("Patterns take time to form — show up when you can, no pressure", Color::Yellow)
-        }
+        let encouragement = Encouragement::for_habits(habits, today_with_cutoff(cutoff_hour));
+        let color = match encouragement {
+            Encouragement::Strong => theme.tiers[4],
+            Encouragement::Steady => theme.tiers[2],
+            Encouragement::Gentle => theme.tiers[1],
+        };
+        (encouragement.message(), color)
     };
 
-    let hint = Paragraph::new(Line::from(message).centered())
+    let hint = Paragraph::new(Line::from(theme.glyphs.plain(message)).centered())
         .fg(color)
         .block(
             Block::default()
                 .borders(Borders::ALL)
-                .border_type(BorderType::Rounded),
+                .border_set(theme.glyphs.border),
         );
     frame.render_widget(hint, area);
 }
@@ -1,10 +1,15 @@
 use std::rc::Rc;
 
-use crate::app::App;
-use crate::habit::{Day, Habit};
-use ratatui::widgets::{BorderType, Paragraph};
+use chrono::NaiveDate;
+
+use crate::app::{App, ListRow, SortMode};
+use crate::habit::HabitType;
+use crate::keymap::{Action, KeyMode};
+
+use super::helpers::{is_compact, render_scrollbar};
+use ratatui::widgets::Paragraph;
 use ratatui::{
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Stylize},
     text::Line,
     widgets::{Block, Borders, List, ListItem},
@@ -12,76 +17,175 @@ use ratatui::{
 };
 
 pub fn render_today_page(body_chunks: Rc<[Rect]>, frame: &mut Frame, app: &App) {
-    let habit_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(body_chunks[0]);
+    // Narrow panes show only the focused list, full width; the focus keys
+    // switch between them.
+    let compact = is_compact(body_chunks[0].width);
+    let habit_chunks = if compact {
+        let none = Rect::new(body_chunks[0].x, body_chunks[0].y, 0, 0);
+        if app.counter.switch {
+            [none, body_chunks[0]]
+        } else {
+            [body_chunks[0], none]
+        }
+    } else {
+        let halves = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+            .split(body_chunks[0]);
+        [halves[0], halves[1]]
+    };
 
-    let build_habit_list = render_habit_list(
-        &app.build_habits,
-        &app.current_day,
-        app.counter.build_counter,
-        !app.counter.switch,
-        "🌟 Build These Habits",
-        Color::Green,
-        app.day_cutoff_hour,
-    );
-    frame.render_widget(build_habit_list, habit_chunks[0]);
+    // Your own order goes unremarked; any other sort is named in the titles.
+    let sort = match app.sort_mode {
+        SortMode::Manual => String::new(),
+        mode => format!(" · sort: {}", mode.label()),
+    };
+    let other = if compact {
+        let (key, label) = if app.counter.switch {
+            (Action::FocusBuild, "build")
+        } else {
+            (Action::FocusAvoid, "avoid")
+        };
+        format!(" · {} {label}", app.keys_for(KeyMode::Today, key))
+    } else {
+        String::new()
+    };
+    let lists = [
+        (
+            HabitType::Build,
+            app.counter.build_counter,
+            !app.counter.switch,
+            app.theme.glyphs.plain(&format!("🌟 Build These Habits{sort}{other}")),
+            app.theme.build,
+            habit_chunks[0],
+        ),
+        (
+            HabitType::Avoid,
+            app.counter.avoid_counter,
+            app.counter.switch,
+            app.theme.glyphs.plain(&format!("🚫 Avoid These Habits{sort}{other}")),
+            app.theme.avoid,
+            habit_chunks[1],
+        ),
+    ];
+    for (habit_type, selected, is_active, title, color, area) in lists {
+        if area.is_empty() {
+            continue;
+        }
+        let (list, selected_line, len) =
+            render_habit_list(habit_type, app.current_date, selected, is_active, title, color, app);
+        let mut states = app.list_states.borrow_mut();
+        let state = match habit_type {
+            HabitType::Build => &mut states.build,
+            HabitType::Avoid => &mut states.avoid,
+        };
+        // Scrolls only as far as it takes to keep the selection in view.
+        state.select(selected_line);
+        frame.render_stateful_widget(list, area, state);
+        render_scrollbar(frame, area, len, state.offset(), &app.theme);
 
-    let avoid_habit_list = render_habit_list(
-        &app.avoid_habits,
-        &app.current_day,
-        app.counter.avoid_counter,
-        app.counter.switch,
-        "🚫 Avoid These Habits",
-        Color::Red,
-        app.day_cutoff_hour,
-    );
-    frame.render_widget(avoid_habit_list, habit_chunks[1]);
+        let inner = area.inner(Margin::new(1, 1));
+        let mut hitboxes = app.hitboxes.borrow_mut();
+        match habit_type {
+            HabitType::Build => hitboxes.build_list = inner,
+            HabitType::Avoid => hitboxes.avoid_list = inner,
+        }
+        if is_active {
+            hitboxes.page_rows = inner.height as usize;
+        }
+    }
 
     render_footer(body_chunks[1], frame, app);
 }
 
-fn render_habit_list<'a>(
-    habits: &[Habit],
-    current_day: &Day,
+fn render_habit_list(
+    habit_type: HabitType,
+    date: NaiveDate,
     selected_index: usize,
     is_active: bool,
-    title: &'a str,
+    title: String,
     color: Color,
-    cutoff_hour: u32,
-) -> List<'a> {
-    let items: Vec<ListItem> = habits
+    app: &App,
+) -> (List<'static>, Option<usize>, usize) {
+    let cutoff_hour = app.day_cutoff_hour;
+    let g = app.theme.glyphs;
+    let habits = match habit_type {
+        HabitType::Build => &app.build_habits,
+        HabitType::Avoid => &app.avoid_habits,
+    };
+    // Numbered as listed, which under grouping isn't storage order.
+    let mut number = 0;
+    let rows = app.list_rows(habit_type);
+    let selected_line = rows
         .iter()
-        .enumerate()
-        .map(|(idx, habit)| {
-            let holiday_tag = if habit.is_on_holiday(current_day.resolve_date(cutoff_hour)) {
-                " 🌴"
+        .position(|row| matches!(row, ListRow::Habit(i) if *i == selected_index));
+    let len = rows.len();
+    let items: Vec<ListItem> = rows
+        .into_iter()
+        .map(|row| {
+            let idx = match row {
+                ListRow::Group(name, count) => return group_heading(&name, count, app),
+                ListRow::Habit(idx) => idx,
+            };
+            let habit = &habits[idx];
+            number += 1;
+            let holiday_tag = if habit.paused.is_some_and(|since| since <= date) {
+                format!(" {}", g.paused)
+            } else if habit.is_on_holiday(date) {
+                format!(" {}", g.holiday)
             } else {
-                ""
+                String::new()
+            };
+            let slip_tag = match habit.slip_count_on(date) {
+                0 => String::new(),
+                1 => " · 1 slip".to_string(),
+                n => format!(" · {n} slips"),
             };
+            let selected = idx == selected_index && is_active;
+            // With cues on the selected row is marked by shape as well as color.
+            let marker = match (app.theme.cues, selected) {
+                (false, _) => "",
+                (true, true) => g.selected,
+                (true, false) => " ",
+            };
+            // The first tag is already the group heading.
+            let extra_tags: String = habit.tags.iter().skip(1).map(|t| format!(" #{t}")).collect();
             let text = format!(
-                "{} [{}] {}{}  •  {}",
-                habit.check_status(current_day, cutoff_hour),
-                idx + 1,
+                "{}{}{} [{}] {}{}{}{}  {}  {}",
+                marker,
+                if marker.is_empty() { "" } else { " " },
+                g.status(&habit.check_status(date, cutoff_hour)),
+                number,
                 habit.name,
+                extra_tags,
                 holiday_tag,
-                habit.check_pattern(cutoff_hour)
+                g.plain(&slip_tag),
+                g.plain("•"),
+                app.theme.tier_label(habit.check_pattern(cutoff_hour))
             );
-            if idx == selected_index && is_active {
-                ListItem::new(text).bg(color).fg(Color::Black)
+            if selected {
+                ListItem::new(text).bg(color).fg(app.theme.highlight_fg)
             } else {
                 ListItem::new(text)
             }
         })
         .collect();
 
-    List::new(items).block(
+    let list = List::new(items).block(
         Block::default()
             .borders(Borders::ALL)
-            .border_type(BorderType::Rounded)
+            .border_set(app.theme.glyphs.border)
+            .border_style(app.theme.border)
             .title(title),
-    )
+    );
+    (list, selected_line, len)
+}
+
+/// "▾ health (3)", or "▸" once folded.
+fn group_heading(name: &str, count: usize, app: &App) -> ListItem<'static> {
+    let g = app.theme.glyphs;
+    let arrow = if app.is_collapsed(name) { g.folded } else { g.unfolded };
+    ListItem::new(Line::from(format!("{arrow} {name} ({count})")).fg(app.theme.muted).bold())
 }
 
 fn render_footer(area: Rect, frame: &mut Frame, app: &App) {
@@ -93,28 +197,45 @@ fn render_footer(area: Rect, frame: &mut Frame, app: &App) {
             Constraint::Percentage(25),
         ])
         .split(area);
-
-    let day_name = app.current_day.as_str();
+    // Narrow panes keep the progress, full width, and drop the help hint.
+    let compact = is_compact(area.width);
+    let day = if compact {
+        app.current_date.format("%b %-d").to_string()
+    } else {
+        app.theme.glyphs.plain(&app.current_date_label())
+    };
 
     let stat_lines = vec![
         ListItem::new(
             Line::from(format!(
                 "{}: {}",
-                day_name,
-                app.check_todays_progress(&app.current_day)
+                day,
+                app.check_todays_progress(app.current_date)
             ))
             .centered(),
         ),
         ListItem::new(Line::from(format!("Week: {}", app.check_weeks_progress())).centered()),
     ];
+    let g = app.theme.glyphs;
+    // The file that failed to load; the title line says what's wrong.
+    let broken = app
+        .config_problem()
+        .map(|problem| problem.split(':').next().unwrap_or_default().to_string());
+    let mut block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(g.border)
+        .border_style(app.theme.border);
+    if let (true, Some(file)) = (compact, &broken) {
+        let marker = Line::from(g.plain(&format!("⚠ {file}"))).fg(app.theme.error);
+        block = block.title_top(marker.right_aligned());
+    }
     frame.render_widget(
-        List::new(stat_lines).block(
-            Block::default()
-                .borders(Borders::ALL)
-                .border_type(BorderType::Rounded),
-        ),
-        inner_chunks[1],
+        List::new(stat_lines).block(block),
+        if compact { area } else { inner_chunks[1] },
     );
+    if compact {
+        return;
+    }
     let inner_inner_chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
@@ -124,10 +245,16 @@ fn render_footer(area: Rect, frame: &mut Frame, app: &App) {
         ])
         .split(inner_chunks[2]);
 
+    let hint = match broken {
+        Some(file) => Line::from(g.plain(&format!("⚠ invalid {file}"))).fg(app.theme.error),
+        None => Line::from(g.plain(&format!(
+            "{} • toggle help",
+            app.keys_for(KeyMode::Global, Action::Help)
+        )))
+        .fg(app.theme.muted),
+    };
     frame.render_widget(
-        Paragraph::new(Line::from("? • toggle help").fg(Color::Gray))
-            .centered()
-            .block(Block::default()),
+        Paragraph::new(hint).centered().block(Block::default()),
         inner_inner_chunks[1],
     );
 }
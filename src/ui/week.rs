@@ -0,0 +1,313 @@
+use std::rc::Rc;
+
+use chrono::NaiveDate;
+use ratatui::{
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, Paragraph},
+    Frame,
+};
+
+use crate::app::{App, ListRow};
+use crate::habit::{Habit, HabitType};
+use crate::keymap::{Action, KeyMode};
+
+const CELL_W: u16 = 7;
+const TOTAL_W: u16 = 7;
+const MIN_NAME_W: u16 = 8;
+/// Narrow panes shrink the day columns down to this, which still fits a
+/// selected "[✘2]", and the total column to "7/7".
+const MIN_CELL_W: u16 = 4;
+const NARROW_TOTAL_W: u16 = 5;
+
+/// A line of the grid: a section heading, a tag group's heading, or a
+/// habit with its row index.
+enum GridLine<'a> {
+    Heading(&'static str),
+    Group(String, usize),
+    Habit(usize, &'a Habit),
+}
+
+pub fn render_week_page(chunks: Rc<[Rect]>, frame: &mut Frame, app: &App) {
+    let dates = app.week_dates();
+    let title = app.theme.glyphs.plain(&format!(
+        "📅 Week of {}",
+        dates[0].format("%a, %b %-d %Y")
+    ));
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .border_set(app.theme.glyphs.border)
+        .border_style(app.theme.border)
+        .title(title);
+    let inner = block.inner(chunks[0]);
+    frame.render_widget(block, chunks[0]);
+    render_grid(inner, frame, app, &dates);
+
+    render_footer(chunks[1], frame, app);
+}
+
+fn render_grid(area: Rect, frame: &mut Frame, app: &App, dates: &[NaiveDate; 7]) {
+    if area.height < 2 {
+        return;
+    }
+    let (cell_w, total_w) = column_widths(area.width);
+    // Names get what they need, up to whatever the day columns leave over;
+    // the grid is centered in the rest.
+    let longest = app
+        .all_habits()
+        .filter(|h| h.archived.is_none())
+        .map(|h| Span::raw(h.name.as_str()).width() as u16 + 4)
+        .max()
+        .unwrap_or(0);
+    let room = area.width.saturating_sub(cell_w * 7 + total_w);
+    let name_w = longest.min(room).max(MIN_NAME_W.min(room));
+    let grid_w = (name_w + cell_w * 7 + total_w).min(area.width);
+    let area = Rect::new(
+        area.x + (area.width - grid_w) / 2,
+        area.y,
+        grid_w,
+        area.height,
+    );
+
+    let mut header = vec![Span::raw(pad_right("Habit", name_w))];
+    for date in dates {
+        let label = if cell_w == CELL_W {
+            date.format("%a %-d").to_string()
+        } else {
+            date.format("%a").to_string().chars().take(2).collect()
+        };
+        let style = if *date == app.current_date {
+            Style::new().fg(app.theme.accent).add_modifier(Modifier::BOLD)
+        } else if *date > app.today() {
+            Style::new().fg(app.theme.muted)
+        } else {
+            Style::new()
+        };
+        header.push(Span::styled(center(&label, cell_w), style));
+    }
+    let total = if total_w >= TOTAL_W { "Total" } else { "Tot" };
+    header.push(Span::raw(center(total, total_w)));
+    frame.render_widget(
+        Paragraph::new(Line::from(header).bold()),
+        Rect::new(area.x, area.y, area.width, 1),
+    );
+
+    let mut lines = Vec::new();
+    let sections = [
+        ("Build", HabitType::Build, &app.build_habits, 0),
+        ("Avoid", HabitType::Avoid, &app.avoid_habits, app.build_habits.len()),
+    ];
+    for (heading, habit_type, habits, first_row) in sections {
+        let rows = app.list_rows(habit_type);
+        if rows.is_empty() {
+            continue;
+        }
+        lines.push(GridLine::Heading(heading));
+        for row in rows {
+            lines.push(match row {
+                ListRow::Group(name, count) => GridLine::Group(name, count),
+                ListRow::Habit(i) => GridLine::Habit(first_row + i, &habits[i]),
+            });
+        }
+    }
+
+    // Scroll just far enough to keep the selected row on screen.
+    let height = (area.height - 1) as usize;
+    let selected = app.selected_row();
+    let selected_line = lines
+        .iter()
+        .position(|l| matches!(l, GridLine::Habit(row, _) if *row == selected))
+        .unwrap_or(0);
+    let offset = (selected_line + 1).saturating_sub(height);
+
+    let mut hitboxes = app.hitboxes.borrow_mut();
+    hitboxes.page_rows = height;
+    for (i, line) in lines.iter().skip(offset).take(height).enumerate() {
+        let y = area.y + 1 + i as u16;
+        let line_area = Rect::new(area.x, y, area.width, 1);
+        match line {
+            GridLine::Heading(heading) => {
+                frame.render_widget(
+                    Paragraph::new(Line::from(*heading).fg(app.theme.muted).italic()),
+                    line_area,
+                );
+            }
+            GridLine::Group(name, count) => {
+                let g = app.theme.glyphs;
+                let arrow = if app.is_collapsed(name) { g.folded } else { g.unfolded };
+                frame.render_widget(
+                    Paragraph::new(Line::from(format!("{arrow} {name} ({count})")).fg(app.theme.muted)),
+                    line_area,
+                );
+            }
+            GridLine::Habit(row, habit) => {
+                let is_selected = *row == selected;
+                let color = match habit.habit_type {
+                    HabitType::Build => app.theme.build,
+                    HabitType::Avoid => app.theme.avoid,
+                };
+                let marker = match (app.theme.cues, is_selected) {
+                    (true, true) => format!("{} ", app.theme.glyphs.selected),
+                    (true, false) => "  ".to_string(),
+                    (false, _) => String::new(),
+                };
+                let name = pad_right(&format!("{marker}{}", habit.name), name_w);
+                let name_style = if is_selected {
+                    Style::new().fg(color).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::new()
+                };
+                let mut spans = vec![Span::styled(name, name_style)];
+
+                for (col, date) in dates.iter().enumerate() {
+                    let (text, fg) = cell(habit, *date, app);
+                    let cursor = is_selected && *date == app.current_date;
+                    let text = if cursor && app.theme.cues {
+                        format!("[{text}]")
+                    } else {
+                        text
+                    };
+                    let style = if cursor {
+                        Style::new().bg(color).fg(app.theme.highlight_fg)
+                    } else {
+                        Style::new().fg(fg)
+                    };
+                    spans.push(Span::styled(center(&text, cell_w), style));
+                    hitboxes.week_cells.push((
+                        Rect::new(area.x + name_w + col as u16 * cell_w, y, cell_w, 1),
+                        *row,
+                        *date,
+                    ));
+                }
+                let (done, due) = habit.tally(dates[0], dates[6].min(app.today()));
+                spans.push(Span::raw(center(&format!("{done}/{due}"), total_w)));
+                frame.render_widget(Paragraph::new(Line::from(spans)), line_area);
+            }
+        }
+    }
+
+    if lines.is_empty() {
+        let message = match &app.tag_filter {
+            Some(tag) => format!(
+                "No habits tagged #{tag}. {} shows the next tag.",
+                app.keys_for(KeyMode::Global, Action::NextTag)
+            ),
+            None => format!(
+                "No habits yet. Add one from the Today tab with {}.",
+                app.keys_for(KeyMode::Today, Action::Add)
+            ),
+        };
+        frame.render_widget(
+            Paragraph::new(message)
+            .fg(app.theme.muted),
+            Rect::new(area.x, area.y + 1, area.width, 1),
+        );
+    }
+}
+
+/// Day and total column widths for a grid `width` columns wide: full size
+/// when a name column fits beside them, else as narrow as they can go.
+fn column_widths(width: u16) -> (u16, u16) {
+    if width >= MIN_NAME_W + CELL_W * 7 + TOTAL_W {
+        return (CELL_W, TOTAL_W);
+    }
+    let cell_w = (width.saturating_sub(MIN_NAME_W + NARROW_TOTAL_W) / 7).clamp(MIN_CELL_W, CELL_W);
+    (cell_w, NARROW_TOTAL_W)
+}
+
+/// What one habit/day cell shows, and its color.
+fn cell(habit: &Habit, date: NaiveDate, app: &App) -> (String, Color) {
+    let g = app.theme.glyphs;
+    if date > app.today() {
+        (g.plain("·"), app.theme.muted)
+    } else if date < habit.created {
+        (String::new(), app.theme.muted)
+    } else if habit.completed_on(date, app.day_cutoff_hour) {
+        (g.done.to_string(), app.theme.success)
+    } else if habit.is_on_holiday(date) {
+        (g.holiday_cell.to_string(), app.theme.info)
+    } else {
+        match habit.slip_count_on(date) {
+            n if n > 1 => (format!("{}{n}", g.missed), app.theme.error),
+            _ => (g.missed.to_string(), app.theme.error),
+        }
+    }
+}
+
+fn render_footer(area: Rect, frame: &mut Frame, app: &App) {
+    let footer_chunks = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([
+            Constraint::Percentage(20),
+            Constraint::Percentage(60),
+            Constraint::Percentage(20),
+        ])
+        .split(area);
+
+    let g = app.theme.glyphs;
+    let lines = vec![
+        ListItem::new(Line::from(format!("Week: {}", app.check_weeks_progress())).centered()),
+        ListItem::new(
+            Line::from(g.plain(&format!(
+                "{} / {} day · {} / {} week · {} toggle · {} today",
+                app.keys_for(KeyMode::Week, Action::PrevDay),
+                app.keys_for(KeyMode::Week, Action::NextDay),
+                app.keys_for(KeyMode::Week, Action::PrevWeek),
+                app.keys_for(KeyMode::Week, Action::NextWeek),
+                app.keys_for(KeyMode::Week, Action::Activate),
+                app.keys_for(KeyMode::Week, Action::GoToToday)
+            )))
+            .fg(app.theme.muted)
+            .centered(),
+        ),
+    ];
+    frame.render_widget(
+        List::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .border_set(g.border)
+                .border_style(app.theme.border),
+        ),
+        footer_chunks[1],
+    );
+}
+
+/// Pads or truncates `text` to exactly `width` columns, left-aligned.
+fn pad_right(text: &str, width: u16) -> String {
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = Span::raw(c.to_string()).width();
+        if used + w >= width as usize {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out + &" ".repeat(width as usize - used)
+}
+
+/// Centers `text` in `width` columns, measuring by display width so
+/// emoji line up with single-column glyphs.
+fn center(text: &str, width: u16) -> String {
+    let w = Span::raw(text).width().min(width as usize);
+    let left = (width as usize - w) / 2;
+    format!("{}{text}{}", " ".repeat(left), " ".repeat(width as usize - w - left))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_fits_down_to_the_minimum_width() {
+        assert_eq!(column_widths(64), (CELL_W, TOTAL_W));
+        assert_eq!(column_widths(63), (CELL_W, NARROW_TOTAL_W));
+        // The Week block's inside at the smallest size drawn.
+        for width in 38..64 {
+            let (cell_w, total_w) = column_widths(width);
+            assert!(cell_w * 7 + total_w + 4 <= width, "{width} columns");
+        }
+    }
+}